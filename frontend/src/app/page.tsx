@@ -36,7 +36,8 @@ export default function Home() {
   }, []);
   
   // Queue data from contract
-  const sellQueueLength = dexData ? dexData.sellQueueTail - dexData.sellQueueHead : 0;
+  // Head and tail are order ids (0 = empty); ids only grow, so this is an upper bound once orders are cancelled
+  const sellQueueLength = dexData && dexData.sellQueueHead !== 0 ? dexData.sellQueueTail - dexData.sellQueueHead + 1 : 0;
   
   // Treasury state management
   const { 
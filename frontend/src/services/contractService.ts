@@ -1,5 +1,5 @@
 import { Program, AnchorProvider, BN } from '@coral-xyz/anchor';
-import { PublicKey, Connection, Transaction } from '@solana/web3.js';
+import { PublicKey, Connection, Transaction, TransactionInstruction, SystemProgram } from '@solana/web3.js';
 import type { VersionedTransaction } from '@solana/web3.js';
 import { WalletContextState } from '@solana/wallet-adapter-react';
 import IDL from '../everrise_dex.json';
@@ -7,8 +7,20 @@ import IDL from '../everrise_dex.json';
 // Contract configuration
 export const PROGRAM_ID = new PublicKey(process.env.NEXT_PUBLIC_PROGRAM_ID || '9tXMAMrSrdkQ6ojkU87TRn3w13joZioz6iuab44ywwpy');
 export const BONDING_CURVE_SEED = process.env.NEXT_PUBLIC_BONDING_CURVE_SEED || 'bonding_curve';
+// Each market is the bonding curve of its base mint
+export const EVER_MINT = new PublicKey(process.env.NEXT_PUBLIC_EVER_MINT || '3q4YFYMKHrdYw5FPANQ7nrCQMT4t12XKgzYX8JaTeEx8');
 export const TREASURY_WALLET = new PublicKey(process.env.NEXT_PUBLIC_TREASURY_WALLET || 'DTA5uQocoAaZwXL59DoVZwWUxJCsxjfBCM6mzpws8T4');
 
+// Trade protection - the program rejects fills below these bounds or after the deadline
+export const DEFAULT_SLIPPAGE_BPS = Number(process.env.NEXT_PUBLIC_SLIPPAGE_BPS || 500); // 5%
+export const DEFAULT_DEADLINE_SECONDS = 120;
+const BASIS_POINTS = 10_000;
+
+// Affiliate program layout - referral levels walked for upline commissions, and the
+// sighash of "global:register_referral"
+const MAX_UPLINE_LEVELS = 3;
+const REGISTER_REFERRAL_DISCRIMINATOR = Buffer.from([158, 196, 134, 102, 193, 102, 184, 86]);
+
 export interface BondingCurveData {
   authority: PublicKey;
   treasuryWallet: PublicKey;
@@ -16,21 +28,33 @@ export interface BondingCurveData {
   y: number; // EVER in reserve
   k: string; // K constant (u128)
   currentPrice: number;
-  sellQueueHead: number;
-  sellQueueTail: number;
+  sellQueueHead: number; // Order id at the head of the sell queue (0 = empty)
+  sellQueueTail: number; // Order id at the tail of the sell queue (0 = empty)
   buyQueueHead: number;
   buyQueueTail: number;
+  nextOrderId: number; // Id the next sell or buy order gets - seeds its PDA
   cumulativeBonus: number;
   lastPriceUpdate: number;
   dailyBoostApplied: boolean;
   circulatingSupply: number;
   lastDailyBoost: number;
   totalVolume24h: number;
+  commissionBps: number; // Market commission when the buyer has no referral
+  affiliateProgram: PublicKey; // PublicKey.default when the market has none
 }
 
 function getBondingCurvePDAKey(): PublicKey {
   const [pda] = PublicKey.findProgramAddressSync(
-    [Buffer.from(BONDING_CURVE_SEED)],
+    [Buffer.from(BONDING_CURVE_SEED), EVER_MINT.toBuffer()],
+    PROGRAM_ID
+  );
+  return pda;
+}
+
+// Order PDAs are seeded by the curve and the order id (u64, little-endian)
+function getOrderPDAKey(kind: 'sell_order' | 'buy_order', bondingCurvePDA: PublicKey, orderId: number): PublicKey {
+  const [pda] = PublicKey.findProgramAddressSync(
+    [Buffer.from(kind), bondingCurvePDA.toBuffer(), new BN(orderId).toArrayLike(Buffer, 'le', 8)],
     PROGRAM_ID
   );
   return pda;
@@ -90,12 +114,15 @@ export async function fetchBondingCurveDataWithProgram(
         sellQueueTail: { toString(): string };
         buyQueueHead: { toString(): string };
         buyQueueTail: { toString(): string };
+        nextOrderId: { toString(): string };
         cumulativeBonus: { toString(): string };
         lastPriceUpdate: { toString(): string };
         dailyBoostApplied: boolean;
         circulatingSupply: { toString(): string };
         lastDailyBoost: { toString(): string };
         totalVolume24h: { toString(): string };
+        config: { commissionBps: number };
+        affiliateProgram: PublicKey;
       };
       return {
         authority: dataTyped.authority,
@@ -108,12 +135,15 @@ export async function fetchBondingCurveDataWithProgram(
         sellQueueTail: dataTyped.sellQueueTail ? parseInt(dataTyped.sellQueueTail.toString(), 10) : 0,
         buyQueueHead: dataTyped.buyQueueHead ? parseInt(dataTyped.buyQueueHead.toString(), 10) : 0,
         buyQueueTail: dataTyped.buyQueueTail ? parseInt(dataTyped.buyQueueTail.toString(), 10) : 0,
+        nextOrderId: dataTyped.nextOrderId ? parseInt(dataTyped.nextOrderId.toString(), 10) : 1,
         cumulativeBonus: dataTyped.cumulativeBonus ? parseInt(dataTyped.cumulativeBonus.toString(), 10) : 0,
         lastPriceUpdate: dataTyped.lastPriceUpdate ? parseInt(dataTyped.lastPriceUpdate.toString(), 10) : 0,
         dailyBoostApplied: dataTyped.dailyBoostApplied || false,
         circulatingSupply: dataTyped.circulatingSupply ? parseInt(dataTyped.circulatingSupply.toString(), 10) : 0,
         lastDailyBoost: dataTyped.lastDailyBoost ? parseInt(dataTyped.lastDailyBoost.toString(), 10) : 0,
         totalVolume24h: dataTyped.totalVolume24h ? parseInt(dataTyped.totalVolume24h.toString(), 10) : 0,
+        commissionBps: dataTyped.config?.commissionBps || 0,
+        affiliateProgram: dataTyped.affiliateProgram || PublicKey.default,
       };
     } catch (fetchError) {
       console.error('Could not fetch bonding curve data:', fetchError);
@@ -143,12 +173,15 @@ export async function fetchBondingCurveDataWithProgram(
         sellQueueTail: 0,
         buyQueueHead: 0,
         buyQueueTail: 0,
+        nextOrderId: 1,
         cumulativeBonus: 0,
         lastPriceUpdate: Math.floor(Date.now() / 1000),
         dailyBoostApplied: false,
         circulatingSupply: 0,
         lastDailyBoost: Math.floor(Date.now() / 1000),
         totalVolume24h: 0,
+        commissionBps: 500,
+        affiliateProgram: PublicKey.default,
       };
     }
   } catch (error) {
@@ -216,20 +249,48 @@ export class ContractService {
     return (x * 1_000_000_000) / (y * 1_000_000);
   }
 
-  // Get buy order PDA (uses bonding curve's buy_queue_tail)
-  getBuyOrderPDA(bondingCurvePDA: PublicKey, queueTail: number): PublicKey {
-    // Convert number to little-endian bytes (8 bytes for u64) to match to_le_bytes()
-    const buffer = Buffer.alloc(8);
-    buffer.writeUInt32LE(queueTail, 0);
-    buffer.writeUInt32LE(0, 4); // High 32 bits are 0 for small numbers
-    
-    const [pda] = PublicKey.findProgramAddressSync(
-      [Buffer.from('buy_order'), buffer],
-      PROGRAM_ID
-    );
+  // Get buy order PDA for an order id
+  getBuyOrderPDA(bondingCurvePDA: PublicKey, orderId: number): PublicKey {
+    return getOrderPDAKey('buy_order', bondingCurvePDA, orderId);
+  }
+
+  // Get an affiliate program PDA - the referral records and registries are keyed by wallet
+  getAffiliatePDA(affiliateProgram: PublicKey, seed: string, wallet?: PublicKey): PublicKey {
+    const seeds = wallet ? [Buffer.from(seed), wallet.toBuffer()] : [Buffer.from(seed)];
+    const [pda] = PublicKey.findProgramAddressSync(seeds, affiliateProgram);
     return pda;
   }
 
+  // Walk the Referral records up from `wallet`, stopping at the first wallet nobody referred
+  async getReferralChain(affiliateProgram: PublicKey, wallet: PublicKey): Promise<{ referral: PublicKey; upline: PublicKey | null }[]> {
+    const chain: { referral: PublicKey; upline: PublicKey | null }[] = [];
+    for (let level = 0; level < MAX_UPLINE_LEVELS; level++) {
+      const referral = this.getAffiliatePDA(affiliateProgram, 'referral', wallet);
+      const accountInfo = await this.connection.getAccountInfo(referral);
+      // Referral data: 8-byte discriminator, then the referrer
+      const upline = accountInfo?.owner.equals(affiliateProgram) ? new PublicKey(accountInfo.data.subarray(8, 40)) : null;
+      chain.push({ referral, upline });
+      if (!upline) break;
+      wallet = upline;
+    }
+    return chain;
+  }
+
+  // Minimum EVER for a buy - the reserve quote after the market commission, less the slippage tolerance
+  quoteMinEverOut(bondingCurveData: BondingCurveData, usdcAmount: BN, slippageBps: number = DEFAULT_SLIPPAGE_BPS): BN {
+    const k = new BN(bondingCurveData.k);
+    const x = new BN(bondingCurveData.x.toString());
+    if (x.isZero()) return new BN(0);
+    const reserveUsdc = usdcAmount.muln(BASIS_POINTS - bondingCurveData.commissionBps).divn(BASIS_POINTS);
+    const quote = k.div(x).sub(k.div(x.add(reserveUsdc)));
+    return quote.muln(BASIS_POINTS - slippageBps).divn(BASIS_POINTS);
+  }
+
+  // Deadline for a trade submitted now
+  getDeadline(): BN {
+    return new BN(Math.floor(Date.now() / 1000) + DEFAULT_DEADLINE_SECONDS);
+  }
+
   // Get user's USDC token account
   async getUserUsdcAccount(): Promise<PublicKey> {
     const { getAssociatedTokenAddress } = await import('@solana/spl-token');
@@ -240,7 +301,6 @@ export class ContractService {
   // Get user's EVER token account
   async getUserEverAccount(): Promise<PublicKey> {
     const { getAssociatedTokenAddress } = await import('@solana/spl-token');
-    const EVER_MINT = new PublicKey(process.env.NEXT_PUBLIC_EVER_MINT || '3q4YFYMKHrdYw5FPANQ7nrCQMT4t12XKgzYX8JaTeEx8');
     return getAssociatedTokenAddress(EVER_MINT, this.wallet.publicKey!);
   }
 
@@ -410,15 +470,13 @@ export class ContractService {
       let sellerUsdcAccount = new PublicKey('11111111111111111111111111111111'); // SystemProgram.programId
 
       // If there are sell orders, get the first one
-      if (bondingCurveData.sellQueueHead < bondingCurveData.sellQueueTail) {
+      if (bondingCurveData.sellQueueHead !== 0) {
         console.log('🔍 Processing sell orders - head:', bondingCurveData.sellQueueHead, 'tail:', bondingCurveData.sellQueueTail);
-        const firstSellOrderPosition = bondingCurveData.sellQueueHead;
-        const pdaSeed = firstSellOrderPosition + 1; // PDA was created with (position + 1)
-        sellOrderPDA = this.getSellOrderPDA(bondingCurvePDA, pdaSeed);
+        sellOrderPDA = this.getSellOrderPDA(bondingCurvePDA, bondingCurveData.sellQueueHead);
         
         // Fetch the sell order data to get the seller's address
         try {
-          const sellOrderData = await this.getSellOrderData(pdaSeed);
+          const sellOrderData = await this.getSellOrderData(bondingCurveData.sellQueueHead);
           if (sellOrderData) {
             console.log('🔍 Sell order data:', sellOrderData);
             const sellOrderTyped = sellOrderData as { seller: string };
@@ -442,65 +500,84 @@ export class ContractService {
         console.log('🔍 No sell orders to process - using dummy accounts');
       }
 
-      // Get referrer's USDC account if referrer is provided
-      console.log('🔍 buyTokens called with referrer:', referrer);
-      let referrerUsdcAccount = null;
-      if (referrer) {
-        try {
-          const { getAssociatedTokenAddress } = await import('@solana/spl-token');
-          const usdcMint = new PublicKey(process.env.NEXT_PUBLIC_USDC_MINT || 'EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v');
-          const referrerUsdcAddress = await getAssociatedTokenAddress(usdcMint, new PublicKey(referrer));
-          
-          // Check if the account exists
-          const accountInfo = await this.connection.getAccountInfo(referrerUsdcAddress);
-          if (accountInfo) {
-            referrerUsdcAccount = referrerUsdcAddress;
-            console.log('Referrer USDC account exists:', referrerUsdcAddress.toString());
-          } else {
-            console.warn('Referrer USDC account does not exist, using treasury instead');
-            referrerUsdcAccount = null;
-          }
-        } catch (error) {
-          console.warn('Could not get referrer USDC account:', error);
-          referrerUsdcAccount = null;
-        }
-      }
-
       // Ensure user's token accounts exist before proceeding
       const USDC_MINT = new PublicKey(process.env.NEXT_PUBLIC_USDC_MINT || 'EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v');
-      const EVER_MINT = new PublicKey(process.env.NEXT_PUBLIC_EVER_MINT || '3q4YFYMKHrdYw5FPANQ7nrCQMT4t12XKgzYX8JaTeEx8');
       
       console.log('🔍 Ensuring token accounts exist...');
-      const userUsdcAccount = await this.ensureTokenAccountExists(USDC_MINT, this.wallet.publicKey!);
-      const userEverAccount = await this.ensureTokenAccountExists(EVER_MINT, this.wallet.publicKey!);
+      const userPubkey = this.wallet.publicKey!;
+      const userUsdcAccount = await this.ensureTokenAccountExists(USDC_MINT, userPubkey);
+      const userEverAccount = await this.ensureTokenAccountExists(EVER_MINT, userPubkey);
 
+      // Referral commissions go through the market's affiliate program and only to the referrer
+      // recorded for the buyer there. A buyer without one pays the market commission to the
+      // treasury, unless a referral code registers them in this transaction.
+      const affiliateProgram = bondingCurveData.affiliateProgram;
+      const hasAffiliateProgram = !affiliateProgram.equals(PublicKey.default);
+      const [buyerReferral] = await this.getReferralChain(affiliateProgram, userPubkey);
+      let referrerPubkey = hasAffiliateProgram && buyerReferral.upline ? buyerReferral.upline : userPubkey;
+      const preInstructions: TransactionInstruction[] = [];
+      if (hasAffiliateProgram && !buyerReferral.upline && referrer && referrer !== userPubkey.toString()) {
+        referrerPubkey = new PublicKey(referrer);
+        const referrerChain = await this.getReferralChain(affiliateProgram, referrerPubkey);
+        preInstructions.push(new TransactionInstruction({
+          programId: affiliateProgram,
+          keys: [
+            { pubkey: this.getAffiliatePDA(affiliateProgram, 'affiliate_program'), isSigner: false, isWritable: true },
+            { pubkey: buyerReferral.referral, isSigner: false, isWritable: true },
+            { pubkey: this.getAffiliatePDA(affiliateProgram, 'referral_registry', referrerPubkey), isSigner: false, isWritable: true },
+            { pubkey: referrerPubkey, isSigner: false, isWritable: false },
+            { pubkey: userPubkey, isSigner: true, isWritable: true },
+            { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+            ...referrerChain.map(({ referral }) => ({ pubkey: referral, isSigner: false, isWritable: false })),
+          ],
+          data: Buffer.concat([REGISTER_REFERRAL_DISCRIMINATOR, referrerPubkey.toBuffer()]),
+        }));
+      }
+      const referred = !referrerPubkey.equals(userPubkey);
+      // Upline walk for process_commission: each level's Referral, then its referrer's registry
+      const uplineWalk = referred
+        ? (await this.getReferralChain(affiliateProgram, referrerPubkey)).flatMap(({ referral, upline }) => [
+            { pubkey: referral, isSigner: false, isWritable: false },
+            ...(upline ? [{ pubkey: this.getAffiliatePDA(affiliateProgram, 'referral_registry', upline), isSigner: false, isWritable: true }] : []),
+          ])
+        : [];
+
+      // The market signs commissions with its own PDA, which the affiliate program must allow-list
+      const [affiliateAuthority] = PublicKey.findProgramAddressSync([Buffer.from('affiliate_authority'), bondingCurvePDA.toBuffer()], PROGRAM_ID);
+      const [volumeStats] = PublicKey.findProgramAddressSync([Buffer.from('volume_stats'), bondingCurvePDA.toBuffer()], PROGRAM_ID);
       const accounts = {
         bondingCurve: bondingCurvePDA,
-        user: this.wallet.publicKey!,
+        user: userPubkey,
         userUsdcAccount: userUsdcAccount,
         userEverAccount: userEverAccount,
         treasuryUsdcAccount: await this.getTreasuryUsdcAccount(),
         programEverAccount: await this.getProgramEverAccount(),
         sellOrder: sellOrderPDA,
         sellerUsdcAccount: sellerUsdcAccount,
-        referrer: referrer ? new PublicKey(referrer) : new PublicKey('11111111111111111111111111111111'), // Use dummy account if no referrer
-        referrerUsdcAccount: referrerUsdcAccount || await this.getTreasuryUsdcAccount(), // Use treasury account if no referrer
+        referral: buyerReferral.referral,
+        referrer: referrerPubkey,
+        commissionVault: this.getAffiliatePDA(affiliateProgram, 'commission_vault'),
+        referralRegistry: this.getAffiliatePDA(affiliateProgram, 'referral_registry', referrerPubkey),
+        affiliateState: this.getAffiliatePDA(affiliateProgram, 'affiliate_program'),
+        affiliateProgram: affiliateProgram,
+        affiliateAuthority: affiliateAuthority,
+        volumeStats: volumeStats,
         tokenProgram: new PublicKey('TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA'),
       };
 
       console.log('🔍 Accounts being passed to instruction:', Object.keys(accounts));
-      console.log('🔍 referrerUsdcAccount:', referrerUsdcAccount?.toString() || 'null');
-      console.log('🔍 referrer:', referrer || 'null');
+      console.log('🔍 referrer:', referred ? referrerPubkey.toString() : 'null');
       console.log('🔍 sellerUsdcAccount:', sellerUsdcAccount.toString());
       console.log('🔍 sellOrderPDA:', sellOrderPDA.toString());
 
+      const minEverOut = this.quoteMinEverOut(bondingCurveData, amount);
       const instruction = await this.program.methods
-        .buySmart(amount)
+        .buySmart(amount, minEverOut, this.getDeadline(), uplineWalk.length)
         .accounts(accounts)
+        .remainingAccounts(uplineWalk)
         .instruction();
 
-      const { Transaction } = await import('@solana/web3.js');
-      const transaction = new Transaction().add(instruction);
+      const transaction = new Transaction().add(...preInstructions, instruction);
       
       // Debug: Log instruction keys to see their writable status
       console.log('🔍 Instruction keys:');
@@ -541,7 +618,7 @@ export class ContractService {
           console.log('🔍 Updated sell queue - head:', updatedBondingCurveData.sellQueueHead, 'tail:', updatedBondingCurveData.sellQueueTail);
           
           // Check if sell orders were processed
-          if (updatedBondingCurveData.sellQueueHead > bondingCurveData.sellQueueHead) {
+          if (updatedBondingCurveData.sellQueueHead !== bondingCurveData.sellQueueHead) {
             console.log('🔍 Sell orders were processed! Head moved from', bondingCurveData.sellQueueHead, 'to', updatedBondingCurveData.sellQueueHead);
           } else {
             console.log('🔍 No sell orders were processed - head unchanged at', updatedBondingCurveData.sellQueueHead);
@@ -694,25 +771,9 @@ export class ContractService {
     }
   }
 
-  // Get sell order PDA (uses the provided seed directly)
-  getSellOrderPDA(bondingCurvePDA: PublicKey, pdaSeed: number): PublicKey {
-    // Convert number to little-endian bytes (8 bytes for u64) to match to_le_bytes()
-    const buffer = Buffer.alloc(8);
-    
-    // Manual little-endian encoding for u64 (8 bytes)
-    let value = pdaSeed;
-    for (let i = 0; i < 8; i++) {
-      buffer[i] = value & 0xff;
-      value = Math.floor(value / 256);
-    }
-    
-    console.log(`Generating sell order PDA with seed: ${pdaSeed}, buffer: ${Array.from(buffer).map(b => b.toString(16).padStart(2, '0')).join('')}`);
-    
-    const [pda] = PublicKey.findProgramAddressSync(
-      [Buffer.from('sell_order'), buffer],
-      PROGRAM_ID
-    );
-    return pda;
+  // Get sell order PDA for an order id
+  getSellOrderPDA(bondingCurvePDA: PublicKey, orderId: number): PublicKey {
+    return getOrderPDAKey('sell_order', bondingCurvePDA, orderId);
   }
 
   // Sell EVER tokens
@@ -734,31 +795,34 @@ export class ContractService {
       console.log('  Buy Queue Head:', bondingCurveData.buyQueueHead);
       console.log('  Buy Queue Tail:', bondingCurveData.buyQueueTail);
       
-      // Use sell_queue_tail + 1 as the seed (matches smart contract)
-      const sellOrderPDA = this.getSellOrderPDA(bondingCurvePDA, bondingCurveData.sellQueueTail + 1);
+      // The new order takes next_order_id and links from the current tail order, if any
+      const sellOrderPDA = this.getSellOrderPDA(bondingCurvePDA, bondingCurveData.nextOrderId);
+      const tailSellOrderPDA = bondingCurveData.sellQueueTail !== 0 ? this.getSellOrderPDA(bondingCurvePDA, bondingCurveData.sellQueueTail) : null;
       
       console.log('🔍 Sell Order PDA Debug:');
       console.log('  Bonding Curve PDA:', bondingCurvePDA.toString());
       console.log('  Sell Queue Tail:', bondingCurveData.sellQueueTail);
-      console.log('  Sell Order Seed (tail + 1):', bondingCurveData.sellQueueTail + 1);
+      console.log('  Sell Order Id:', bondingCurveData.nextOrderId);
       console.log('  Generated Sell Order PDA:', sellOrderPDA.toString());
 
       // Ensure user's EVER account exists before proceeding
-      const EVER_MINT = new PublicKey(process.env.NEXT_PUBLIC_EVER_MINT || '3q4YFYMKHrdYw5FPANQ7nrCQMT4t12XKgzYX8JaTeEx8');
       console.log('🔍 Ensuring EVER token account exists...');
       const userEverAccount = await this.ensureTokenAccountExists(EVER_MINT, this.wallet.publicKey!);
       const programEverAccount = await this.getProgramEverAccount();
 
+      // The order locks the current price - reject it if the price dropped past the slippage tolerance
+      const minLockedPrice = new BN(bondingCurveData.currentPrice).muln(BASIS_POINTS - DEFAULT_SLIPPAGE_BPS).divn(BASIS_POINTS);
       const instruction = await this.program.methods
-        .sell(everAmountBN)
+        .sell(everAmountBN, minLockedPrice, this.getDeadline())
         .accounts({
           bondingCurve: bondingCurvePDA,
           sellOrder: sellOrderPDA,
+          tailSellOrder: tailSellOrderPDA,
           user: userPubkey,
           userEverAccount: userEverAccount,
           programEverAccount: programEverAccount,
           tokenProgram: new PublicKey('TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA'),
-          systemProgram: PublicKey.default,
+          systemProgram: SystemProgram.programId,
         })
         .instruction();
 
@@ -833,18 +897,18 @@ export class ContractService {
     return signature;
   }
 
-  // Get sell order data by position
-  async getSellOrderData(queuePosition: number): Promise<unknown | null> {
+  // Get sell order data by order id
+  async getSellOrderData(orderId: number): Promise<unknown | null> {
     try {
       const bondingCurvePDA = this.getBondingCurvePDA();
-      const sellOrderPDA = this.getSellOrderPDA(bondingCurvePDA, queuePosition);
+      const sellOrderPDA = this.getSellOrderPDA(bondingCurvePDA, orderId);
       
-      console.log(`Fetching sell order at position ${queuePosition}:`, sellOrderPDA.toString());
+      console.log(`Fetching sell order ${orderId}:`, sellOrderPDA.toString());
       
       const sellOrderData = await (this.program.account as unknown as { sellOrder: { fetch: (pda: PublicKey) => Promise<unknown> } }).sellOrder.fetch(sellOrderPDA);
       return sellOrderData;
     } catch (error) {
-      console.error(`Error fetching sell order at position ${queuePosition}:`, error);
+      console.error(`Error fetching sell order ${orderId}:`, error);
       return null;
     }
   }
@@ -858,16 +922,13 @@ export class ContractService {
       console.log('🔍 getAllSellOrders - Queue status:', {
         head: bondingCurveData.sellQueueHead,
         tail: bondingCurveData.sellQueueTail,
-        length: bondingCurveData.sellQueueTail - bondingCurveData.sellQueueHead
       });
 
       const sellOrders = [];
-      // The sell orders are created with PDA seed (i + 1), where i is the queue position
-      // So for queue positions 0, 1, 2... the PDAs use seeds 1, 2, 3...
-      for (let i = bondingCurveData.sellQueueHead; i < bondingCurveData.sellQueueTail; i++) {
-        const pdaSeed = i + 1; // PDA was created with (queue_position + 1)
-        console.log(`🔍 Fetching order at queue position ${i}, PDA seed ${pdaSeed}`);
-        const orderData = await this.getSellOrderData(pdaSeed);
+      // Open orders form a linked list by order id, from the head to the tail (0 ends it)
+      for (let orderId = bondingCurveData.sellQueueHead, i = 0; orderId !== 0; i++) {
+        console.log(`🔍 Fetching order ${orderId} at queue position ${i}`);
+        const orderData = await this.getSellOrderData(orderId);
         if (orderData) {
           const orderTyped = orderData as {
             seller: { toString(): string };
@@ -876,6 +937,7 @@ export class ContractService {
             locked_price?: { toString(): string };
             lockedPrice?: { toString(): string };
             processed: boolean;
+            next: { toString(): string };
           };
           console.log(`✅ Found order at position ${i}:`, {
             seller: orderTyped.seller.toString(),
@@ -890,8 +952,10 @@ export class ContractService {
             lockedPrice: orderTyped.locked_price?.toString() || orderTyped.lockedPrice?.toString(),
             processed: orderTyped.processed
           });
+          orderId = parseInt(orderTyped.next.toString(), 10);
         } else {
-          console.log(`❌ No order found at position ${i}`);
+          console.log(`❌ No order found with id ${orderId}`);
+          break;
         }
       }
       
@@ -915,15 +979,11 @@ export class ContractService {
       }
       
       console.log(`📊 Sell Queue: Head=${bondingCurveData.sellQueueHead}, Tail=${bondingCurveData.sellQueueTail}`);
-      console.log(`📊 Queue Length: ${bondingCurveData.sellQueueTail - bondingCurveData.sellQueueHead}`);
-      
-      // Try to fetch the sell order at position 1 (your sell order)
-      if (bondingCurveData.sellQueueTail > bondingCurveData.sellQueueHead) {
-        // The first sell order should be at queue position 0, but PDA seed is 1
-        const queuePosition = bondingCurveData.sellQueueHead;
-        const pdaSeed = queuePosition + 1;
-        console.log(`Trying to fetch sell order at queue position ${queuePosition} with PDA seed ${pdaSeed}`);
-        const sellOrderData = await this.getSellOrderData(pdaSeed);
+      
+      // Try to fetch the sell order at the head of the queue
+      if (bondingCurveData.sellQueueHead !== 0) {
+        console.log(`Trying to fetch sell order ${bondingCurveData.sellQueueHead} at the head of the queue`);
+        const sellOrderData = await this.getSellOrderData(bondingCurveData.sellQueueHead);
         if (sellOrderData) {
           console.log('✅ Found sell order:', sellOrderData);
         } else {
@@ -14,19 +14,19 @@ export type EverriseDex = {
   },
   "instructions": [
     {
-      "name": "applyDailyBoostManual",
+      "name": "acceptAuthority",
       "docs": [
-        "Manually apply daily boost (for testing and maintenance)"
+        "Accept a pending authority transfer (signed by the proposed authority)"
       ],
       "discriminator": [
-        135,
-        97,
-        225,
         107,
-        101,
-        3,
-        45,
-        240
+        86,
+        198,
+        91,
+        33,
+        12,
+        107,
+        160
       ],
       "accounts": [
         {
@@ -51,81 +51,36 @@ export type EverriseDex = {
                   118,
                   101
                 ]
-              }
-            ]
-          }
-        },
-        {
-          "name": "authority",
-          "writable": true,
-          "signer": true
-        }
-      ],
-      "args": []
-    },
-    {
-      "name": "bumpBuyTail",
-      "docs": [
-        "Bump buy_queue_tail by 1 to skip an occupied PDA"
-      ],
-      "discriminator": [
-        182,
-        96,
-        197,
-        80,
-        212,
-        144,
-        53,
-        86
-      ],
-      "accounts": [
-        {
-          "name": "bondingCurve",
-          "writable": true,
-          "pda": {
-            "seeds": [
+              },
               {
-                "kind": "const",
-                "value": [
-                  98,
-                  111,
-                  110,
-                  100,
-                  105,
-                  110,
-                  103,
-                  95,
-                  99,
-                  117,
-                  114,
-                  118,
-                  101
-                ]
+                "kind": "account",
+                "path": "bonding_curve.config.base_mint",
+                "account": "bondingCurve"
               }
             ]
           }
         },
         {
-          "name": "user",
+          "name": "newAuthority",
           "signer": true
         }
       ],
       "args": []
     },
     {
-      "name": "bumpSellTail",
+      "name": "applyDailyBoostManual",
       "docs": [
-        "Bump sell_queue_tail by 1 to skip an occupied sell_order PDA"
+        "Manually apply daily boost (for testing and maintenance, authority only)"
       ],
       "discriminator": [
-        208,
-        132,
-        217,
+        135,
+        97,
+        225,
         107,
-        235,
-        46,
-        63,
-        124
+        101,
+        3,
+        45,
+        240
       ],
       "accounts": [
         {
@@ -150,13 +105,22 @@ export type EverriseDex = {
                   118,
                   101
                 ]
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.config.base_mint",
+                "account": "bondingCurve"
               }
             ]
           }
         },
         {
-          "name": "user",
-          "signer": true
+          "name": "authority",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "bondingCurve"
+          ]
         }
       ],
       "args": []
@@ -164,7 +128,8 @@ export type EverriseDex = {
     {
       "name": "buy",
       "docs": [
-        "Buy EVER tokens using USDC from the bonding curve. This is now an atomic operation."
+        "Buy EVER tokens using USDC from the bonding curve. This is now an atomic operation.",
+        "Fails if fewer than `min_ever_out` tokens would be received or `deadline` has passed."
       ],
       "discriminator": [
         102,
@@ -199,6 +164,11 @@ export type EverriseDex = {
                   118,
                   101
                 ]
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.config.base_mint",
+                "account": "bondingCurve"
               }
             ]
           }
@@ -224,6 +194,35 @@ export type EverriseDex = {
           "name": "programEverAccount",
           "writable": true
         },
+        {
+          "name": "volumeStats",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  118,
+                  111,
+                  108,
+                  117,
+                  109,
+                  101,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  115
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bondingCurve"
+              }
+            ]
+          }
+        },
         {
           "name": "tokenProgram",
           "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
@@ -233,13 +232,26 @@ export type EverriseDex = {
         {
           "name": "usdcAmount",
           "type": "u64"
+        },
+        {
+          "name": "minEverOut",
+          "type": "u64"
+        },
+        {
+          "name": "deadline",
+          "type": {
+            "option": "i64"
+          }
         }
       ]
     },
     {
       "name": "buySmart",
       "docs": [
-        "Smart buy that processes sell orders first, then buys from reserves if needed"
+        "Smart buy that processes sell orders first, then buys from reserves if needed.",
+        "Fails if fewer than `min_ever_out` tokens are received in total or `deadline` has passed.",
+        "The last `upline_accounts` remaining accounts are the referrer's upline, forwarded to",
+        "affiliate_program::process_commission."
       ],
       "discriminator": [
         85,
@@ -274,6 +286,11 @@ export type EverriseDex = {
                   118,
                   101
                 ]
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.config.base_mint",
+                "account": "bondingCurve"
               }
             ]
           }
@@ -307,142 +324,132 @@ export type EverriseDex = {
           "name": "sellerUsdcAccount",
           "writable": true
         },
+        {
+          "name": "referral"
+        },
         {
           "name": "referrer"
         },
         {
-          "name": "referrerUsdcAccount"
+          "name": "commissionVault",
+          "writable": true
         },
         {
-          "name": "tokenProgram",
-          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
-        }
-      ],
-      "args": [
+          "name": "referralRegistry",
+          "writable": true
+        },
         {
-          "name": "usdcAmount",
-          "type": "u64"
-        }
-      ]
-    },
-    {
-      "name": "emergencyRefund",
-      "docs": [
-        "Emergency refund function - refunds USDC to buyer if transaction fails",
-        "This is a safety mechanism to prevent USDC loss"
-      ],
-      "discriminator": [
-        188,
-        73,
-        52,
-        195,
-        137,
-        70,
-        180,
-        147
-      ],
-      "accounts": [
+          "name": "affiliateState",
+          "writable": true
+        },
         {
-          "name": "bondingCurve",
-          "writable": true,
+          "name": "affiliateProgram"
+        },
+        {
+          "name": "affiliateAuthority",
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  98,
-                  111,
-                  110,
-                  100,
+                  97,
+                  102,
+                  102,
                   105,
-                  110,
-                  103,
+                  108,
+                  105,
+                  97,
+                  116,
+                  101,
                   95,
-                  99,
+                  97,
                   117,
+                  116,
+                  104,
+                  111,
                   114,
-                  118,
-                  101
+                  105,
+                  116,
+                  121
                 ]
+              },
+              {
+                "kind": "account",
+                "path": "bondingCurve"
               }
             ]
           }
         },
         {
-          "name": "buyOrder",
+          "name": "volumeStats",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  98,
-                  117,
-                  121,
-                  95,
+                  118,
                   111,
-                  114,
-                  100,
+                  108,
+                  117,
+                  109,
                   101,
-                  114
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  115
                 ]
               },
               {
                 "kind": "account",
-                "path": "bonding_curve.buy_queue_head",
-                "account": "bondingCurve"
+                "path": "bondingCurve"
               }
             ]
           }
         },
         {
-          "name": "programUsdcAccount",
-          "writable": true
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        }
+      ],
+      "args": [
+        {
+          "name": "usdcAmount",
+          "type": "u64"
         },
         {
-          "name": "buyerUsdcAccount",
-          "writable": true
+          "name": "minEverOut",
+          "type": "u64"
         },
         {
-          "name": "tokenProgram",
-          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+          "name": "deadline",
+          "type": {
+            "option": "i64"
+          }
+        },
+        {
+          "name": "uplineAccounts",
+          "type": "u8"
         }
-      ],
-      "args": []
-    },
-    {
-      "name": "getVersion",
-      "docs": [
-        "Get smart contract version for debugging"
-      ],
-      "discriminator": [
-        168,
-        85,
-        244,
-        45,
-        81,
-        56,
-        130,
-        50
-      ],
-      "accounts": [],
-      "args": [],
-      "returns": "u32"
+      ]
     },
     {
-      "name": "initialize",
+      "name": "cancelSellOrder",
       "docs": [
-        "Initialize the EverRise DEX with initial bonding curve parameters"
+        "Cancel an open sell order - returns the unfilled EVER to the seller, unlinks the order",
+        "from the queue and closes the PDA"
       ],
       "discriminator": [
-        175,
-        175,
-        109,
-        31,
-        13,
-        152,
-        155,
-        237
+        35,
+        49,
+        106,
+        38,
+        91,
+        127,
+        157,
+        20
       ],
       "accounts": [
         {
@@ -467,80 +474,92 @@ export type EverriseDex = {
                   118,
                   101
                 ]
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.config.base_mint",
+                "account": "bondingCurve"
               }
             ]
           }
         },
         {
-          "name": "authority",
-          "writable": true,
-          "signer": true
-        },
-        {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
-        }
-      ],
-      "args": [
-        {
-          "name": "treasuryWallet",
-          "type": "pubkey"
-        }
-      ]
-    },
-    {
-      "name": "processBuyQueue",
-      "docs": [
-        "Process buy orders from the queue with partial fill support and transaction safety"
-      ],
-      "discriminator": [
-        27,
-        219,
-        137,
-        135,
-        14,
-        221,
-        3,
-        217
-      ],
-      "accounts": [
-        {
-          "name": "bondingCurve",
+          "name": "sellOrder",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  98,
+                  115,
+                  101,
+                  108,
+                  108,
+                  95,
                   111,
-                  110,
+                  114,
                   100,
-                  105,
-                  110,
-                  103,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bondingCurve"
+              },
+              {
+                "kind": "arg",
+                "path": "orderId"
+              }
+            ]
+          }
+        },
+        {
+          "name": "prevSellOrder",
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  115,
+                  101,
+                  108,
+                  108,
                   95,
-                  99,
-                  117,
+                  111,
                   114,
-                  118,
-                  101
+                  100,
+                  101,
+                  114
                 ]
+              },
+              {
+                "kind": "account",
+                "path": "bondingCurve"
+              },
+              {
+                "kind": "account",
+                "path": "sell_order.prev",
+                "account": "sellOrder"
               }
             ]
           }
         },
         {
-          "name": "buyOrder",
+          "name": "nextSellOrder",
           "writable": true,
+          "optional": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  98,
-                  117,
-                  121,
+                  115,
+                  101,
+                  108,
+                  108,
                   95,
                   111,
                   114,
@@ -551,17 +570,26 @@ export type EverriseDex = {
               },
               {
                 "kind": "account",
-                "path": "bonding_curve.buy_queue_head",
-                "account": "bondingCurve"
+                "path": "bondingCurve"
+              },
+              {
+                "kind": "account",
+                "path": "sell_order.next",
+                "account": "sellOrder"
               }
             ]
           }
         },
         {
-          "name": "sellOrder"
+          "name": "seller",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "sellOrder"
+          ]
         },
         {
-          "name": "programUsdcAccount",
+          "name": "sellerEverAccount",
           "writable": true
         },
         {
@@ -569,37 +597,64 @@ export type EverriseDex = {
           "writable": true
         },
         {
-          "name": "buyerEverAccount",
-          "writable": true
-        },
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        }
+      ],
+      "args": [
         {
-          "name": "sellerUsdcAccount"
-        },
+          "name": "orderId",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "closeOrder",
+      "docs": [
+        "Close a processed order and return its rent to the original seller/buyer (permissionless crank).",
+        "Further (order, owner) pairs passed in remaining accounts are closed in the same call."
+      ],
+      "discriminator": [
+        90,
+        103,
+        209,
+        28,
+        7,
+        63,
+        168,
+        4
+      ],
+      "accounts": [
         {
-          "name": "treasuryUsdcAccount",
+          "name": "order",
           "writable": true
         },
         {
-          "name": "tokenProgram",
-          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+          "name": "owner",
+          "writable": true
         }
       ],
       "args": []
     },
     {
-      "name": "processSellQueue",
+      "name": "createMarket",
       "docs": [
-        "Process sell orders from the queue - handles matching with buy orders or direct processing"
+        "Create a market - a bonding curve for `params.base_mint` with its own order queues.",
+        "The signer must be the base mint's mint authority and becomes the curve authority.",
+        "This is deliberate: the curve PDA is keyed by the base mint alone, so anyone else",
+        "could otherwise squat a token's only market with their own params and treasury.",
+        "Tokens whose mint authority was revoked can't get a market.",
+        "buy_smart pays referral commissions through `affiliate_program` (Pubkey::default() for none)."
       ],
       "discriminator": [
+        103,
+        226,
+        97,
         235,
-        78,
-        232,
-        34,
-        50,
-        67,
-        229,
-        211
+        200,
+        188,
+        251,
+        254
       ],
       "accounts": [
         {
@@ -624,80 +679,96 @@ export type EverriseDex = {
                   118,
                   101
                 ]
+              },
+              {
+                "kind": "arg",
+                "path": "params.base_mint"
               }
             ]
           }
         },
         {
-          "name": "sellOrder",
+          "name": "baseMint"
+        },
+        {
+          "name": "quoteMint"
+        },
+        {
+          "name": "volumeStats",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  115,
-                  101,
-                  108,
-                  108,
-                  95,
+                  118,
                   111,
-                  114,
-                  100,
+                  108,
+                  117,
+                  109,
                   101,
-                  114
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  115
                 ]
               },
               {
                 "kind": "account",
-                "path": "bonding_curve.sell_queue_head",
-                "account": "bondingCurve"
+                "path": "bondingCurve"
               }
             ]
           }
         },
         {
-          "name": "programEverAccount",
-          "writable": true
+          "name": "authority",
+          "writable": true,
+          "signer": true
         },
         {
-          "name": "sellerUsdcAccount",
-          "writable": true
-        },
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
         {
-          "name": "treasuryUsdcAccount",
-          "writable": true
+          "name": "treasuryWallet",
+          "type": "pubkey"
         },
         {
-          "name": "burnEverAccount",
-          "writable": true
+          "name": "params",
+          "type": {
+            "defined": {
+              "name": "curveParams"
+            }
+          }
         },
         {
-          "name": "tokenProgram",
-          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+          "name": "affiliateProgram",
+          "type": "pubkey"
         }
-      ],
-      "args": []
+      ]
     },
     {
-      "name": "sell",
+      "name": "createVolumeStats",
       "docs": [
-        "Sell EVER tokens to the queue system with enhanced transaction safety"
+        "Create the VolumeStats account for a market opened before volume tracking"
       ],
       "discriminator": [
-        51,
-        230,
-        133,
-        164,
-        1,
-        127,
-        131,
-        173
+        45,
+        57,
+        98,
+        203,
+        182,
+        186,
+        216,
+        241
       ],
       "accounts": [
         {
           "name": "bondingCurve",
-          "writable": true,
           "pda": {
             "seeds": [
               {
@@ -717,57 +788,71 @@ export type EverriseDex = {
                   118,
                   101
                 ]
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.config.base_mint",
+                "account": "bondingCurve"
               }
             ]
           }
         },
         {
-          "name": "sellOrder",
-          "writable": true
+          "name": "volumeStats",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  118,
+                  111,
+                  108,
+                  117,
+                  109,
+                  101,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  115
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bondingCurve"
+              }
+            ]
+          }
         },
         {
-          "name": "user",
+          "name": "payer",
           "writable": true,
           "signer": true
         },
-        {
-          "name": "userEverAccount",
-          "writable": true
-        },
-        {
-          "name": "programEverAccount",
-          "writable": true
-        },
-        {
-          "name": "tokenProgram",
-          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
-        },
         {
           "name": "systemProgram",
           "address": "11111111111111111111111111111111"
         }
       ],
-      "args": [
-        {
-          "name": "everAmount",
-          "type": "u64"
-        }
-      ]
+      "args": []
     },
     {
-      "name": "skipOrphanedBuyOrders",
+      "name": "emergencyRefund",
       "docs": [
-        "Skip orphaned buy order accounts (emergency function)"
+        "Emergency refund function - refunds USDC to buyer if transaction fails",
+        "This is a safety mechanism to prevent USDC loss"
       ],
       "discriminator": [
-        83,
-        140,
-        198,
-        115,
-        8,
-        227,
-        133,
-        13
+        188,
+        73,
+        52,
+        195,
+        137,
+        70,
+        180,
+        147
       ],
       "accounts": [
         {
@@ -792,96 +877,1364 @@ export type EverriseDex = {
                   118,
                   101
                 ]
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.config.base_mint",
+                "account": "bondingCurve"
               }
             ]
           }
         },
         {
-          "name": "user",
-          "docs": [
-            "Anyone can call this function to skip orphaned accounts"
-          ],
-          "signer": true
-        }
-      ],
-      "args": [
-        {
-          "name": "count",
-          "type": "u32"
-        }
-      ]
-    }
-  ],
-  "accounts": [
-    {
-      "name": "bondingCurve",
-      "discriminator": [
-        23,
-        183,
-        248,
-        55,
-        96,
-        216,
-        172,
-        96
-      ]
+          "name": "buyOrder",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  117,
+                  121,
+                  95,
+                  111,
+                  114,
+                  100,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bondingCurve"
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.buy_queue_head",
+                "account": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "programUsdcAccount",
+          "writable": true
+        },
+        {
+          "name": "buyerUsdcAccount",
+          "writable": true
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        }
+      ],
+      "args": []
     },
     {
-      "name": "buyOrder",
+      "name": "getVersion",
+      "docs": [
+        "Get smart contract code version and account layout version for debugging"
+      ],
       "discriminator": [
-        227,
-        11,
-        110,
-        187,
-        37,
-        80,
-        95,
-        121
-      ]
+        168,
+        85,
+        244,
+        45,
+        81,
+        56,
+        130,
+        50
+      ],
+      "accounts": [],
+      "args": [],
+      "returns": {
+        "defined": {
+          "name": "versionInfo"
+        }
+      }
     },
     {
-      "name": "sellOrder",
-      "discriminator": [
-        125,
-        28,
-        219,
-        150,
-        25,
-        64,
-        250,
-        236
-      ]
-    }
-  ],
-  "events": [
-    {
-      "name": "atomicBuyEvent",
+      "name": "migrateBondingCurve",
+      "docs": [
+        "Convert a bonding curve (and any order accounts passed as remaining accounts)",
+        "from an older layout to LAYOUT_VERSION in place (authority only)"
+      ],
       "discriminator": [
-        61,
-        41,
+        1,
+        199,
+        182,
         17,
-        222,
-        50,
-        72,
-        113,
-        104
-      ]
+        100,
+        57,
+        86,
+        158
+      ],
+      "accounts": [
+        {
+          "name": "bondingCurve",
+          "docs": [
+            "discriminator, PDA and authority are validated in handle_migrate_bonding_curve"
+          ],
+          "writable": true
+        },
+        {
+          "name": "authority",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
     },
     {
-      "name": "buyProcessedEvent",
+      "name": "migrateLegacyBondingCurve",
+      "docs": [
+        "Move the curve of the original single-market deployment from [b\"bonding_curve\"]",
+        "to its per-market address, converting it to LAYOUT_VERSION (authority only, queues drained)"
+      ],
       "discriminator": [
-        49,
+        164,
+        175,
         249,
-        147,
-        191,
-        103,
-        88,
-        214,
-        155
-      ]
+        235,
+        125,
+        200,
+        95,
+        25
+      ],
+      "accounts": [
+        {
+          "name": "legacyBondingCurve",
+          "docs": [
+            "and authority are validated in handle_migrate_legacy_bonding_curve"
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  111,
+                  110,
+                  100,
+                  105,
+                  110,
+                  103,
+                  95,
+                  99,
+                  117,
+                  114,
+                  118,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "bondingCurve",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  111,
+                  110,
+                  100,
+                  105,
+                  110,
+                  103,
+                  95,
+                  99,
+                  117,
+                  114,
+                  118,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "baseMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "baseMint",
+          "address": "3q4YFYMKHrdYw5FPANQ7nrCQMT4t12XKgzYX8JaTeEx8"
+        },
+        {
+          "name": "volumeStats",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  118,
+                  111,
+                  108,
+                  117,
+                  109,
+                  101,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  115
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "programEverAccount",
+          "writable": true
+        },
+        {
+          "name": "programUsdcAccount",
+          "writable": true
+        },
+        {
+          "name": "treasuryUsdcAccount",
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "authority",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
     },
     {
-      "name": "buyQueueEvent",
+      "name": "pause",
+      "docs": [
+        "Pause the given operations (PAUSE_* flags, authority only)"
+      ],
+      "discriminator": [
+        211,
+        22,
+        221,
+        251,
+        74,
+        121,
+        193,
+        47
+      ],
+      "accounts": [
+        {
+          "name": "bondingCurve",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  111,
+                  110,
+                  100,
+                  105,
+                  110,
+                  103,
+                  95,
+                  99,
+                  117,
+                  114,
+                  118,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.config.base_mint",
+                "account": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "signer": true,
+          "relations": [
+            "bondingCurve"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "operations",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "processBuyQueue",
+      "docs": [
+        "Process buy orders from the queue with partial fill support and transaction safety"
+      ],
+      "discriminator": [
+        27,
+        219,
+        137,
+        135,
+        14,
+        221,
+        3,
+        217
+      ],
+      "accounts": [
+        {
+          "name": "bondingCurve",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  111,
+                  110,
+                  100,
+                  105,
+                  110,
+                  103,
+                  95,
+                  99,
+                  117,
+                  114,
+                  118,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.config.base_mint",
+                "account": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "buyOrder",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  117,
+                  121,
+                  95,
+                  111,
+                  114,
+                  100,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bondingCurve"
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.buy_queue_head",
+                "account": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "sellOrder",
+          "writable": true
+        },
+        {
+          "name": "programUsdcAccount",
+          "writable": true
+        },
+        {
+          "name": "programEverAccount",
+          "writable": true
+        },
+        {
+          "name": "buyerEverAccount",
+          "writable": true
+        },
+        {
+          "name": "sellerUsdcAccount",
+          "writable": true
+        },
+        {
+          "name": "treasuryUsdcAccount",
+          "writable": true
+        },
+        {
+          "name": "volumeStats",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  118,
+                  111,
+                  108,
+                  117,
+                  109,
+                  101,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  115
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "processSellQueue",
+      "docs": [
+        "Process sell orders from the queue - handles matching with buy orders or direct processing"
+      ],
+      "discriminator": [
+        235,
+        78,
+        232,
+        34,
+        50,
+        67,
+        229,
+        211
+      ],
+      "accounts": [
+        {
+          "name": "bondingCurve",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  111,
+                  110,
+                  100,
+                  105,
+                  110,
+                  103,
+                  95,
+                  99,
+                  117,
+                  114,
+                  118,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.config.base_mint",
+                "account": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "sellOrder",
+          "writable": true
+        },
+        {
+          "name": "programEverAccount",
+          "writable": true
+        },
+        {
+          "name": "sellerUsdcAccount",
+          "writable": true
+        },
+        {
+          "name": "treasuryUsdcAccount",
+          "writable": true
+        },
+        {
+          "name": "everMint",
+          "writable": true
+        },
+        {
+          "name": "volumeStats",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  118,
+                  111,
+                  108,
+                  117,
+                  109,
+                  101,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  115
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "proposeAuthority",
+      "docs": [
+        "Propose a new authority - takes effect once the new key calls accept_authority"
+      ],
+      "discriminator": [
+        20,
+        148,
+        236,
+        198,
+        76,
+        119,
+        99,
+        142
+      ],
+      "accounts": [
+        {
+          "name": "bondingCurve",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  111,
+                  110,
+                  100,
+                  105,
+                  110,
+                  103,
+                  95,
+                  99,
+                  117,
+                  114,
+                  118,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.config.base_mint",
+                "account": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "signer": true,
+          "relations": [
+            "bondingCurve"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "newAuthority",
+          "type": "pubkey"
+        }
+      ]
+    },
+    {
+      "name": "queueBuy",
+      "docs": [
+        "Queue a buy order - escrows USDC in the program vault until process_buy_queue fills it"
+      ],
+      "discriminator": [
+        49,
+        129,
+        177,
+        97,
+        58,
+        26,
+        220,
+        182
+      ],
+      "accounts": [
+        {
+          "name": "bondingCurve",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  111,
+                  110,
+                  100,
+                  105,
+                  110,
+                  103,
+                  95,
+                  99,
+                  117,
+                  114,
+                  118,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.config.base_mint",
+                "account": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "buyOrder",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  117,
+                  121,
+                  95,
+                  111,
+                  114,
+                  100,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bondingCurve"
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.next_order_id",
+                "account": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "tailBuyOrder",
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  117,
+                  121,
+                  95,
+                  111,
+                  114,
+                  100,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bondingCurve"
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.buy_queue_tail",
+                "account": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "user",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "userUsdcAccount",
+          "writable": true
+        },
+        {
+          "name": "programUsdcAccount",
+          "writable": true
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "usdcAmount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "sell",
+      "docs": [
+        "Sell EVER tokens to the queue system with enhanced transaction safety.",
+        "Fails if the locked price would be below `min_locked_price` or `deadline` has passed."
+      ],
+      "discriminator": [
+        51,
+        230,
+        133,
+        164,
+        1,
+        127,
+        131,
+        173
+      ],
+      "accounts": [
+        {
+          "name": "bondingCurve",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  111,
+                  110,
+                  100,
+                  105,
+                  110,
+                  103,
+                  95,
+                  99,
+                  117,
+                  114,
+                  118,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.config.base_mint",
+                "account": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "sellOrder",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  115,
+                  101,
+                  108,
+                  108,
+                  95,
+                  111,
+                  114,
+                  100,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bondingCurve"
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.next_order_id",
+                "account": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "tailSellOrder",
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  115,
+                  101,
+                  108,
+                  108,
+                  95,
+                  111,
+                  114,
+                  100,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bondingCurve"
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.sell_queue_tail",
+                "account": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "user",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "userEverAccount",
+          "writable": true
+        },
+        {
+          "name": "programEverAccount",
+          "writable": true
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "everAmount",
+          "type": "u64"
+        },
+        {
+          "name": "minLockedPrice",
+          "type": "u64"
+        },
+        {
+          "name": "deadline",
+          "type": {
+            "option": "i64"
+          }
+        }
+      ]
+    },
+    {
+      "name": "setAffiliateProgram",
+      "docs": [
+        "Update the affiliate program buy_smart pays commissions through - Pubkey::default()",
+        "sends every commission to the treasury. Referred buys fail until that program allow-lists",
+        "the market's [b\"affiliate_authority\", bonding_curve] PDA (authority only)"
+      ],
+      "discriminator": [
+        154,
+        187,
+        89,
+        81,
+        213,
+        152,
+        181,
+        82
+      ],
+      "accounts": [
+        {
+          "name": "bondingCurve",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  111,
+                  110,
+                  100,
+                  105,
+                  110,
+                  103,
+                  95,
+                  99,
+                  117,
+                  114,
+                  118,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.config.base_mint",
+                "account": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "signer": true,
+          "relations": [
+            "bondingCurve"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "affiliateProgram",
+          "type": "pubkey"
+        }
+      ]
+    },
+    {
+      "name": "setSellSettlement",
+      "docs": [
+        "Choose whether direct sells burn EVER or return it to reserve (SELL_SETTLEMENT_* mode, authority only)"
+      ],
+      "discriminator": [
+        26,
+        62,
+        55,
+        174,
+        226,
+        53,
+        219,
+        208
+      ],
+      "accounts": [
+        {
+          "name": "bondingCurve",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  111,
+                  110,
+                  100,
+                  105,
+                  110,
+                  103,
+                  95,
+                  99,
+                  117,
+                  114,
+                  118,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.config.base_mint",
+                "account": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "signer": true,
+          "relations": [
+            "bondingCurve"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "sellSettlement",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "setTreasuryWallet",
+      "docs": [
+        "Update the treasury wallet that receives USDC (authority only)"
+      ],
+      "discriminator": [
+        48,
+        205,
+        2,
+        69,
+        219,
+        132,
+        176,
+        133
+      ],
+      "accounts": [
+        {
+          "name": "bondingCurve",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  111,
+                  110,
+                  100,
+                  105,
+                  110,
+                  103,
+                  95,
+                  99,
+                  117,
+                  114,
+                  118,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.config.base_mint",
+                "account": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "signer": true,
+          "relations": [
+            "bondingCurve"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "treasuryWallet",
+          "type": "pubkey"
+        }
+      ]
+    },
+    {
+      "name": "unpause",
+      "docs": [
+        "Resume the given operations (PAUSE_* flags, authority only)"
+      ],
+      "discriminator": [
+        169,
+        144,
+        4,
+        38,
+        10,
+        141,
+        188,
+        255
+      ],
+      "accounts": [
+        {
+          "name": "bondingCurve",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  111,
+                  110,
+                  100,
+                  105,
+                  110,
+                  103,
+                  95,
+                  99,
+                  117,
+                  114,
+                  118,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.config.base_mint",
+                "account": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "signer": true,
+          "relations": [
+            "bondingCurve"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "operations",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "verifyInvariants",
+      "docs": [
+        "Check X/Y/K, reserve and escrow balances and queue ordering (read-only).",
+        "Pass every open sell order, head first, as remaining accounts."
+      ],
+      "discriminator": [
+        38,
+        242,
+        23,
+        158,
+        18,
+        238,
+        240,
+        209
+      ],
+      "accounts": [
+        {
+          "name": "bondingCurve",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  111,
+                  110,
+                  100,
+                  105,
+                  110,
+                  103,
+                  95,
+                  99,
+                  117,
+                  114,
+                  118,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bonding_curve.config.base_mint",
+                "account": "bondingCurve"
+              }
+            ]
+          }
+        },
+        {
+          "name": "treasuryUsdcAccount"
+        },
+        {
+          "name": "programEverAccount"
+        },
+        {
+          "name": "everMint"
+        }
+      ],
+      "args": [],
+      "returns": {
+        "defined": {
+          "name": "invariantReport"
+        }
+      }
+    }
+  ],
+  "accounts": [
+    {
+      "name": "bondingCurve",
+      "discriminator": [
+        23,
+        183,
+        248,
+        55,
+        96,
+        216,
+        172,
+        96
+      ]
+    },
+    {
+      "name": "buyOrder",
+      "discriminator": [
+        227,
+        11,
+        110,
+        187,
+        37,
+        80,
+        95,
+        121
+      ]
+    },
+    {
+      "name": "sellOrder",
+      "discriminator": [
+        125,
+        28,
+        219,
+        150,
+        25,
+        64,
+        250,
+        236
+      ]
+    },
+    {
+      "name": "volumeStats",
+      "discriminator": [
+        238,
+        138,
+        49,
+        231,
+        155,
+        28,
+        202,
+        12
+      ]
+    }
+  ],
+  "events": [
+    {
+      "name": "accountMigratedEvent",
+      "discriminator": [
+        109,
+        3,
+        25,
+        119,
+        155,
+        108,
+        69,
+        61
+      ]
+    },
+    {
+      "name": "affiliateProgramUpdatedEvent",
+      "discriminator": [
+        116,
+        210,
+        34,
+        120,
+        216,
+        8,
+        93,
+        47
+      ]
+    },
+    {
+      "name": "atomicBuyEvent",
+      "discriminator": [
+        61,
+        41,
+        17,
+        222,
+        50,
+        72,
+        113,
+        104
+      ]
+    },
+    {
+      "name": "authorityProposedEvent",
+      "discriminator": [
+        221,
+        27,
+        73,
+        198,
+        252,
+        169,
+        231,
+        224
+      ]
+    },
+    {
+      "name": "authorityTransferredEvent",
+      "discriminator": [
+        54,
+        31,
+        118,
+        237,
+        152,
+        5,
+        201,
+        11
+      ]
+    },
+    {
+      "name": "buyProcessedEvent",
+      "discriminator": [
+        49,
+        249,
+        147,
+        191,
+        103,
+        88,
+        214,
+        155
+      ]
+    },
+    {
+      "name": "buyQueueEvent",
       "discriminator": [
         91,
         157,
@@ -919,6 +2272,71 @@ export type EverriseDex = {
         127
       ]
     },
+    {
+      "name": "invariantsViolatedEvent",
+      "discriminator": [
+        191,
+        141,
+        32,
+        139,
+        252,
+        231,
+        86,
+        46
+      ]
+    },
+    {
+      "name": "marketCreatedEvent",
+      "discriminator": [
+        130,
+        142,
+        5,
+        16,
+        107,
+        160,
+        73,
+        124
+      ]
+    },
+    {
+      "name": "orderClosedEvent",
+      "discriminator": [
+        0,
+        41,
+        45,
+        185,
+        166,
+        185,
+        19,
+        113
+      ]
+    },
+    {
+      "name": "pauseUpdatedEvent",
+      "discriminator": [
+        181,
+        206,
+        46,
+        3,
+        114,
+        110,
+        43,
+        211
+      ]
+    },
+    {
+      "name": "sellOrderCancelledEvent",
+      "discriminator": [
+        97,
+        74,
+        249,
+        167,
+        165,
+        103,
+        191,
+        50
+      ]
+    },
     {
       "name": "sellProcessedEvent",
       "discriminator": [
@@ -944,6 +2362,32 @@ export type EverriseDex = {
         25,
         185
       ]
+    },
+    {
+      "name": "sellSettlementUpdatedEvent",
+      "discriminator": [
+        173,
+        11,
+        155,
+        136,
+        171,
+        184,
+        10,
+        69
+      ]
+    },
+    {
+      "name": "treasuryWalletUpdatedEvent",
+      "discriminator": [
+        195,
+        100,
+        2,
+        45,
+        164,
+        227,
+        117,
+        51
+      ]
     }
   ],
   "errors": [
@@ -973,53 +2417,590 @@ export type EverriseDex = {
       "msg": "Transaction amount too large"
     },
     {
-      "code": 6005,
-      "name": "unauthorized",
-      "msg": "Unauthorized access"
+      "code": 6005,
+      "name": "unauthorized",
+      "msg": "Unauthorized access"
+    },
+    {
+      "code": 6006,
+      "name": "invalidBuyer",
+      "msg": "Invalid buyer address"
+    },
+    {
+      "code": 6007,
+      "name": "refundNotReady",
+      "msg": "Transaction not ready for refund"
+    },
+    {
+      "code": 6008,
+      "name": "priceCalculationFailed",
+      "msg": "Price calculation failed"
+    },
+    {
+      "code": 6009,
+      "name": "insufficientLiquidity",
+      "msg": "Insufficient liquidity"
+    },
+    {
+      "code": 6010,
+      "name": "invalidRemainingAccounts",
+      "msg": "Remaining accounts must be (sell_order, seller_usdc_account) pairs"
+    },
+    {
+      "code": 6011,
+      "name": "orderAlreadyProcessed",
+      "msg": "Order already processed"
+    },
+    {
+      "code": 6012,
+      "name": "invalidSellOrder",
+      "msg": "Sell order account is not the PDA at the queue head"
+    },
+    {
+      "code": 6013,
+      "name": "invalidSellerUsdcAccount",
+      "msg": "Seller USDC account is not a valid token account"
+    },
+    {
+      "code": 6014,
+      "name": "sellerUsdcOwnerMismatch",
+      "msg": "Seller USDC account does not belong to the sell order's seller"
+    },
+    {
+      "code": 6015,
+      "name": "invalidSellerUsdcMint",
+      "msg": "Seller USDC account is not a quote mint account"
+    },
+    {
+      "code": 6016,
+      "name": "operationPaused",
+      "msg": "Operation is paused"
+    },
+    {
+      "code": 6017,
+      "name": "slippageExceeded",
+      "msg": "Slippage tolerance exceeded"
+    },
+    {
+      "code": 6018,
+      "name": "deadlineExpired",
+      "msg": "Transaction deadline expired"
+    },
+    {
+      "code": 6019,
+      "name": "invalidCurveParams",
+      "msg": "Invalid curve parameters"
+    },
+    {
+      "code": 6020,
+      "name": "unsupportedAccountLayout",
+      "msg": "Account layout is not recognised by this program version"
+    },
+    {
+      "code": 6021,
+      "name": "orderNotProcessed",
+      "msg": "Order has not been fully processed"
+    },
+    {
+      "code": 6022,
+      "name": "invalidOrderAccount",
+      "msg": "Account is not a SellOrder or BuyOrder"
+    },
+    {
+      "code": 6023,
+      "name": "orderOwnerMismatch",
+      "msg": "Rent recipient is not the order's seller/buyer"
+    },
+    {
+      "code": 6024,
+      "name": "invalidCloseAccounts",
+      "msg": "Remaining accounts must be (order, owner) pairs"
+    },
+    {
+      "code": 6025,
+      "name": "queueLinkMissing",
+      "msg": "A neighbouring queue order is required to relink the queue"
+    },
+    {
+      "code": 6026,
+      "name": "queueNotDrained",
+      "msg": "Order queues must be drained before migrating to this layout"
+    },
+    {
+      "code": 6027,
+      "name": "invariantKMismatch",
+      "msg": "K does not equal X * Y"
+    },
+    {
+      "code": 6028,
+      "name": "treasuryUnderfunded",
+      "msg": "Treasury USDC balance is below X"
+    },
+    {
+      "code": 6029,
+      "name": "reserveUnderfunded",
+      "msg": "Program EVER balance is below Y"
+    },
+    {
+      "code": 6030,
+      "name": "escrowMismatch",
+      "msg": "Program EVER beyond Y or the tracked escrow does not match the open sell orders"
+    },
+    {
+      "code": 6031,
+      "name": "queueOrderInvalid",
+      "msg": "Queue head is past its tail or the queue does not end at its tail"
+    },
+    {
+      "code": 6032,
+      "name": "affiliateCommissionMissing",
+      "msg": "Affiliate program did not return the commission paid"
+    },
+    {
+      "code": 6033,
+      "name": "buyerUsdcOwnerMismatch",
+      "msg": "Buyer USDC account does not belong to the buy order's buyer"
+    }
+  ],
+  "types": [
+    {
+      "name": "accountMigratedEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "account",
+            "type": "pubkey"
+          },
+          {
+            "name": "fromVersion",
+            "type": "u8"
+          },
+          {
+            "name": "toVersion",
+            "type": "u8"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "affiliateProgramUpdatedEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "previousAffiliateProgram",
+            "type": "pubkey"
+          },
+          {
+            "name": "affiliateProgram",
+            "type": "pubkey"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "atomicBuyEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "usdcAmount",
+            "type": "u64"
+          },
+          {
+            "name": "everReceived",
+            "type": "u64"
+          },
+          {
+            "name": "newPrice",
+            "type": "u64"
+          },
+          {
+            "name": "appreciationBonus",
+            "type": "u64"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "authorityProposedEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "authority",
+            "type": "pubkey"
+          },
+          {
+            "name": "pendingAuthority",
+            "type": "pubkey"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "authorityTransferredEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "previousAuthority",
+            "type": "pubkey"
+          },
+          {
+            "name": "newAuthority",
+            "type": "pubkey"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "bondingCurve",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "authority",
+            "type": "pubkey"
+          },
+          {
+            "name": "treasuryWallet",
+            "type": "pubkey"
+          },
+          {
+            "name": "x",
+            "type": "u64"
+          },
+          {
+            "name": "y",
+            "type": "u64"
+          },
+          {
+            "name": "k",
+            "type": "u128"
+          },
+          {
+            "name": "lastDailyBoost",
+            "type": "i64"
+          },
+          {
+            "name": "totalVolume24h",
+            "type": "u64"
+          },
+          {
+            "name": "sellQueueHead",
+            "type": "u64"
+          },
+          {
+            "name": "sellQueueTail",
+            "type": "u64"
+          },
+          {
+            "name": "buyQueueHead",
+            "type": "u64"
+          },
+          {
+            "name": "buyQueueTail",
+            "type": "u64"
+          },
+          {
+            "name": "cumulativeBonus",
+            "type": "u64"
+          },
+          {
+            "name": "currentPrice",
+            "type": "u64"
+          },
+          {
+            "name": "lastPriceUpdate",
+            "type": "i64"
+          },
+          {
+            "name": "dailyBoostApplied",
+            "type": "bool"
+          },
+          {
+            "name": "circulatingSupply",
+            "type": "u64"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "pendingAuthority",
+            "type": "pubkey"
+          },
+          {
+            "name": "pausedOperations",
+            "type": "u8"
+          },
+          {
+            "name": "config",
+            "type": {
+              "defined": {
+                "name": "curveParams"
+              }
+            }
+          },
+          {
+            "name": "dayStartPrice",
+            "type": "u64"
+          },
+          {
+            "name": "dayStartTs",
+            "type": "i64"
+          },
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "nextOrderId",
+            "type": "u64"
+          },
+          {
+            "name": "sellSettlement",
+            "type": "u8"
+          },
+          {
+            "name": "totalBurned",
+            "type": "u64"
+          },
+          {
+            "name": "escrowedSellEver",
+            "type": "u64"
+          },
+          {
+            "name": "affiliateProgram",
+            "type": "pubkey"
+          },
+          {
+            "name": "reserved",
+            "type": {
+              "array": [
+                "u8",
+                71
+              ]
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "buyOrder",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "usdcAmount",
+            "type": "u64"
+          },
+          {
+            "name": "expectedTokens",
+            "type": "u64"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          },
+          {
+            "name": "processed",
+            "type": "bool"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "id",
+            "type": "u64"
+          },
+          {
+            "name": "next",
+            "type": "u64"
+          },
+          {
+            "name": "reserved",
+            "type": {
+              "array": [
+                "u8",
+                16
+              ]
+            }
+          }
+        ]
+      }
     },
     {
-      "code": 6006,
-      "name": "invalidBuyer",
-      "msg": "Invalid buyer address"
+      "name": "buyProcessedEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "usdcAmount",
+            "type": "u64"
+          },
+          {
+            "name": "everTokens",
+            "type": "u64"
+          },
+          {
+            "name": "queueTransactions",
+            "type": "u64"
+          },
+          {
+            "name": "reserveTransactions",
+            "type": "u64"
+          },
+          {
+            "name": "appreciationBonus",
+            "type": "u64"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
     },
     {
-      "code": 6007,
-      "name": "refundNotReady",
-      "msg": "Transaction not ready for refund"
+      "name": "buyQueueEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "usdcAmount",
+            "type": "u64"
+          },
+          {
+            "name": "estimatedTokens",
+            "type": "u64"
+          },
+          {
+            "name": "orderId",
+            "type": "u64"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
     },
     {
-      "code": 6008,
-      "name": "priceCalculationFailed",
-      "msg": "Price calculation failed"
+      "name": "curveParams",
+      "docs": [
+        "Per-curve parameters supplied at initialize (replaces the old compile-time constants)"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "quoteMint",
+            "type": "pubkey"
+          },
+          {
+            "name": "baseMint",
+            "type": "pubkey"
+          },
+          {
+            "name": "initialX",
+            "type": "u64"
+          },
+          {
+            "name": "initialY",
+            "type": "u64"
+          },
+          {
+            "name": "dailyGrowthBps",
+            "type": "u16"
+          },
+          {
+            "name": "maxQuoteAmount",
+            "type": "u64"
+          },
+          {
+            "name": "maxBaseAmount",
+            "type": "u64"
+          },
+          {
+            "name": "commissionBps",
+            "type": "u16"
+          }
+        ]
+      }
     },
     {
-      "code": 6009,
-      "name": "insufficientLiquidity",
-      "msg": "Insufficient liquidity"
-    }
-  ],
-  "types": [
-    {
-      "name": "atomicBuyEvent",
+      "name": "dailyBoostEvent",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "buyer",
-            "type": "pubkey"
+            "name": "organicPrice",
+            "type": "u64"
           },
           {
-            "name": "usdcAmount",
+            "name": "minimumPrice",
             "type": "u64"
           },
           {
-            "name": "everReceived",
+            "name": "finalPrice",
             "type": "u64"
           },
           {
-            "name": "newPrice",
+            "name": "daysPassed",
+            "type": "i64"
+          },
+          {
+            "name": "boostAmount",
             "type": "u64"
           },
+          {
+            "name": "dayStartTs",
+            "type": "i64"
+          },
           {
             "name": "timestamp",
             "type": "i64"
@@ -1028,137 +3009,121 @@ export type EverriseDex = {
       }
     },
     {
-      "name": "bondingCurve",
+      "name": "emergencyRefundEvent",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "authority",
-            "type": "pubkey"
-          },
-          {
-            "name": "treasuryWallet",
+            "name": "buyer",
             "type": "pubkey"
           },
           {
-            "name": "x",
-            "type": "u64"
-          },
-          {
-            "name": "y",
+            "name": "usdcAmount",
             "type": "u64"
           },
           {
-            "name": "k",
-            "type": "u128"
+            "name": "timeElapsed",
+            "type": "i64"
           },
           {
-            "name": "lastDailyBoost",
+            "name": "timestamp",
             "type": "i64"
-          },
+          }
+        ]
+      }
+    },
+    {
+      "name": "invariantReport",
+      "type": {
+        "kind": "struct",
+        "fields": [
           {
-            "name": "totalVolume24h",
+            "name": "x",
             "type": "u64"
           },
           {
-            "name": "sellQueueHead",
+            "name": "y",
             "type": "u64"
           },
           {
-            "name": "sellQueueTail",
-            "type": "u64"
+            "name": "k",
+            "type": "u128"
           },
           {
-            "name": "buyQueueHead",
+            "name": "treasuryUsdc",
             "type": "u64"
           },
           {
-            "name": "buyQueueTail",
+            "name": "reserveEver",
             "type": "u64"
           },
           {
-            "name": "cumulativeBonus",
+            "name": "escrowedSellEver",
             "type": "u64"
           },
           {
-            "name": "currentPrice",
+            "name": "trackedEscrowedSellEver",
             "type": "u64"
           },
           {
-            "name": "lastPriceUpdate",
-            "type": "i64"
+            "name": "openSellOrders",
+            "type": "u32"
           },
           {
-            "name": "dailyBoostApplied",
-            "type": "bool"
+            "name": "circulatingSupply",
+            "type": "u64"
           },
           {
-            "name": "circulatingSupply",
+            "name": "mintSupply",
             "type": "u64"
           },
           {
-            "name": "bump",
+            "name": "violations",
             "type": "u8"
           }
         ]
       }
     },
     {
-      "name": "buyOrder",
+      "name": "invariantsViolatedEvent",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "buyer",
+            "name": "bondingCurve",
             "type": "pubkey"
           },
           {
-            "name": "usdcAmount",
-            "type": "u64"
-          },
-          {
-            "name": "expectedTokens",
-            "type": "u64"
+            "name": "violations",
+            "type": "u8"
           },
           {
             "name": "timestamp",
             "type": "i64"
-          },
-          {
-            "name": "processed",
-            "type": "bool"
-          },
-          {
-            "name": "bump",
-            "type": "u8"
           }
         ]
       }
     },
     {
-      "name": "buyProcessedEvent",
+      "name": "marketCreatedEvent",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "buyer",
+            "name": "bondingCurve",
             "type": "pubkey"
           },
           {
-            "name": "usdcAmount",
-            "type": "u64"
-          },
-          {
-            "name": "everTokens",
-            "type": "u64"
+            "name": "baseMint",
+            "type": "pubkey"
           },
           {
-            "name": "queueTransactions",
-            "type": "u64"
+            "name": "quoteMint",
+            "type": "pubkey"
           },
           {
-            "name": "reserveTransactions",
-            "type": "u64"
+            "name": "authority",
+            "type": "pubkey"
           },
           {
             "name": "timestamp",
@@ -1168,24 +3133,24 @@ export type EverriseDex = {
       }
     },
     {
-      "name": "buyQueueEvent",
+      "name": "orderClosedEvent",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "buyer",
+            "name": "order",
             "type": "pubkey"
           },
           {
-            "name": "usdcAmount",
-            "type": "u64"
+            "name": "owner",
+            "type": "pubkey"
           },
           {
-            "name": "estimatedTokens",
-            "type": "u64"
+            "name": "orderType",
+            "type": "u8"
           },
           {
-            "name": "queuePosition",
+            "name": "rentReclaimed",
             "type": "u64"
           },
           {
@@ -1196,53 +3161,98 @@ export type EverriseDex = {
       }
     },
     {
-      "name": "dailyBoostEvent",
+      "name": "pauseUpdatedEvent",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "organicPrice",
+            "name": "pausedOperations",
+            "type": "u8"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "sellOrder",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "everAmount",
             "type": "u64"
           },
           {
-            "name": "minimumPrice",
+            "name": "remainingAmount",
             "type": "u64"
           },
           {
-            "name": "finalPrice",
+            "name": "lockedPrice",
             "type": "u64"
           },
           {
-            "name": "daysPassed",
+            "name": "timestamp",
             "type": "i64"
           },
           {
-            "name": "boostAmount",
+            "name": "processed",
+            "type": "bool"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "id",
             "type": "u64"
           },
           {
-            "name": "timestamp",
-            "type": "i64"
+            "name": "prev",
+            "type": "u64"
+          },
+          {
+            "name": "next",
+            "type": "u64"
+          },
+          {
+            "name": "reserved",
+            "type": {
+              "array": [
+                "u8",
+                8
+              ]
+            }
           }
         ]
       }
     },
     {
-      "name": "emergencyRefundEvent",
+      "name": "sellOrderCancelledEvent",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "buyer",
+            "name": "seller",
             "type": "pubkey"
           },
           {
-            "name": "usdcAmount",
+            "name": "everAmount",
             "type": "u64"
           },
           {
-            "name": "timeElapsed",
-            "type": "i64"
+            "name": "orderId",
+            "type": "u64"
           },
           {
             "name": "timestamp",
@@ -1252,7 +3262,7 @@ export type EverriseDex = {
       }
     },
     {
-      "name": "sellOrder",
+      "name": "sellProcessedEvent",
       "type": {
         "kind": "struct",
         "fields": [
@@ -1265,7 +3275,7 @@ export type EverriseDex = {
             "type": "u64"
           },
           {
-            "name": "remainingAmount",
+            "name": "usdcAmount",
             "type": "u64"
           },
           {
@@ -1273,22 +3283,22 @@ export type EverriseDex = {
             "type": "u64"
           },
           {
-            "name": "timestamp",
-            "type": "i64"
+            "name": "processingType",
+            "type": "u8"
           },
           {
-            "name": "processed",
-            "type": "bool"
+            "name": "appreciationBonus",
+            "type": "u64"
           },
           {
-            "name": "bump",
-            "type": "u8"
+            "name": "timestamp",
+            "type": "i64"
           }
         ]
       }
     },
     {
-      "name": "sellProcessedEvent",
+      "name": "sellQueueEvent",
       "type": {
         "kind": "struct",
         "fields": [
@@ -1301,15 +3311,27 @@ export type EverriseDex = {
             "type": "u64"
           },
           {
-            "name": "usdcAmount",
+            "name": "lockedPrice",
             "type": "u64"
           },
           {
-            "name": "lockedPrice",
+            "name": "orderId",
             "type": "u64"
           },
           {
-            "name": "processingType",
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "sellSettlementUpdatedEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "sellSettlement",
             "type": "u8"
           },
           {
@@ -1320,29 +3342,118 @@ export type EverriseDex = {
       }
     },
     {
-      "name": "sellQueueEvent",
+      "name": "treasuryWalletUpdatedEvent",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "seller",
+            "name": "previousTreasuryWallet",
             "type": "pubkey"
           },
           {
-            "name": "everAmount",
+            "name": "treasuryWallet",
+            "type": "pubkey"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "versionInfo",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "codeVersion",
+            "type": "u32"
+          },
+          {
+            "name": "layoutVersion",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "volume",
+      "docs": [
+        "USDC volume, by side and by venue"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "buy",
             "type": "u64"
           },
           {
-            "name": "lockedPrice",
+            "name": "sell",
             "type": "u64"
           },
           {
-            "name": "queuePosition",
+            "name": "queue",
             "type": "u64"
           },
           {
-            "name": "timestamp",
+            "name": "reserve",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "volumeStats",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "bondingCurve",
+            "type": "pubkey"
+          },
+          {
+            "name": "currentHour",
             "type": "i64"
+          },
+          {
+            "name": "hourly",
+            "type": {
+              "array": [
+                {
+                  "defined": {
+                    "name": "volume"
+                  }
+                },
+                24
+              ]
+            }
+          },
+          {
+            "name": "lifetime",
+            "type": {
+              "defined": {
+                "name": "volume"
+              }
+            }
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "reserved",
+            "type": {
+              "array": [
+                "u8",
+                64
+              ]
+            }
           }
         ]
       }
@@ -1,41 +1,92 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
 
-declare_id!("AffiliateProgram1111111111111111111111111111111");
+declare_id!("2vxcei86d8uh3NWcZKqJui9uFwMEe71KZbRmbFFv5ayP");
+
+// Layouts have no in-place migration - changing them needs a fresh deploy and set_affiliate_program on each market
+
+pub const BASIS_POINTS: u64 = 10_000; // 100% = 10,000 basis points
+pub const MAX_COMMISSION_TIERS: usize = 8;
+pub const MAX_UPLINE_LEVELS: usize = 3; // Levels paid above the direct referrer
+pub const MAX_COMMISSION_AUTHORITIES: usize = 16; // Markets allowed to report purchases
+
+// Launch tiers by cumulative referred volume (USDC, 6 decimals): 3%, then 5% from 10k, 7% from 100k
+const DEFAULT_COMMISSION_TIERS: [CommissionTier; 3] = [
+    CommissionTier { min_volume: 0, rate_bps: 300 },
+    CommissionTier { min_volume: 10_000_000_000, rate_bps: 500 },
+    CommissionTier { min_volume: 100_000_000_000, rate_bps: 700 },
+];
+
+// Launch split: the direct referrer's own referrer gets 20% of each commission
+const DEFAULT_UPLINE_SPLITS_BPS: [u16; 1] = [2_000];
 
 #[program]
 pub mod affiliate_program {
     use super::*;
 
-    // Initialize the affiliate program
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+    // Initialize the affiliate program and its USDC commission vault. `commission_authorities`
+    // are the only signers process_commission accepts - the [b"affiliate_authority", bonding_curve]
+    // PDAs of the DEX markets allowed to pay commissions.
+    pub fn initialize(ctx: Context<Initialize>, commission_authorities: Vec<Pubkey>) -> Result<()> {
+        require!(commission_authorities.len() <= MAX_COMMISSION_AUTHORITIES, ErrorCode::TooManyCommissionAuthorities);
+        
         let affiliate_program = &mut ctx.accounts.affiliate_program;
         affiliate_program.authority = ctx.accounts.authority.key();
+        affiliate_program.commission_authorities = commission_authorities;
         affiliate_program.treasury_wallet = ctx.accounts.treasury_wallet.key();
         affiliate_program.total_referrals = 0;
         affiliate_program.total_commissions_paid = 0;
+        affiliate_program.commission_tiers = DEFAULT_COMMISSION_TIERS.to_vec();
+        affiliate_program.upline_splits_bps = DEFAULT_UPLINE_SPLITS_BPS.to_vec();
+        affiliate_program.vesting_period = 0;
         
         msg!("Affiliate program initialized");
         Ok(())
     }
 
-    // Register a new referral relationship
-    pub fn register_referral(ctx: Context<RegisterReferral>, referrer: Pubkey) -> Result<()> {
-        let referral_registry = &mut ctx.accounts.referral_registry;
-        let affiliate_program = &mut ctx.accounts.affiliate_program;
+    // Register a new referral relationship - a wallet can only ever have one referrer.
+    // Remaining accounts: the Referral PDAs up the referrer's chain (the referrer's own first),
+    // until one does not exist or MAX_UPLINE_LEVELS are checked, so no cycle can form within
+    // the levels that get paid.
+    pub fn register_referral<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RegisterReferral<'info>>,
+        referrer: Pubkey,
+    ) -> Result<()> {
+        require_keys_eq!(referrer, ctx.accounts.referrer.key(), ErrorCode::InvalidReferrer);
+        require_keys_neq!(referrer, ctx.accounts.referred_wallet.key(), ErrorCode::SelfReferral);
         
-        // Check if this wallet is already referred
-        require!(
-            !referral_registry.referred_wallets.contains(&ctx.accounts.referred_wallet.key()),
-            ErrorCode::AlreadyReferred
-        );
+        let referred_wallet = ctx.accounts.referred_wallet.key();
+        let mut upline_accounts = ctx.remaining_accounts.iter();
+        let mut wallet = referrer;
+        for _ in 0..MAX_UPLINE_LEVELS {
+            let referral_info = upline_accounts.next().ok_or(ErrorCode::UplineAccountsMissing)?;
+            match referrer_of(referral_info, &wallet)? {
+                Some(upline) => {
+                    require_keys_neq!(upline, referred_wallet, ErrorCode::ReferralCycle);
+                    wallet = upline;
+                }
+                None => break,
+            }
+        }
+        
+        // The Referral PDA is keyed by the referred wallet, so this is a global check
+        let referral = &mut ctx.accounts.referral;
+        require!(referral.referrer == Pubkey::default(), ErrorCode::AlreadyReferred);
+        referral.referrer = referrer;
+        referral.created_at = Clock::get()?.unix_timestamp;
+        referral.bump = ctx.bumps.referral;
         
-        // Add the referred wallet to the referrer's list
-        referral_registry.referred_wallets.push(ctx.accounts.referred_wallet.key());
+        // Referrer stats are created by the first referral
+        let referral_registry = &mut ctx.accounts.referral_registry;
+        if referral_registry.referrer == Pubkey::default() {
+            referral_registry.referrer = referrer;
+            referral_registry.rate_override_bps = None;
+            referral_registry.bump = ctx.bumps.referral_registry;
+        }
         referral_registry.total_referrals += 1;
-        referral_registry.total_commission_earned = 0;
         
         // Update global stats
+        let affiliate_program = &mut ctx.accounts.affiliate_program;
         affiliate_program.total_referrals += 1;
         
         msg!("New referral registered: {} -> {}", 
@@ -45,50 +96,241 @@ pub mod affiliate_program {
         Ok(())
     }
 
-    // Process affiliate commission for a purchase
-    pub fn process_commission(
-        ctx: Context<ProcessCommission>,
+    // Process affiliate commission for a purchase - called by everrise_dex::buy_smart via CPI,
+    // signed by an allowed market's commission authority so purchases (and so referred volume)
+    // cannot be reported by anyone else. Only credits the referrer recorded in the buyer's Referral, at the referrer's override or
+    // the tier for their referred volume so far. Each upline level takes its configured split
+    // of the commission and the direct referrer keeps the rest. The whole commission moves into
+    // the commission vault and is credited to the referrers' registries, to be claimed later
+    // with claim_commissions. Returns the commission charged.
+    //
+    // Remaining accounts walk the upline, one level at a time: the Referral PDA of the previous
+    // level's wallet, then - if it exists - that referrer's ReferralRegistry.
+    // The walk ends at a missing Referral, a wallet already paid, or the configured depth.
+    pub fn process_commission<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ProcessCommission<'info>>,
         purchase_amount: u64,
-        commission_rate: u64, // Basis points (500 = 5%)
-    ) -> Result<()> {
+    ) -> Result<u64> {
         let affiliate_program = &mut ctx.accounts.affiliate_program;
         let referral_registry = &mut ctx.accounts.referral_registry;
+        let buyer = ctx.accounts.buyer.key();
+        let timestamp = Clock::get()?.unix_timestamp;
+        
+        let rate_bps = referral_registry.commission_rate_bps(affiliate_program);
+        let commission_amount = share_of(purchase_amount, rate_bps)?;
+        
+        let vesting_period = affiliate_program.vesting_period;
+        
+        // Credit the upline first - the direct referrer keeps what is left
+        let mut upline_accounts = ctx.remaining_accounts.iter();
+        let mut paid_wallets = vec![buyer, referral_registry.referrer];
+        let mut upline_paid = 0u64;
+        for (level, split_bps) in affiliate_program.upline_splits_bps.iter().enumerate() {
+            let referral_info = upline_accounts.next().ok_or(ErrorCode::UplineAccountsMissing)?;
+            let upline = match referrer_of(referral_info, paid_wallets.last().unwrap())? {
+                Some(upline) if !paid_wallets.contains(&upline) => upline,
+                _ => break, // Top of the chain, or a cycle longer than registration checks
+            };
+            
+            let registry_info = upline_accounts.next().ok_or(ErrorCode::UplineAccountsMissing)?;
+            let mut upline_registry = Account::<ReferralRegistry>::try_from(registry_info)?;
+            require_keys_eq!(upline_registry.referrer, upline, ErrorCode::InvalidUplineAccount);
+            
+            let amount = share_of(commission_amount, *split_bps)?;
+            upline_registry.accrue(amount, timestamp, vesting_period)?;
+            upline_registry.exit(&crate::ID)?;
+            
+            upline_paid += amount;
+            paid_wallets.push(upline);
+            emit!(CommissionAccruedEvent { buyer, referrer: upline, level: level as u8 + 2, amount, timestamp });
+        }
+        
+        let referrer_amount = commission_amount.checked_sub(upline_paid).ok_or(ErrorCode::MathOverflow)?;
+        referral_registry.accrue(referrer_amount, timestamp, vesting_period)?;
+        emit!(CommissionAccruedEvent { buyer, referrer: referral_registry.referrer, level: 1, amount: referrer_amount, timestamp });
+        
+        // Move the whole commission into the vault
+        if commission_amount > 0 {
+            let transfer_instruction = Transfer {
+                from: ctx.accounts.buyer_usdc_account.to_account_info(),
+                to: ctx.accounts.commission_vault.to_account_info(),
+                authority: ctx.accounts.buyer.to_account_info(),
+            };
+            
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                transfer_instruction,
+            );
+            
+            token::transfer(cpi_ctx, commission_amount)?;
+        }
+        
+        // Update referral stats - referred volume (and so the tier) only counts direct referrals
+        let old_tier = affiliate_program.tier_for(referral_registry.referred_volume);
+        referral_registry.referred_volume = referral_registry.referred_volume
+            .checked_add(purchase_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        affiliate_program.total_commissions_paid = affiliate_program.total_commissions_paid
+            .checked_add(commission_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        
+        let new_tier = affiliate_program.tier_for(referral_registry.referred_volume);
+        if new_tier != old_tier {
+            emit!(ReferrerTierChangedEvent {
+                referrer: referral_registry.referrer,
+                referred_volume: referral_registry.referred_volume,
+                old_rate_bps: old_tier.rate_bps,
+                new_rate_bps: new_tier.rate_bps,
+                timestamp,
+            });
+        }
+        
+        msg!("Commission accrued: {} USDC ({} bps) for referrer {}", 
+             commission_amount, 
+             rate_bps,
+             ctx.accounts.referrer.key().to_string());
+        
+        Ok(commission_amount)
+    }
+
+    // Withdraw the referrer's vested commissions from the vault - everything accrued since the
+    // last claim in one transfer, or at most `max_amount` of it
+    pub fn claim_commissions(ctx: Context<ClaimCommissions>, max_amount: Option<u64>) -> Result<()> {
+        let referral_registry = &mut ctx.accounts.referral_registry;
+        let timestamp = Clock::get()?.unix_timestamp;
         
-        // Calculate commission amount (5% of purchase)
-        let commission_amount = (purchase_amount * commission_rate) / 10000;
+        referral_registry.vest(timestamp)?;
+        let amount = max_amount.map_or(referral_registry.claimable, |max_amount| max_amount.min(referral_registry.claimable));
+        require!(amount > 0, ErrorCode::NothingToClaim);
         
-        require!(commission_amount > 0, ErrorCode::InvalidCommissionAmount);
+        referral_registry.claimable -= amount;
+        referral_registry.total_claimed = referral_registry.total_claimed
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
         
-        // Transfer USDC commission to referrer
+        // The vault is owned by the AffiliateProgram PDA
+        let seeds = &[&b"affiliate_program"[..], &[ctx.bumps.affiliate_program]];
+        let signer = &[&seeds[..]];
         let transfer_instruction = Transfer {
-            from: ctx.accounts.buyer_usdc_account.to_account_info(),
+            from: ctx.accounts.commission_vault.to_account_info(),
             to: ctx.accounts.referrer_usdc_account.to_account_info(),
-            authority: ctx.accounts.buyer.to_account_info(),
+            authority: ctx.accounts.affiliate_program.to_account_info(),
         };
+        token::transfer(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), transfer_instruction, signer),
+            amount,
+        )?;
         
-        let cpi_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            transfer_instruction,
+        emit!(CommissionsClaimedEvent {
+            referrer: referral_registry.referrer,
+            amount,
+            still_vesting: referral_registry.vesting_amount,
+            timestamp,
+        });
+        Ok(())
+    }
+
+    // Replace the commission tiers - ordered by min_volume, the first starting at 0 (authority only)
+    pub fn set_commission_tiers(ctx: Context<UpdateCommissionConfig>, tiers: Vec<CommissionTier>) -> Result<()> {
+        require!(
+            !tiers.is_empty() && tiers.len() <= MAX_COMMISSION_TIERS && tiers[0].min_volume == 0,
+            ErrorCode::InvalidCommissionTiers
+        );
+        require!(
+            tiers.windows(2).all(|pair| pair[0].min_volume < pair[1].min_volume),
+            ErrorCode::InvalidCommissionTiers
+        );
+        require!(
+            tiers.iter().all(|tier| tier.rate_bps as u64 <= BASIS_POINTS),
+            ErrorCode::InvalidCommissionAmount
         );
         
-        token::transfer(cpi_ctx, commission_amount)?;
+        ctx.accounts.affiliate_program.commission_tiers = tiers.clone();
         
-        // Update referral stats
-        referral_registry.total_commission_earned += commission_amount;
-        affiliate_program.total_commissions_paid += commission_amount;
+        emit!(CommissionTiersUpdatedEvent {
+            tiers,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    // Replace the upline splits - one per level above the direct referrer, in basis points of
+    // the commission (authority only)
+    pub fn set_upline_splits(ctx: Context<UpdateCommissionConfig>, splits_bps: Vec<u16>) -> Result<()> {
+        require!(splits_bps.len() <= MAX_UPLINE_LEVELS, ErrorCode::InvalidUplineSplits);
+        require!(
+            splits_bps.iter().map(|split_bps| *split_bps as u64).sum::<u64>() <= BASIS_POINTS,
+            ErrorCode::InvalidUplineSplits
+        );
         
-        msg!("Commission paid: {} USDC to referrer {}", 
-             commission_amount, 
-             ctx.accounts.referrer.key().to_string());
+        ctx.accounts.affiliate_program.upline_splits_bps = splits_bps.clone();
         
+        emit!(UplineSplitsUpdatedEvent {
+            splits_bps,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
         Ok(())
     }
 
-    // Get referral history for an affiliate
-    pub fn get_referral_history(ctx: Context<GetReferralHistory>) -> Result<Vec<Pubkey>> {
-        let referral_registry = &ctx.accounts.referral_registry;
-        Ok(referral_registry.referred_wallets.clone())
+    // Set how long new commissions take to vest, in seconds - 0 makes them claimable at once
+    // (authority only)
+    pub fn set_vesting_period(ctx: Context<UpdateCommissionConfig>, vesting_period: i64) -> Result<()> {
+        require!(vesting_period >= 0, ErrorCode::InvalidVestingPeriod);
+        ctx.accounts.affiliate_program.vesting_period = vesting_period;
+        
+        emit!(VestingPeriodUpdatedEvent {
+            vesting_period,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
     }
+
+    // Replace the signers process_commission accepts - one per allowed DEX market, e.g. when a
+    // market opens or the DEX moves (authority only)
+    pub fn set_commission_authorities(ctx: Context<UpdateCommissionConfig>, commission_authorities: Vec<Pubkey>) -> Result<()> {
+        require!(commission_authorities.len() <= MAX_COMMISSION_AUTHORITIES, ErrorCode::TooManyCommissionAuthorities);
+        
+        ctx.accounts.affiliate_program.commission_authorities = commission_authorities.clone();
+        
+        emit!(CommissionAuthoritiesUpdatedEvent {
+            commission_authorities,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    // Pin a referrer to a fixed rate, or return them to the tiers with None (authority only)
+    pub fn set_referrer_rate_override(ctx: Context<SetReferrerRateOverride>, rate_bps: Option<u16>) -> Result<()> {
+        if let Some(rate_bps) = rate_bps {
+            require!(rate_bps as u64 <= BASIS_POINTS, ErrorCode::InvalidCommissionAmount);
+        }
+        
+        let referral_registry = &mut ctx.accounts.referral_registry;
+        referral_registry.rate_override_bps = rate_bps;
+        
+        emit!(ReferrerRateOverrideEvent {
+            referrer: referral_registry.referrer,
+            rate_bps,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+}
+
+// `bps` basis points of `amount`, rounded down
+fn share_of(amount: u64, bps: u16) -> Result<u64> {
+    u64::try_from(amount as u128 * bps as u128 / BASIS_POINTS as u128).map_err(|_| error!(ErrorCode::MathOverflow))
+}
+
+// The referrer recorded in `wallet`'s Referral, or None when the wallet was never referred
+fn referrer_of(referral_info: &AccountInfo, wallet: &Pubkey) -> Result<Option<Pubkey>> {
+    let (referral_address, _) = Pubkey::find_program_address(&[b"referral", wallet.as_ref()], &crate::ID);
+    require_keys_eq!(referral_info.key(), referral_address, ErrorCode::InvalidUplineAccount);
+    if referral_info.owner != &crate::ID || referral_info.data_is_empty() {
+        return Ok(None);
+    }
+    let referral = Referral::try_deserialize(&mut &referral_info.data.borrow()[..])?;
+    Ok(Some(referral.referrer))
 }
 
 #[derive(Accounts)]
@@ -96,30 +338,54 @@ pub struct Initialize<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + 32 + 32 + 8 + 8, // discriminator + authority + treasury + total_referrals + total_commissions
+        space = 8 + 32 + 32 + 8 + 8 + 4 + CommissionTier::SIZE * MAX_COMMISSION_TIERS + 4 + 2 * MAX_UPLINE_LEVELS + 8 + 4 + 32 * MAX_COMMISSION_AUTHORITIES, // discriminator + authority + treasury + total_referrals + total_commissions + tiers + upline splits + vesting_period + commission_authorities
         seeds = [b"affiliate_program"],
         bump
     )]
     pub affiliate_program: Account<'info, AffiliateProgram>,
     
+    // Holds accrued commissions until they are claimed
+    #[account(
+        init,
+        payer = authority,
+        token::mint = usdc_mint,
+        token::authority = affiliate_program,
+        seeds = [b"commission_vault"],
+        bump
+    )]
+    pub commission_vault: Account<'info, TokenAccount>,
+    
+    pub usdc_mint: Account<'info, Mint>,
+    
     #[account(mut)]
     pub authority: Signer<'info>,
     
     /// CHECK: Treasury wallet for fallback commissions
     pub treasury_wallet: UncheckedAccount<'info>,
     
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
 pub struct RegisterReferral<'info> {
-    #[account(mut)]
+    #[account(mut, seeds = [b"affiliate_program"], bump)]
     pub affiliate_program: Account<'info, AffiliateProgram>,
     
     #[account(
-        init,
+        init_if_needed,
         payer = referred_wallet,
-        space = 8 + 32 + 4 + (32 * 100) + 8, // discriminator + referrer + vec_len + wallets + total_commission
+        space = 8 + 32 + 8 + 1, // discriminator + referrer + created_at + bump
+        seeds = [b"referral", referred_wallet.key().as_ref()],
+        bump
+    )]
+    pub referral: Account<'info, Referral>,
+    
+    #[account(
+        init_if_needed,
+        payer = referred_wallet,
+        space = 8 + 32 + 8 + 8 + 8 + 3 + 8 + 8 + 8 + 8 + 8 + 1, // discriminator + referrer + total_referrals + total_commission_earned + referred_volume + rate_override_bps + claimable + vesting_amount + vesting_start + vesting_end + total_claimed + bump
         seeds = [b"referral_registry", referrer.key().as_ref()],
         bump
     )]
@@ -136,30 +402,92 @@ pub struct RegisterReferral<'info> {
 
 #[derive(Accounts)]
 pub struct ProcessCommission<'info> {
-    #[account(mut)]
+    #[account(mut, seeds = [b"affiliate_program"], bump)]
     pub affiliate_program: Account<'info, AffiliateProgram>,
     
-    #[account(mut)]
+    #[account(
+        seeds = [b"referral", buyer.key().as_ref()],
+        bump = referral.bump,
+        constraint = referral.referrer == referrer.key() @ ErrorCode::ReferralNotFound
+    )]
+    pub referral: Account<'info, Referral>,
+    
+    #[account(
+        mut,
+        seeds = [b"referral_registry", referrer.key().as_ref()],
+        bump = referral_registry.bump
+    )]
     pub referral_registry: Account<'info, ReferralRegistry>,
     
-    /// CHECK: The referrer wallet
+    /// CHECK: The buyer's referrer - must match the Referral
     pub referrer: UncheckedAccount<'info>,
     
-    /// CHECK: The buyer wallet
-    pub buyer: UncheckedAccount<'info>,
+    // The buyer pays the commission
+    pub buyer: Signer<'info>,
     
-    #[account(mut)]
+    #[account(mut, constraint = buyer_usdc_account.owner == buyer.key())]
     pub buyer_usdc_account: Account<'info, TokenAccount>,
     
-    #[account(mut)]
+    #[account(mut, seeds = [b"commission_vault"], bump)]
+    pub commission_vault: Account<'info, TokenAccount>,
+    
+    // An allowed DEX market's signer PDA - only those may report purchases
+    #[account(
+        constraint = affiliate_program.commission_authorities.contains(&commission_authority.key()) @ ErrorCode::Unauthorized
+    )]
+    pub commission_authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimCommissions<'info> {
+    #[account(seeds = [b"affiliate_program"], bump)]
+    pub affiliate_program: Account<'info, AffiliateProgram>,
+    
+    #[account(
+        mut,
+        seeds = [b"referral_registry", referrer.key().as_ref()],
+        bump = referral_registry.bump
+    )]
+    pub referral_registry: Account<'info, ReferralRegistry>,
+    
+    pub referrer: Signer<'info>,
+    
+    #[account(mut, seeds = [b"commission_vault"], bump)]
+    pub commission_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = referrer_usdc_account.owner == referrer.key(),
+        constraint = referrer_usdc_account.mint == commission_vault.mint
+    )]
     pub referrer_usdc_account: Account<'info, TokenAccount>,
     
     pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct GetReferralHistory<'info> {
+pub struct UpdateCommissionConfig<'info> {
+    #[account(mut, seeds = [b"affiliate_program"], bump, has_one = authority @ ErrorCode::Unauthorized)]
+    pub affiliate_program: Account<'info, AffiliateProgram>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetReferrerRateOverride<'info> {
+    #[account(seeds = [b"affiliate_program"], bump, has_one = authority @ ErrorCode::Unauthorized)]
+    pub affiliate_program: Account<'info, AffiliateProgram>,
+    
+    #[account(
+        mut,
+        seeds = [b"referral_registry", referral_registry.referrer.as_ref()],
+        bump = referral_registry.bump
+    )]
     pub referral_registry: Account<'info, ReferralRegistry>,
+    
+    pub authority: Signer<'info>,
 }
 
 #[account]
@@ -168,14 +496,161 @@ pub struct AffiliateProgram {
     pub treasury_wallet: Pubkey,
     pub total_referrals: u64,
     pub total_commissions_paid: u64,
+    pub commission_tiers: Vec<CommissionTier>, // Ordered by min_volume, the first at 0
+    pub upline_splits_bps: Vec<u16>, // Share of each commission for levels 2, 3, ... - the direct referrer keeps the rest
+    pub vesting_period: i64, // Seconds new commissions take to become claimable (0 = at once)
+    pub commission_authorities: Vec<Pubkey>, // Signers accepted by process_commission (allowed DEX markets' PDAs)
+}
+
+impl AffiliateProgram {
+    // The highest tier the referred volume has reached
+    pub fn tier_for(&self, referred_volume: u64) -> CommissionTier {
+        self.commission_tiers
+            .iter()
+            .rev()
+            .find(|tier| referred_volume >= tier.min_volume)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CommissionTier {
+    pub min_volume: u64, // Cumulative referred purchase volume (USDC) that unlocks the tier
+    pub rate_bps: u16, // Commission in basis points (500 = 5%)
 }
 
+impl CommissionTier {
+    pub const SIZE: usize = 8 + 2;
+}
+
+// One per referred wallet, seeded by it - a referrer's referrals can be listed with
+// getProgramAccounts filtered on `referrer`
+#[account]
+pub struct Referral {
+    pub referrer: Pubkey,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+// Per-referrer stats, seeded by the referrer
 #[account]
 pub struct ReferralRegistry {
     pub referrer: Pubkey,
-    pub referred_wallets: Vec<Pubkey>,
     pub total_referrals: u64,
     pub total_commission_earned: u64,
+    pub referred_volume: u64, // Purchases commissioned so far - selects the tier
+    pub rate_override_bps: Option<u16>, // Set by the authority - replaces the tier rate
+    pub claimable: u64, // Vested commissions waiting in the vault
+    pub vesting_amount: u64, // Commissions still vesting, released linearly until vesting_end
+    pub vesting_start: i64,
+    pub vesting_end: i64,
+    pub total_claimed: u64,
+    pub bump: u8,
+}
+
+impl ReferralRegistry {
+    pub fn commission_rate_bps(&self, affiliate_program: &AffiliateProgram) -> u16 {
+        self.rate_override_bps
+            .unwrap_or_else(|| affiliate_program.tier_for(self.referred_volume).rate_bps)
+    }
+    
+    // Release the vested part of vesting_amount into claimable
+    pub fn vest(&mut self, timestamp: i64) -> Result<()> {
+        if self.vesting_amount == 0 || timestamp <= self.vesting_start {
+            return Ok(());
+        }
+        let released = if timestamp >= self.vesting_end {
+            self.vesting_amount
+        } else {
+            let elapsed = (timestamp - self.vesting_start) as u128;
+            let remaining_window = (self.vesting_end - self.vesting_start) as u128;
+            (self.vesting_amount as u128 * elapsed / remaining_window) as u64
+        };
+        self.claimable = self.claimable.checked_add(released).ok_or(ErrorCode::MathOverflow)?;
+        self.vesting_amount -= released;
+        self.vesting_start = timestamp;
+        Ok(())
+    }
+    
+    // Credit a commission. What has vested is released first; the rest still vesting and the new
+    // commission then share one window, ending at their amount-weighted average unlock time, so
+    // earlier commissions are neither released early nor pushed back a whole vesting period
+    pub fn accrue(&mut self, amount: u64, timestamp: i64, vesting_period: i64) -> Result<()> {
+        self.vest(timestamp)?;
+        self.total_commission_earned = self.total_commission_earned
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if vesting_period == 0 {
+            self.claimable = self.claimable.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        } else if amount > 0 {
+            let outstanding = self.vesting_amount as u128;
+            let outstanding_window = self.vesting_end.saturating_sub(timestamp).max(0) as u128;
+            let total = outstanding + amount as u128;
+            let window = (outstanding * outstanding_window + amount as u128 * vesting_period as u128) / total;
+            self.vesting_amount = self.vesting_amount.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+            self.vesting_start = timestamp;
+            self.vesting_end = timestamp.checked_add(window as i64).ok_or(ErrorCode::MathOverflow)?;
+        }
+        Ok(())
+    }
+}
+
+#[event]
+pub struct CommissionTiersUpdatedEvent {
+    pub tiers: Vec<CommissionTier>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UplineSplitsUpdatedEvent {
+    pub splits_bps: Vec<u16>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CommissionAccruedEvent {
+    pub buyer: Pubkey,
+    pub referrer: Pubkey,
+    pub level: u8, // 1 = the buyer's own referrer
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CommissionsClaimedEvent {
+    pub referrer: Pubkey,
+    pub amount: u64,
+    pub still_vesting: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestingPeriodUpdatedEvent {
+    pub vesting_period: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CommissionAuthoritiesUpdatedEvent {
+    pub commission_authorities: Vec<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferrerRateOverrideEvent {
+    pub referrer: Pubkey,
+    pub rate_bps: Option<u16>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferrerTierChangedEvent {
+    pub referrer: Pubkey,
+    pub referred_volume: u64,
+    pub old_rate_bps: u16,
+    pub new_rate_bps: u16,
+    pub timestamp: i64,
 }
 
 #[error_code]
@@ -186,4 +661,119 @@ pub enum ErrorCode {
     InvalidCommissionAmount,
     #[msg("Referral not found")]
     ReferralNotFound,
+    #[msg("Referrer does not match the referrer account")]
+    InvalidReferrer,
+    #[msg("A wallet cannot refer itself")]
+    SelfReferral,
+    #[msg("Commission tiers must start at 0 and rise strictly by min_volume")]
+    InvalidCommissionTiers,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Upline splits exceed MAX_UPLINE_LEVELS or 100% of the commission")]
+    InvalidUplineSplits,
+    #[msg("Remaining accounts end before the referral chain does")]
+    UplineAccountsMissing,
+    #[msg("Upline account does not belong to the next referrer in the chain")]
+    InvalidUplineAccount,
+    #[msg("Referral would create a cycle")]
+    ReferralCycle,
+    #[msg("Vesting period cannot be negative")]
+    InvalidVestingPeriod,
+    #[msg("No vested commissions to claim")]
+    NothingToClaim,
+    #[msg("More commission authorities than MAX_COMMISSION_AUTHORITIES")]
+    TooManyCommissionAuthorities,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERIOD: i64 = 1_000;
+
+    fn registry() -> ReferralRegistry {
+        ReferralRegistry {
+            referrer: Pubkey::new_unique(),
+            total_referrals: 1,
+            total_commission_earned: 0,
+            referred_volume: 0,
+            rate_override_bps: None,
+            claimable: 0,
+            vesting_amount: 0,
+            vesting_start: 0,
+            vesting_end: 0,
+            total_claimed: 0,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn commissions_are_claimable_at_once_without_vesting() {
+        let mut registry = registry();
+        registry.accrue(1_000, 100, 0).unwrap();
+        assert_eq!(registry.claimable, 1_000);
+        assert_eq!(registry.vesting_amount, 0);
+        assert_eq!(registry.total_commission_earned, 1_000);
+    }
+
+    #[test]
+    fn commissions_vest_linearly_over_the_period() {
+        let mut registry = registry();
+        registry.accrue(1_000, 0, PERIOD).unwrap();
+        assert_eq!(registry.claimable, 0);
+        assert_eq!(registry.vesting_end, PERIOD);
+
+        registry.vest(PERIOD / 4).unwrap();
+        assert_eq!(registry.claimable, 250);
+        assert_eq!(registry.vesting_amount, 750);
+
+        registry.vest(PERIOD / 2).unwrap();
+        assert_eq!(registry.claimable, 500);
+
+        registry.vest(PERIOD * 2).unwrap();
+        assert_eq!(registry.claimable, 1_000);
+        assert_eq!(registry.vesting_amount, 0);
+    }
+
+    #[test]
+    fn new_commission_keeps_vested_progress() {
+        let mut registry = registry();
+        registry.accrue(1_000, 0, PERIOD).unwrap();
+        registry.accrue(1_000, PERIOD / 2, PERIOD).unwrap();
+
+        // Half of the first commission vested and stays released
+        assert_eq!(registry.claimable, 500);
+        assert_eq!(registry.vesting_amount, 1_500);
+        // 500 due in 500s and 1,000 due in 1,000s average out to 833s from now
+        assert_eq!(registry.vesting_start, PERIOD / 2);
+        assert_eq!(registry.vesting_end, PERIOD / 2 + 833);
+
+        registry.vest(PERIOD / 2 + 833).unwrap();
+        assert_eq!(registry.claimable, 2_000);
+        assert_eq!(registry.total_commission_earned, 2_000);
+    }
+
+    #[test]
+    fn commission_after_full_vesting_gets_a_full_period() {
+        let mut registry = registry();
+        registry.accrue(1_000, 0, PERIOD).unwrap();
+        registry.accrue(400, PERIOD * 3, PERIOD).unwrap();
+
+        assert_eq!(registry.claimable, 1_000);
+        assert_eq!(registry.vesting_amount, 400);
+        assert_eq!(registry.vesting_end, PERIOD * 4);
+    }
+
+    #[test]
+    fn zero_commission_leaves_the_window_alone() {
+        let mut registry = registry();
+        registry.accrue(1_000, 0, PERIOD).unwrap();
+        registry.accrue(0, PERIOD / 2, PERIOD).unwrap();
+
+        assert_eq!(registry.claimable, 500);
+        assert_eq!(registry.vesting_amount, 500);
+        assert_eq!(registry.vesting_end, PERIOD);
+    }
 }
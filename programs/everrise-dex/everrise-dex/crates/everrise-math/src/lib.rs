@@ -0,0 +1,476 @@
+//! EverRise bonding curve math.
+//!
+//! This is the single source of truth for the curve calculations used by the
+//! `everrise_dex` program. Off-chain clients (backend, keeper bots) should quote
+//! with these functions so their numbers round exactly like the program does.
+//!
+//! Every change to X, Y, K and the circulating supply is one of the supply
+//! transitions on [`Curve`], so that SC + Y + escrowed EVER always equals the
+//! EVER mint's supply.
+//!
+//! Units follow the program: USDC amounts have 6 decimals, EVER amounts have
+//! 9 decimals and prices are USDC base units per whole EVER (X * 10^9 / Y).
+#![no_std]
+
+/// 10^9 - EVER base units per whole token, also the price scale
+pub const EVER_UNIT: u64 = 1_000_000_000;
+/// 1.0 in the 18-decimal fixed point used for daily growth factors
+pub const GROWTH_SCALE: u128 = 1_000_000_000_000_000_000;
+/// 100% = 10,000 basis points
+pub const BASIS_POINTS: u128 = 10_000;
+/// Seconds in a day for the daily boost
+pub const SECONDS_PER_DAY: i64 = 86_400;
+/// 10^6 - USDC base units per whole USDC
+pub const QUOTE_UNIT: u64 = 1_000_000;
+/// The 0.001 appreciation factor as 1 / APPRECIATION_DIVISOR
+pub const APPRECIATION_DIVISOR: u64 = 1_000;
+/// Scales (0.001 × V) / ((X/Y) × SC) from base units into price units:
+/// QUOTE_UNIT (bonus in USDC base units) × EVER_UNIT (SC in base units) / APPRECIATION_DIVISOR
+pub const APPRECIATION_SCALE: u128 = QUOTE_UNIT as u128 * EVER_UNIT as u128 / APPRECIATION_DIVISOR as u128;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MathError {
+    /// An intermediate or result did not fit, or a divisor was zero
+    Overflow,
+}
+
+pub type Result<T> = core::result::Result<T, MathError>;
+
+/// Snapshot of the bonding curve state the math depends on
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Curve {
+    pub x: u64, // USDC in treasury
+    pub y: u64, // EVER in reserve
+    pub k: u128, // K = X * Y
+    pub cumulative_bonus: u64, // Sum of all historical bonuses
+    pub circulating_supply: u64, // SC - EVER held outside the program (not in Y or sell-order escrow)
+    pub current_price: u64, // Current locked price
+    pub daily_growth_bps: u16, // Guaranteed daily price growth (2 = 0.02%)
+    pub day_start_price: u64, // P_start - effective price at T_start
+    pub day_start_ts: i64, // T_start - midnight UTC of the current day
+}
+
+/// Settlement of the days closed since T_start
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DailyBoost {
+    pub organic_price: u64, // Effective price before the boost
+    pub minimum_price: u64, // P_start * (1 + g)^days_passed
+    pub boosted_price: u64, // max(organic_price, minimum_price), the new P_start
+    pub bonus_added: u64, // Added to cumulative_bonus
+    pub days_passed: i64, // UTC days closed since T_start
+    pub day_start_ts: i64, // The new T_start
+}
+
+/// A queued sell order as seen by the fill simulation
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SellOrderQuote {
+    pub remaining_amount: u64, // EVER still for sale
+    pub locked_price: u64,
+}
+
+/// Result of filling a buy against one sell order
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OrderFill {
+    pub ever_amount: u64,
+    pub usdc_amount: u64,
+}
+
+/// Result of filling a buy FIFO against a list of sell orders
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FillSimulation {
+    pub ever_filled: u64,
+    pub usdc_spent: u64,
+    pub appreciation_bonus: u64,
+    pub orders_consumed: usize, // Orders fully filled (the queue head advances past these)
+    pub remaining_usdc: u64, // Left over for the reserves
+}
+
+impl Curve {
+    /// Organic price X / Y, scaled to USDC base units per whole EVER (0 if undefined)
+    pub fn organic_price(&self) -> u64 {
+        if self.y == 0 {
+            return 0;
+        }
+
+        // u128 intermediate so reserves above ~18,446 USDC don't overflow
+        let price = (self.x as u128)
+            .checked_mul(EVER_UNIT as u128)
+            .unwrap_or(0)
+            .checked_div(self.y as u128)
+            .unwrap_or(0);
+        u64::try_from(price).unwrap_or(0)
+    }
+
+    /// Organic price plus the cumulative bonus from queue fills and daily boosts
+    pub fn effective_price(&self) -> u64 {
+        let organic_price = self.organic_price();
+        organic_price
+            .checked_add(self.cumulative_bonus)
+            .unwrap_or(organic_price)
+    }
+
+    /// EVER received for `usdc_amount` from the reserves (X * Y = K)
+    pub fn buy_quote(&self, usdc_amount: u64) -> Result<u64> {
+        let new_x = self.x.checked_add(usdc_amount).ok_or(MathError::Overflow)?;
+        let new_y = u64::try_from(self.k.checked_div(new_x as u128).ok_or(MathError::Overflow)?)
+            .map_err(|_| MathError::Overflow)?;
+        self.y.checked_sub(new_y).ok_or(MathError::Overflow)
+    }
+
+    /// Locked price and USDC value for selling `ever_amount` at the current effective price
+    pub fn sell_quote(&self, ever_amount: u64) -> Result<(u64, u64)> {
+        let locked_price = self.effective_price();
+        Ok((locked_price, usdc_value(ever_amount, locked_price)?))
+    }
+
+    /// Minimum price guaranteed `days_passed` days after T_start (P_start compounded daily)
+    pub fn minimum_daily_price(&self, days_passed: i64) -> Result<u64> {
+        if days_passed <= 0 || self.day_start_price == 0 {
+            return Ok(self.day_start_price);
+        }
+
+        // Compound growth: P_start * (1 + g)^days, rounded down
+        let growth_factor = compound_growth_factor(self.daily_growth_bps, days_passed as u64)?;
+        u64::try_from(mul_div(self.day_start_price as u128, growth_factor, GROWTH_SCALE)?)
+            .map_err(|_| MathError::Overflow)
+    }
+
+    /// Daily boost due at `current_timestamp`, or None while still inside the day at T_start.
+    /// Every UTC day closed since T_start must have grown the price by at least g, so the
+    /// effective price is lifted to P_start * (1 + g)^days when organic growth fell short.
+    /// Missed days are caught up in one step - only the elapsed day count matters.
+    pub fn daily_boost(&self, current_timestamp: i64) -> Result<Option<DailyBoost>> {
+        let today = day_start(current_timestamp);
+        if today <= self.day_start_ts {
+            return Ok(None);
+        }
+
+        let days_passed = (today - self.day_start_ts) / SECONDS_PER_DAY;
+        let organic_price = self.effective_price();
+        let minimum_price = self.minimum_daily_price(days_passed)?;
+        let boosted_price = organic_price.max(minimum_price);
+
+        Ok(Some(DailyBoost {
+            organic_price,
+            minimum_price,
+            boosted_price,
+            bonus_added: boosted_price - organic_price,
+            days_passed,
+            day_start_ts: today,
+        }))
+    }
+
+    /// Apply the daily boost due at `current_timestamp` to this snapshot
+    /// (what `apply_daily_boost` does on-chain) and roll P_start / T_start to today
+    pub fn apply_daily_boost(&mut self, current_timestamp: i64) -> Result<Option<DailyBoost>> {
+        let Some(boost) = self.daily_boost(current_timestamp)? else {
+            return Ok(None);
+        };
+        self.cumulative_bonus = self
+            .cumulative_bonus
+            .checked_add(boost.bonus_added)
+            .ok_or(MathError::Overflow)?;
+        self.current_price = boost.boosted_price;
+        self.day_start_price = boost.boosted_price;
+        self.day_start_ts = boost.day_start_ts;
+        Ok(Some(boost))
+    }
+
+    /// Effective price at `timestamp`, assuming no trades until then
+    pub fn projected_price(&self, timestamp: i64) -> Result<u64> {
+        let mut curve = *self;
+        curve.apply_daily_boost(timestamp)?;
+        Ok(curve.effective_price())
+    }
+
+    /// Reserve buy: `usdc_amount` joins X and `ever_amount` leaves Y for the buyer
+    pub fn reserve_buy(&mut self, usdc_amount: u64, ever_amount: u64) -> Result<()> {
+        let x = self.x.checked_add(usdc_amount).ok_or(MathError::Overflow)?;
+        let y = self.y.checked_sub(ever_amount).ok_or(MathError::Overflow)?;
+        let circulating_supply = self.circulating_supply.checked_add(ever_amount).ok_or(MathError::Overflow)?;
+        self.set_reserves(x, y);
+        self.circulating_supply = circulating_supply;
+        Ok(())
+    }
+
+    /// Sell order placed: the seller's EVER leaves circulation for escrow
+    pub fn escrow_sell(&mut self, ever_amount: u64) -> Result<()> {
+        self.circulating_supply = self.circulating_supply.checked_sub(ever_amount).ok_or(MathError::Overflow)?;
+        Ok(())
+    }
+
+    /// Escrowed EVER returns to circulation - bought off the queue or handed back on cancel
+    pub fn release_escrow(&mut self, ever_amount: u64) -> Result<()> {
+        self.circulating_supply = self.circulating_supply.checked_add(ever_amount).ok_or(MathError::Overflow)?;
+        Ok(())
+    }
+
+    /// Escrowed EVER sold to the reserves: `usdc_amount` leaves X, and the EVER joins Y
+    /// when `keep_as_reserve`, otherwise it is burned. SC is unchanged either way.
+    pub fn reserve_sell(&mut self, usdc_amount: u64, ever_amount: u64, keep_as_reserve: bool) -> Result<()> {
+        let x = self.x.checked_sub(usdc_amount).ok_or(MathError::Overflow)?;
+        let y = if keep_as_reserve {
+            self.y.checked_add(ever_amount).ok_or(MathError::Overflow)?
+        } else {
+            self.y
+        };
+        self.set_reserves(x, y);
+        Ok(())
+    }
+
+    /// Move to new reserves, re-deriving K = X * Y (a u64 product always fits in a u128)
+    fn set_reserves(&mut self, x: u64, y: u64) {
+        self.k = (x as u128) * (y as u128);
+        self.x = x;
+        self.y = y;
+    }
+}
+
+/// (1 + growth_bps / 10,000)^days in GROWTH_SCALE fixed point, by exponentiation by squaring.
+/// Every multiply rounds down, so the factor never exceeds the exact value.
+pub fn compound_growth_factor(growth_bps: u16, days: u64) -> Result<u128> {
+    let mut base = GROWTH_SCALE + (growth_bps as u128) * (GROWTH_SCALE / BASIS_POINTS);
+    let mut factor = GROWTH_SCALE;
+    let mut exponent = days;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            factor = mul_div(factor, base, GROWTH_SCALE)?;
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = mul_div(base, base, GROWTH_SCALE)?;
+        }
+    }
+
+    Ok(factor)
+}
+
+/// floor(a * b / denominator) with a 256-bit intermediate product
+pub fn mul_div(a: u128, b: u128, denominator: u128) -> Result<u128> {
+    if denominator == 0 {
+        return Err(MathError::Overflow);
+    }
+    // Fast path - the product fits in 128 bits
+    if let Some(product) = a.checked_mul(b) {
+        return Ok(product / denominator);
+    }
+
+    let (hi, lo) = widening_mul(a, b);
+    if hi >= denominator {
+        return Err(MathError::Overflow); // Quotient would not fit in 128 bits
+    }
+
+    // Shift-subtract long division of (hi, lo) by denominator; hi stays < denominator
+    let mut remainder = hi;
+    let mut quotient = 0u128;
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((lo >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= denominator {
+            remainder = remainder.wrapping_sub(denominator);
+            quotient |= 1;
+        }
+    }
+    Ok(quotient)
+}
+
+/// Full 256-bit product of two u128 values as (high, low) halves
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    let cross = (lo_lo >> 64) + (hi_lo & MASK) + (lo_hi & MASK);
+    let lo = (cross << 64) | (lo_lo & MASK);
+    let hi = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (cross >> 64);
+    (hi, lo)
+}
+
+/// Midnight UTC at or before `timestamp` (T_start of the day containing it)
+pub fn day_start(timestamp: i64) -> i64 {
+    timestamp - timestamp.rem_euclid(SECONDS_PER_DAY)
+}
+
+/// USDC value of `ever_amount` at `price` (9 -> 6 decimals, rounded down)
+pub fn usdc_value(ever_amount: u64, price: u64) -> Result<u64> {
+    u64::try_from((ever_amount as u128) * (price as u128) / EVER_UNIT as u128)
+        .map_err(|_| MathError::Overflow)
+}
+
+/// Appreciation bonus for a queue fill, in price units
+/// Formula: (0.001 × V) / ((X/Y) × SC)
+/// Where V = USDC volume of the fill, X/Y = organic price at the time of the fill, SC = circulating supply.
+/// No bonus accrues while the organic price or the circulating supply is zero.
+pub fn appreciation_bonus(transaction_volume: u64, organic_price: u64, circulating_supply: u64) -> Result<u64> {
+    if organic_price == 0 || circulating_supply == 0 {
+        return Ok(0);
+    }
+
+    let numerator = (transaction_volume as u128) * APPRECIATION_SCALE;
+    let denominator = (organic_price as u128) * (circulating_supply as u128);
+    u64::try_from(numerator / denominator).map_err(|_| MathError::Overflow)
+}
+
+/// Fill up to `usdc_amount` against one sell order at its locked price.
+/// Takes the whole order when affordable, otherwise spends all the USDC on a partial fill.
+pub fn fill_sell_order(order: &SellOrderQuote, usdc_amount: u64) -> Result<OrderFill> {
+    // USDC needed to take the whole remaining order at its locked price (9 -> 6 decimals)
+    let usdc_for_order = usdc_value(order.remaining_amount, order.locked_price)?;
+    if usdc_for_order <= usdc_amount {
+        return Ok(OrderFill {
+            ever_amount: order.remaining_amount,
+            usdc_amount: usdc_for_order,
+        });
+    }
+
+    let ever_for_partial = u64::try_from(
+        (usdc_amount as u128)
+            .checked_mul(EVER_UNIT as u128)
+            .ok_or(MathError::Overflow)?
+            .checked_div(order.locked_price as u128)
+            .ok_or(MathError::Overflow)?,
+    )
+    .map_err(|_| MathError::Overflow)?;
+    Ok(OrderFill {
+        ever_amount: ever_for_partial.min(order.remaining_amount),
+        usdc_amount,
+    })
+}
+
+/// Simulate a buy of `usdc_amount` filled FIFO against `orders` (queue head first),
+/// stopping like the program does at the first empty, unfillable or partially filled order.
+/// Queue fills leave X and Y alone, so every fill earns its bonus at the curve's organic price,
+/// and the program releases the filled EVER into circulation only after the last fill.
+pub fn simulate_fill(curve: &Curve, orders: &[SellOrderQuote], usdc_amount: u64) -> Result<FillSimulation> {
+    let organic_price = curve.organic_price();
+    let mut simulation = FillSimulation {
+        remaining_usdc: usdc_amount,
+        ..FillSimulation::default()
+    };
+
+    for order in orders {
+        if simulation.remaining_usdc == 0 || order.remaining_amount == 0 {
+            break;
+        }
+
+        let fill = fill_sell_order(order, simulation.remaining_usdc)?;
+        if fill.ever_amount == 0 {
+            break;
+        }
+
+        simulation.remaining_usdc -= fill.usdc_amount;
+        simulation.ever_filled = simulation.ever_filled.checked_add(fill.ever_amount).ok_or(MathError::Overflow)?;
+        simulation.usdc_spent = simulation.usdc_spent.checked_add(fill.usdc_amount).ok_or(MathError::Overflow)?;
+        simulation.appreciation_bonus = simulation
+            .appreciation_bonus
+            .checked_add(appreciation_bonus(fill.usdc_amount, organic_price, curve.circulating_supply)?)
+            .ok_or(MathError::Overflow)?;
+
+        // A partial fill means the buy is exhausted
+        if fill.ever_amount < order.remaining_amount {
+            break;
+        }
+        simulation.orders_consumed += 1;
+    }
+
+    Ok(simulation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2026-01-01T00:00:00Z
+    const MIDNIGHT: i64 = 1_767_225_600;
+
+    // Mainnet launch parameters: 10,000 USDC / 100,000,000 EVER
+    fn launch_curve() -> Curve {
+        Curve {
+            x: 10_000_000_000,
+            y: 100_000_000_000_000_000,
+            k: 10_000_000_000u128 * 100_000_000_000_000_000u128,
+            cumulative_bonus: 0,
+            circulating_supply: 0,
+            current_price: 100,
+            daily_growth_bps: 2,
+            day_start_price: 100,
+            day_start_ts: MIDNIGHT,
+        }
+    }
+
+    #[test]
+    fn launch_price_and_buy_quote() {
+        let curve = launch_curve();
+        assert_eq!(curve.organic_price(), 100);
+        // 10,000 USDC doubles X, so half of Y comes out
+        assert_eq!(curve.buy_quote(10_000_000_000).unwrap(), 50_000_000_000_000_000);
+    }
+
+    #[test]
+    fn daily_boost_lifts_to_minimum_price() {
+        let mut curve = launch_curve();
+        curve.day_start_price = 1_000_000;
+        let boost = curve.apply_daily_boost(MIDNIGHT + 10 * SECONDS_PER_DAY + 5).unwrap().unwrap();
+        assert_eq!(boost.days_passed, 10);
+        assert_eq!(boost.minimum_price, 1_002_001); // 1.0002^10 = 1.0020018...
+        assert_eq!(boost.bonus_added, 1_002_001 - boost.organic_price);
+        assert_eq!(curve.effective_price(), 1_002_001);
+        assert_eq!(curve.day_start_price, 1_002_001);
+        assert_eq!(curve.day_start_ts, MIDNIGHT + 10 * SECONDS_PER_DAY);
+    }
+
+    #[test]
+    fn daily_boost_waits_for_utc_midnight() {
+        let mut curve = launch_curve();
+        curve.day_start_price = 1_000_000;
+        // 23:59:59 on the same day - nothing to settle yet
+        assert_eq!(curve.apply_daily_boost(MIDNIGHT + SECONDS_PER_DAY - 1).unwrap(), None);
+        // One second later the day closes regardless of when the last boost ran
+        assert_eq!(curve.apply_daily_boost(MIDNIGHT + SECONDS_PER_DAY).unwrap().unwrap().days_passed, 1);
+    }
+
+    #[test]
+    fn catch_up_matches_daily_settlement() {
+        let mut daily = launch_curve();
+        daily.day_start_price = 1_000_000;
+        let mut once = daily;
+        for day in 1..=30 {
+            daily.apply_daily_boost(MIDNIGHT + day * SECONDS_PER_DAY + 3_600).unwrap();
+        }
+        once.apply_daily_boost(MIDNIGHT + 30 * SECONDS_PER_DAY + 7_200).unwrap();
+        // Daily settlement rounds down every day, so it can trail the one-step catch-up slightly
+        assert!(once.effective_price() - daily.effective_price() <= 30);
+        assert_eq!(once.day_start_ts, daily.day_start_ts);
+    }
+
+    #[test]
+    fn appreciation_bonus_follows_spec_formula() {
+        // 0.001 × 1,000 USDC / (0.0001 USDC × 100,000,000 EVER) = 0.0001 USDC per EVER = 100 price units
+        assert_eq!(appreciation_bonus(1_000_000_000, 100, 100_000_000_000_000_000).unwrap(), 100);
+        // 0.001 × 10 USDC / (0.001 USDC × 1,000,000,000 EVER) = 0.00000001 USDC, below one price unit
+        assert_eq!(appreciation_bonus(10_000_000, 1_000, 1_000_000_000_000_000_000).unwrap(), 0);
+        assert_eq!(appreciation_bonus(1_000_000_000, 100, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn simulate_fill_stops_on_partial_fill() {
+        let orders = [
+            SellOrderQuote { remaining_amount: 1_000_000_000, locked_price: 1_000_000 }, // 1 EVER at 1 USDC
+            SellOrderQuote { remaining_amount: 2_000_000_000, locked_price: 2_000_000 }, // 2 EVER at 2 USDC
+            SellOrderQuote { remaining_amount: 1_000_000_000, locked_price: 1_000_000 },
+        ];
+        let simulation = simulate_fill(&launch_curve(), &orders, 3_000_000).unwrap();
+        assert_eq!(simulation.orders_consumed, 1);
+        assert_eq!(simulation.appreciation_bonus, 0); // Nothing in circulation yet
+        assert_eq!(simulation.ever_filled, 2_000_000_000);
+        assert_eq!(simulation.usdc_spent, 3_000_000);
+        assert_eq!(simulation.remaining_usdc, 0);
+    }
+}
@@ -0,0 +1,78 @@
+//! Property tests for compound daily growth against an exact big-integer reference.
+
+use everrise_math::{compound_growth_factor, mul_div, Curve, GROWTH_SCALE};
+use num_bigint::BigUint;
+use proptest::prelude::*;
+
+/// Exact floor(price * ((10,000 + bps) / 10,000)^days)
+fn reference_price(price: u64, growth_bps: u16, days: u64) -> BigUint {
+    let numerator = BigUint::from(10_000u32 + growth_bps as u32).pow(days as u32);
+    let denominator = BigUint::from(10_000u32).pow(days as u32);
+    BigUint::from(price) * numerator / denominator
+}
+
+fn curve(day_start_price: u64, daily_growth_bps: u16) -> Curve {
+    Curve {
+        daily_growth_bps,
+        day_start_price,
+        ..Curve::default()
+    }
+}
+
+proptest! {
+    // Up to ten years of idle days at the launch growth rate
+    #[test]
+    fn matches_reference_over_multi_year_gaps(price in 1u64..1_000_000_000_000, days in 1u64..3_650) {
+        let exact = reference_price(price, 2, days);
+        let actual = BigUint::from(curve(price, 2).minimum_daily_price(days as i64).unwrap());
+
+        // Never over-delivers, and rounding loses at most one base unit
+        prop_assert!(actual <= exact);
+        prop_assert!(&exact - &actual <= BigUint::from(1u32));
+    }
+
+    #[test]
+    fn matches_reference_across_growth_rates(price in 1u64..1_000_000_000, growth_bps in 0u16..=100, days in 1u64..730) {
+        let exact = reference_price(price, growth_bps, days);
+        match curve(price, growth_bps).minimum_daily_price(days as i64) {
+            Ok(actual) => {
+                let actual = BigUint::from(actual);
+                prop_assert!(actual <= exact);
+                prop_assert!(&exact - &actual <= BigUint::from(1u32));
+            }
+            // Only acceptable when the exact price does not fit in a u64
+            Err(_) => prop_assert!(exact > BigUint::from(u64::MAX)),
+        }
+    }
+
+    #[test]
+    fn growth_is_monotonic_in_days(price in 1u64..1_000_000_000_000, days in 0i64..3_650) {
+        let curve = curve(price, 2);
+        prop_assert!(curve.minimum_daily_price(days + 1).unwrap() >= curve.minimum_daily_price(days).unwrap());
+    }
+
+    #[test]
+    fn mul_div_matches_big_integers(a in any::<u128>(), b in any::<u128>(), denominator in 1u128..) {
+        let exact = BigUint::from(a) * BigUint::from(b) / BigUint::from(denominator);
+        match mul_div(a, b, denominator) {
+            Ok(actual) => prop_assert_eq!(BigUint::from(actual), exact),
+            Err(_) => prop_assert!(exact > BigUint::from(u128::MAX)),
+        }
+    }
+}
+
+#[test]
+fn compounding_beats_linear_approximation() {
+    // 1.0002^3650 = 2.0749..., the old linear 1 + 0.0002 * 3650 gave 1.73
+    let factor = compound_growth_factor(2, 3_650).unwrap();
+    assert!(factor > GROWTH_SCALE * 2);
+    assert_eq!(curve(1_000_000, 2).minimum_daily_price(3_650).unwrap(), 2_074_929);
+}
+
+#[test]
+fn steepest_allowed_rate_survives_ten_idle_years() {
+    // 1% a day (MAX_DAILY_GROWTH_BPS) from the launch price of 100 still fits after 3,650 days
+    assert!(curve(100, 100).minimum_daily_price(3_650).is_ok());
+    // while 100% a day overflows within months
+    assert!(curve(100, 10_000).minimum_daily_price(90).is_err());
+}
@@ -0,0 +1,143 @@
+//! Property tests for supply accounting: however trades interleave, the circulating
+//! supply plus reserve holdings plus escrowed EVER must reconcile with the mint's supply.
+//!
+//! Scope: the model drives the same `Curve` transitions as the program's transition.rs and
+//! applies the program's guards on top - a reserve buy must fit in the unescrowed reserve,
+//! a direct sale is capped at what X can pay, and an operation whose arithmetic the program
+//! rejects is skipped, as the transaction would fail. Token transfers, order linking, bonuses
+//! and daily boosts are not modelled; on-chain, verify_invariants checks the same
+//! reconciliation against the real balances (the TS suite runs it after process_buy_queue).
+
+use everrise_math::{Curve, EVER_UNIT};
+use proptest::prelude::*;
+
+/// Token balances the program would see, tracked independently of the curve
+#[derive(Debug)]
+struct Ledger {
+    curve: Curve,
+    mint_supply: u64, // EVER mint supply (burns shrink it)
+    holders: u64, // EVER in user wallets
+    escrow: u64, // EVER held for open sell orders
+}
+
+#[derive(Clone, Debug)]
+enum Op {
+    ReserveBuy { usdc_amount: u64 }, // buy, buy_smart or process_buy_queue from reserves
+    Sell { percent: u64 }, // sell - of all wallet EVER
+    QueueFill { percent: u64 }, // buy_smart / process_buy_queue fill - of escrow
+    Cancel { percent: u64 }, // cancel_sell_order - of escrow
+    ReserveSell { percent: u64, keep_as_reserve: bool }, // process_sell_queue direct sale - of escrow
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (1u64..50_000_000_000).prop_map(|usdc_amount| Op::ReserveBuy { usdc_amount }),
+        (1u64..=100).prop_map(|percent| Op::Sell { percent }),
+        (1u64..=100).prop_map(|percent| Op::QueueFill { percent }),
+        (1u64..=100).prop_map(|percent| Op::Cancel { percent }),
+        (1u64..=100, any::<bool>()).prop_map(|(percent, keep_as_reserve)| Op::ReserveSell { percent, keep_as_reserve }),
+    ]
+}
+
+impl Ledger {
+    // Mainnet launch reserves, with `premint` EVER already in wallets when the market opens
+    fn launch(premint: u64) -> Self {
+        let (x, y) = (10_000_000_000u64, 100_000_000_000_000_000u64);
+        Ledger {
+            curve: Curve {
+                x,
+                y,
+                k: x as u128 * y as u128,
+                circulating_supply: premint,
+                ..Curve::default()
+            },
+            mint_supply: y + premint,
+            holders: premint,
+            escrow: 0,
+        }
+    }
+
+    fn apply(&mut self, op: &Op) {
+        match *op {
+            Op::ReserveBuy { usdc_amount } => {
+                let Ok(ever_amount) = self.curve.buy_quote(usdc_amount) else {
+                    return;
+                };
+                // The vault holds Y plus the escrow, and only Y may be sold (InsufficientLiquidity)
+                if ever_amount > self.reserve_vault() - self.escrow {
+                    return;
+                }
+                self.curve.reserve_buy(usdc_amount, ever_amount).unwrap();
+                self.holders += ever_amount;
+            }
+            Op::Sell { percent } => {
+                let ever_amount = self.holders * percent / 100;
+                self.curve.escrow_sell(ever_amount).unwrap();
+                self.holders -= ever_amount;
+                self.escrow += ever_amount;
+            }
+            Op::QueueFill { percent } | Op::Cancel { percent } => {
+                let ever_amount = self.escrow * percent / 100;
+                self.curve.release_escrow(ever_amount).unwrap();
+                self.escrow -= ever_amount;
+                self.holders += ever_amount;
+            }
+            Op::ReserveSell { percent, keep_as_reserve } => {
+                // process_sell_queue sells at the order's locked price, capped at what X can pay
+                let locked_price = self.curve.organic_price();
+                let Some(max_ever_sellable) = self.curve.x.checked_mul(EVER_UNIT).and_then(|x| x.checked_div(locked_price)) else {
+                    return;
+                };
+                let ever_amount = (self.escrow * percent / 100).min(max_ever_sellable);
+                let Some(usdc_amount) = ever_amount.checked_mul(locked_price).map(|usdc| usdc / EVER_UNIT) else {
+                    return;
+                };
+                self.curve.reserve_sell(usdc_amount, ever_amount, keep_as_reserve).unwrap();
+                self.escrow -= ever_amount;
+                if !keep_as_reserve {
+                    self.mint_supply -= ever_amount;
+                }
+            }
+        }
+    }
+
+    // EVER in program_ever_account - the reserves plus the sell-order escrow
+    fn reserve_vault(&self) -> u64 {
+        self.curve.y + self.escrow
+    }
+
+    fn assert_reconciled(&self) -> Result<(), TestCaseError> {
+        let curve = &self.curve;
+        prop_assert_eq!(curve.circulating_supply + curve.y + self.escrow, self.mint_supply);
+        prop_assert_eq!(curve.circulating_supply, self.holders);
+        prop_assert_eq!(curve.k, curve.x as u128 * curve.y as u128);
+        Ok(())
+    }
+}
+
+proptest! {
+    #[test]
+    fn supply_reconciles_with_mint(premint in 0u64..1_000_000_000_000_000, ops in prop::collection::vec(op(), 1..64)) {
+        let mut ledger = Ledger::launch(premint);
+        ledger.assert_reconciled()?;
+        for op in &ops {
+            ledger.apply(op);
+            ledger.assert_reconciled()?;
+        }
+    }
+}
+
+#[test]
+fn burning_shrinks_supply_but_not_circulation() {
+    let mut ledger = Ledger::launch(1_000_000_000_000);
+    ledger.apply(&Op::Sell { percent: 100 });
+    assert_eq!(ledger.curve.circulating_supply, 0);
+
+    let y_before = ledger.curve.y;
+    ledger.apply(&Op::ReserveSell { percent: 50, keep_as_reserve: false });
+    assert_eq!(ledger.curve.y, y_before);
+    ledger.apply(&Op::ReserveSell { percent: 100, keep_as_reserve: true });
+    assert_eq!(ledger.curve.y, y_before + 500_000_000_000);
+    assert_eq!(ledger.mint_supply, y_before + 500_000_000_000);
+    assert_eq!(ledger.curve.circulating_supply, 0);
+}
@@ -1,5 +1,5 @@
 const { Connection, PublicKey, Keypair } = require('@solana/web3.js');
-const { Program, AnchorProvider } = require('@coral-xyz/anchor');
+const { Program, AnchorProvider, BN } = require('@coral-xyz/anchor');
 const fs = require('fs');
 
 async function main() {
@@ -31,8 +31,18 @@ async function main() {
   // Program and PDA addresses
   const PROGRAM_ID = new PublicKey('9tXMAMrSrdkQ6ojkU87TRn3w13joZioz6iuab44ywwpy');
   const TREASURY_WALLET = new PublicKey('DTA5uQocoAaZwXL59DoVZwWUxJCsxjfBCM6mzpws8T4');
+  const CURVE_PARAMS = {
+    quoteMint: new PublicKey('EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v'), // Mainnet USDC
+    baseMint: new PublicKey('3q4YFYMKHrdYw5FPANQ7nrCQMT4t12XKgzYX8JaTeEx8'), // Production EVER mint
+    initialX: new BN('10000000000'), // 10,000 USDC (6 decimals)
+    initialY: new BN('100000000000000000'), // 100,000,000 EVER (9 decimals)
+    dailyGrowthBps: 2, // 0.02%
+    maxQuoteAmount: new BN('10000000000000'), // 10M USDC per transaction
+    maxBaseAmount: new BN('10000000000000000'), // 10M EVER per transaction
+    commissionBps: 500, // 5%
+  };
   const [bondingCurvePDA] = PublicKey.findProgramAddressSync(
-    [Buffer.from('bonding_curve')],
+    [Buffer.from('bonding_curve'), CURVE_PARAMS.baseMint.toBuffer()],
     PROGRAM_ID
   );
   
@@ -52,9 +62,11 @@ async function main() {
     
     // Initialize the bonding curve
     const tx = await program.methods
-      .initialize(TREASURY_WALLET)
+      .createMarket(TREASURY_WALLET, CURVE_PARAMS)
       .accounts({
         bondingCurve: bondingCurvePDA,
+        baseMint: CURVE_PARAMS.baseMint,
+        quoteMint: CURVE_PARAMS.quoteMint,
         authority: wallet.publicKey,
         systemProgram: PublicKey.default,
       })
@@ -0,0 +1,261 @@
+use anchor_lang::prelude::*;
+
+use crate::{apply_daily_boost, BondingCurve, ErrorCode, PAUSE_ALL, SELL_SETTLEMENT_BURN, SELL_SETTLEMENT_RESERVE};
+
+// Admin instructions - every operation here is gated on BondingCurve.authority
+
+/// Manually apply daily boost (for testing and maintenance)
+pub fn handle_apply_daily_boost_manual(ctx: Context<ApplyDailyBoost>) -> Result<()> {
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    let clock = Clock::get()?;
+
+    // Apply daily boost
+    apply_daily_boost(bonding_curve, clock.unix_timestamp)?;
+
+    msg!("Daily boost manually applied at timestamp: {}", clock.unix_timestamp);
+
+    Ok(())
+}
+
+/// Step 1 of an authority transfer - the current authority nominates a successor
+pub fn handle_propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.pending_authority = new_authority;
+
+    emit!(AuthorityProposedEvent {
+        authority: bonding_curve.authority,
+        pending_authority: new_authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Authority transfer proposed: {} -> {}", bonding_curve.authority, new_authority);
+    Ok(())
+}
+
+/// Step 2 of an authority transfer - the nominated key signs to take over
+pub fn handle_accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    let previous_authority = bonding_curve.authority;
+
+    bonding_curve.authority = ctx.accounts.new_authority.key();
+    bonding_curve.pending_authority = Pubkey::default();
+
+    emit!(AuthorityTransferredEvent {
+        previous_authority,
+        new_authority: bonding_curve.authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Authority transferred: {} -> {}", previous_authority, bonding_curve.authority);
+    Ok(())
+}
+
+/// Point the curve at a new treasury wallet
+pub fn handle_set_treasury_wallet(ctx: Context<SetTreasuryWallet>, treasury_wallet: Pubkey) -> Result<()> {
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    let previous_treasury_wallet = bonding_curve.treasury_wallet;
+    bonding_curve.treasury_wallet = treasury_wallet;
+
+    emit!(TreasuryWalletUpdatedEvent {
+        previous_treasury_wallet,
+        treasury_wallet,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Treasury wallet updated: {} -> {}", previous_treasury_wallet, treasury_wallet);
+    Ok(())
+}
+
+/// Point the curve at a new affiliate program
+pub fn handle_set_affiliate_program(ctx: Context<SetAffiliateProgram>, affiliate_program: Pubkey) -> Result<()> {
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    let previous_affiliate_program = bonding_curve.affiliate_program;
+    bonding_curve.affiliate_program = affiliate_program;
+
+    emit!(AffiliateProgramUpdatedEvent {
+        previous_affiliate_program,
+        affiliate_program,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Affiliate program updated: {} -> {}", previous_affiliate_program, affiliate_program);
+    Ok(())
+}
+
+/// Pause the given PAUSE_* operations (circuit breaker)
+pub fn handle_pause(ctx: Context<SetPaused>, operations: u8) -> Result<()> {
+    require!(operations != 0 && operations & !PAUSE_ALL == 0, ErrorCode::InvalidAmount);
+
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.paused_operations |= operations;
+
+    emit!(PauseUpdatedEvent {
+        paused_operations: bonding_curve.paused_operations,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Operations paused: {:#05b}", bonding_curve.paused_operations);
+    Ok(())
+}
+
+/// Resume the given PAUSE_* operations
+pub fn handle_unpause(ctx: Context<SetPaused>, operations: u8) -> Result<()> {
+    require!(operations != 0 && operations & !PAUSE_ALL == 0, ErrorCode::InvalidAmount);
+
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.paused_operations &= !operations;
+
+    emit!(PauseUpdatedEvent {
+        paused_operations: bonding_curve.paused_operations,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Operations paused: {:#05b}", bonding_curve.paused_operations);
+    Ok(())
+}
+
+/// Choose what process_sell_queue does with EVER sold to reserves (SELL_SETTLEMENT_* mode)
+pub fn handle_set_sell_settlement(ctx: Context<SetSellSettlement>, sell_settlement: u8) -> Result<()> {
+    require!(
+        sell_settlement == SELL_SETTLEMENT_BURN || sell_settlement == SELL_SETTLEMENT_RESERVE,
+        ErrorCode::InvalidAmount
+    );
+
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.sell_settlement = sell_settlement;
+
+    emit!(SellSettlementUpdatedEvent {
+        sell_settlement,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Sell settlement mode set to {}", sell_settlement);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ApplyDailyBoost<'info> {
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", bonding_curve.config.base_mint.as_ref()],
+        bump = bonding_curve.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", bonding_curve.config.base_mint.as_ref()],
+        bump = bonding_curve.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", bonding_curve.config.base_mint.as_ref()],
+        bump = bonding_curve.bump,
+        constraint = bonding_curve.pending_authority == new_authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetTreasuryWallet<'info> {
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", bonding_curve.config.base_mint.as_ref()],
+        bump = bonding_curve.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAffiliateProgram<'info> {
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", bonding_curve.config.base_mint.as_ref()],
+        bump = bonding_curve.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", bonding_curve.config.base_mint.as_ref()],
+        bump = bonding_curve.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetSellSettlement<'info> {
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", bonding_curve.config.base_mint.as_ref()],
+        bump = bonding_curve.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    pub authority: Signer<'info>,
+}
+
+// Events
+#[event]
+pub struct AuthorityProposedEvent {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferredEvent {
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TreasuryWalletUpdatedEvent {
+    pub previous_treasury_wallet: Pubkey,
+    pub treasury_wallet: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AffiliateProgramUpdatedEvent {
+    pub previous_affiliate_program: Pubkey,
+    pub affiliate_program: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PauseUpdatedEvent {
+    pub paused_operations: u8, // Bitmask of PAUSE_* flags after the update
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SellSettlementUpdatedEvent {
+    pub sell_settlement: u8, // SELL_SETTLEMENT_* mode after the update
+    pub timestamp: i64,
+}
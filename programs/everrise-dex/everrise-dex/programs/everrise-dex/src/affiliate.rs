@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::{get_return_data, invoke_signed};
+
+use crate::ErrorCode;
+
+// Affiliate commissions - buy_smart pays them through affiliate_program::process_commission,
+// which only credits the referrer recorded in the buyer's Referral, at a rate the affiliate
+// program picks from its commission tiers and splits across the referrer's upline. The USDC
+// goes into the affiliate program's commission vault, where referrers claim it. Each market signs
+// the call with its own [b"affiliate_authority", bonding_curve] PDA, and the affiliate program only
+// accepts the markets it has allow-listed, so purchases cannot be reported from outside buy_smart
+// or from a market nobody approved. The affiliate program is built
+// against an older Anchor, so the CPI is assembled by hand instead of through its `cpi` feature.
+
+/// Accounts of affiliate_program::process_commission, in instruction order
+pub struct ProcessCommission<'info> {
+    pub affiliate_state: AccountInfo<'info>, // AffiliateProgram PDA [b"affiliate_program"]
+    pub referral: AccountInfo<'info>, // Referral PDA [b"referral", buyer]
+    pub referral_registry: AccountInfo<'info>, // ReferralRegistry PDA [b"referral_registry", referrer]
+    pub referrer: AccountInfo<'info>,
+    pub buyer: AccountInfo<'info>, // Signs for the commission transfer
+    pub buyer_usdc_account: AccountInfo<'info>,
+    pub commission_vault: AccountInfo<'info>, // Token account PDA [b"commission_vault"]
+    pub commission_authority: AccountInfo<'info>, // The market's PDA [b"affiliate_authority", bonding_curve], signs the CPI
+    pub token_program: AccountInfo<'info>,
+    pub affiliate_program: AccountInfo<'info>, // The market's BondingCurve.affiliate_program
+    pub upline: &'info [AccountInfo<'info>], // Upline walk, passed through as the CPI's remaining accounts
+}
+
+/// Invoke affiliate_program::process_commission for `purchase_amount`, returning the commission charged.
+/// `authority_bump` is the bump of `bonding_curve`'s [b"affiliate_authority", bonding_curve] PDA.
+pub fn process_commission(
+    accounts: ProcessCommission,
+    purchase_amount: u64,
+    bonding_curve: &Pubkey,
+    authority_bump: u8,
+) -> Result<u64> {
+    // Anchor instruction data: sighash of "global:<name>", then the borsh-encoded args
+    let mut data = hash(b"global:process_commission").to_bytes()[..8].to_vec();
+    data.extend_from_slice(&purchase_amount.to_le_bytes());
+
+    let mut metas = vec![
+        AccountMeta::new(accounts.affiliate_state.key(), false),
+        AccountMeta::new_readonly(accounts.referral.key(), false),
+        AccountMeta::new(accounts.referral_registry.key(), false),
+        AccountMeta::new_readonly(accounts.referrer.key(), false),
+        AccountMeta::new_readonly(accounts.buyer.key(), true),
+        AccountMeta::new(accounts.buyer_usdc_account.key(), false),
+        AccountMeta::new(accounts.commission_vault.key(), false),
+        AccountMeta::new_readonly(accounts.commission_authority.key(), true),
+        AccountMeta::new_readonly(accounts.token_program.key(), false),
+    ];
+    metas.extend(accounts.upline.iter().map(|info| AccountMeta {
+        pubkey: info.key(),
+        is_signer: false,
+        is_writable: info.is_writable,
+    }));
+    let affiliate_program_id = accounts.affiliate_program.key();
+    let instruction = Instruction {
+        program_id: affiliate_program_id,
+        accounts: metas,
+        data,
+    };
+
+    let mut infos = vec![
+        accounts.affiliate_state,
+        accounts.referral,
+        accounts.referral_registry,
+        accounts.referrer,
+        accounts.buyer,
+        accounts.buyer_usdc_account,
+        accounts.commission_vault,
+        accounts.commission_authority,
+        accounts.token_program,
+        accounts.affiliate_program,
+    ];
+    infos.extend_from_slice(accounts.upline);
+    let seeds = &[&b"affiliate_authority"[..], bonding_curve.as_ref(), &[authority_bump]];
+    invoke_signed(&instruction, &infos, &[&seeds[..]])?;
+
+    // The commission amount comes back as borsh-encoded return data
+    let (program_id, return_data) = get_return_data().ok_or(ErrorCode::AffiliateCommissionMissing)?;
+    require_keys_eq!(program_id, affiliate_program_id, ErrorCode::AffiliateCommissionMissing);
+    let commission_amount: [u8; 8] = return_data.as_slice().try_into().map_err(|_| ErrorCode::AffiliateCommissionMissing)?;
+    Ok(u64::from_le_bytes(commission_amount))
+}
@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, TokenAccount};
+
+use crate::{sell_order_address, BondingCurve, ErrorCode, SellOrder};
+
+// Read-only consistency check of a bonding curve against its token accounts.
+// Every open sell order must be passed in remaining accounts, head first, so the
+// escrowed EVER can be summed.
+
+// Bits of InvariantReport.violations - one per invariant
+pub const INVARIANT_K: u8 = 1 << 0; // K == X * Y
+pub const INVARIANT_TREASURY: u8 = 1 << 1; // Treasury USDC covers X
+pub const INVARIANT_RESERVE: u8 = 1 << 2; // Program EVER covers Y
+pub const INVARIANT_ESCROW: u8 = 1 << 3; // Program EVER beyond Y and the tracked escrow match the open sell orders
+pub const INVARIANT_QUEUE_ORDER: u8 = 1 << 4; // Queues run head to tail
+
+/// Check the curve's bookkeeping against real balances and the open sell orders.
+/// Returns the report as return data. If any invariant is broken, every violation is
+/// logged and emitted first, then the call fails with the error for the first one.
+pub fn handle_verify_invariants(ctx: Context<VerifyInvariants>) -> Result<InvariantReport> {
+    let bonding_curve = &ctx.accounts.bonding_curve;
+    let bonding_curve_key = bonding_curve.key();
+
+    // Queue ids only grow along the queue, and an empty queue has neither head nor tail
+    let (head, tail) = (bonding_curve.sell_queue_head, bonding_curve.sell_queue_tail);
+    let (buy_head, buy_tail) = (bonding_curve.buy_queue_head, bonding_curve.buy_queue_tail);
+    let queues_ordered = (head == 0) == (tail == 0)
+        && head <= tail
+        && (buy_head == 0) == (buy_tail == 0)
+        && buy_head <= buy_tail;
+
+    // Walk the sell queue from head to tail, summing what is still escrowed
+    let mut escrowed_sell_ever = 0u64;
+    let mut open_sell_orders = 0u32;
+    let mut order_id = head;
+    let mut last_order_id = 0;
+    for sell_order_info in ctx.remaining_accounts.iter() {
+        require!(order_id != 0, ErrorCode::InvalidRemainingAccounts); // More accounts than open orders
+        require_keys_eq!(
+            sell_order_info.key(),
+            sell_order_address(&bonding_curve_key, order_id),
+            ErrorCode::InvalidSellOrder
+        );
+        require_keys_eq!(*sell_order_info.owner, crate::ID, ErrorCode::InvalidSellOrder);
+        let sell_order = {
+            let sell_order_data = sell_order_info.try_borrow_data()?;
+            SellOrder::try_deserialize(&mut sell_order_data.as_ref())?
+        };
+
+        escrowed_sell_ever = escrowed_sell_ever.checked_add(sell_order.remaining_amount).ok_or(ErrorCode::MathOverflow)?;
+        open_sell_orders += 1;
+        last_order_id = order_id;
+        order_id = sell_order.next;
+    }
+    require!(order_id == 0, ErrorCode::QueueLinkMissing); // Every open order must be supplied
+
+    let mut report = InvariantReport {
+        x: bonding_curve.x,
+        y: bonding_curve.y,
+        k: bonding_curve.k,
+        treasury_usdc: ctx.accounts.treasury_usdc_account.amount,
+        reserve_ever: ctx.accounts.program_ever_account.amount,
+        escrowed_sell_ever,
+        tracked_escrowed_sell_ever: bonding_curve.escrowed_sell_ever,
+        open_sell_orders,
+        circulating_supply: bonding_curve.circulating_supply,
+        mint_supply: ctx.accounts.ever_mint.supply,
+        violations: 0,
+    };
+
+    // Run every check before failing so a single call reports all broken invariants
+    let checks = [
+        (report.k == (report.x as u128) * (report.y as u128), INVARIANT_K, ErrorCode::InvariantKMismatch),
+        (report.treasury_usdc >= report.x, INVARIANT_TREASURY, ErrorCode::TreasuryUnderfunded),
+        (report.reserve_ever >= report.y, INVARIANT_RESERVE, ErrorCode::ReserveUnderfunded),
+        (
+            report.reserve_ever.checked_sub(report.y) == Some(escrowed_sell_ever)
+                && report.tracked_escrowed_sell_ever == escrowed_sell_ever,
+            INVARIANT_ESCROW,
+            ErrorCode::EscrowMismatch,
+        ),
+        (queues_ordered && last_order_id == tail, INVARIANT_QUEUE_ORDER, ErrorCode::QueueOrderInvalid),
+    ];
+    let mut first_violation = None;
+    for (holds, flag, error) in checks {
+        if !holds {
+            msg!("Invariant violated: {}", error);
+            report.violations |= flag;
+            first_violation.get_or_insert(error);
+        }
+    }
+    msg!("Invariant report: {:?}", report);
+
+    if let Some(error) = first_violation {
+        emit!(InvariantsViolatedEvent {
+            bonding_curve: bonding_curve_key,
+            violations: report.violations,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        return Err(error.into());
+    }
+
+    Ok(report)
+}
+
+#[derive(Accounts)]
+pub struct VerifyInvariants<'info> {
+    #[account(
+        seeds = [b"bonding_curve", bonding_curve.config.base_mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+        constraint = treasury_usdc_account.owner == bonding_curve.treasury_wallet,
+        constraint = treasury_usdc_account.mint == bonding_curve.config.quote_mint
+    )]
+    pub treasury_usdc_account: Account<'info, TokenAccount>,
+
+    // Program EVER account (holds the reserves and escrowed sell orders)
+    #[account(
+        constraint = program_ever_account.owner == bonding_curve.key(),
+        constraint = program_ever_account.mint == bonding_curve.config.base_mint
+    )]
+    pub program_ever_account: Account<'info, TokenAccount>,
+
+    #[account(address = bonding_curve.config.base_mint)]
+    pub ever_mint: Account<'info, Mint>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct InvariantReport {
+    pub x: u64, // BondingCurve.x
+    pub y: u64, // BondingCurve.y
+    pub k: u128, // BondingCurve.k - must equal x * y
+    pub treasury_usdc: u64, // treasury_usdc_account balance - must cover x
+    pub reserve_ever: u64, // program_ever_account balance - must be y plus escrowed_sell_ever
+    pub escrowed_sell_ever: u64, // Sum of remaining_amount over the open sell orders
+    pub tracked_escrowed_sell_ever: u64, // BondingCurve.escrowed_sell_ever - must equal escrowed_sell_ever
+    pub open_sell_orders: u32,
+    pub circulating_supply: u64, // BondingCurve.circulating_supply
+    pub mint_supply: u64, // EVER mint supply - circulating_supply + y + escrowed_sell_ever once reconciled
+    pub violations: u8, // INVARIANT_* bits of the broken invariants (0 when all hold)
+}
+
+// Events
+#[event]
+pub struct InvariantsViolatedEvent {
+    pub bonding_curve: Pubkey,
+    pub violations: u8, // INVARIANT_* bits
+    pub timestamp: i64,
+}
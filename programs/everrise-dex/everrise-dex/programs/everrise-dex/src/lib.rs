@@ -1,69 +1,123 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount};
-use std::str::FromStr;
-
-// Affiliate program ID (will be set during deployment)
-pub const AFFILIATE_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
-
-// Mint addresses for validation
-const USDC_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"); // Mainnet USDC
-const EVER_MINT: Pubkey = pubkey!("3q4YFYMKHrdYw5FPANQ7nrCQMT4t12XKgzYX8JaTeEx8"); // Production EVER mint
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use everrise_math::{Curve, MathError};
+
+pub mod admin;
+pub use admin::*;
+mod affiliate;
+pub mod migration;
+pub use migration::*;
+pub mod invariants;
+pub use invariants::*;
+pub mod volume;
+pub use volume::*;
+mod transition;
 
 declare_id!("9tXMAMrSrdkQ6ojkU87TRn3w13joZioz6iuab44ywwpy");
 
-// Constants from EverRise Formula
-const INITIAL_X: u64 = 10_000_000_000; // 10,000 USDC (6 decimals)
-const INITIAL_Y: u64 = 100_000_000_000_000_000; // 100,000,000 EVER (9 decimals)
-const DAILY_GROWTH_RATE: u64 = 2; // 0.02% = 2 basis points
+// Constants from EverRise Formula (curve parameters are supplied at initialize, see CurveParams)
 const BASIS_POINTS: u64 = 10_000; // 100% = 10,000 basis points
+// Steepest daily growth a market may guarantee (1%) - far steeper rates overflow the daily boost
+// within months of idle days, which would block every buy and sell
+pub const MAX_DAILY_GROWTH_BPS: u16 = 100;
+
+// Pause flags for BondingCurve.paused_operations (refunds and cancellations are never paused)
+pub const PAUSE_BUY: u8 = 1 << 0; // buy, buy_smart, queue_buy
+pub const PAUSE_SELL: u8 = 1 << 1; // sell
+pub const PAUSE_QUEUE_PROCESSING: u8 = 1 << 2; // process_buy_queue, process_sell_queue
+pub const PAUSE_ALL: u8 = PAUSE_BUY | PAUSE_SELL | PAUSE_QUEUE_PROCESSING;
+
+// Settlement modes for BondingCurve.sell_settlement - what process_sell_queue does with EVER sold to reserves
+pub const SELL_SETTLEMENT_BURN: u8 = 0; // Burn it from program_ever_account - leaves the mint supply and y
+pub const SELL_SETTLEMENT_RESERVE: u8 = 1; // Keep it in program_ever_account and add it to y
+
+// Account layout version written to BondingCurve, SellOrder and BuyOrder (see migration.rs)
+pub const LAYOUT_VERSION: u8 = 5;
 
 #[program]
 pub mod everrise_dex {
     use super::*;
 
-    /// Initialize the EverRise DEX with initial bonding curve parameters
-    pub fn initialize(
-        ctx: Context<Initialize>,
+    /// Create a market - a bonding curve for `params.base_mint` with its own order queues.
+    /// The signer must be the base mint's mint authority and becomes the curve authority.
+    /// This is deliberate: the curve PDA is keyed by the base mint alone, so anyone else
+    /// could otherwise squat a token's only market with their own params and treasury.
+    /// Tokens whose mint authority was revoked can't get a market.
+    /// buy_smart pays referral commissions through `affiliate_program` (Pubkey::default() for none).
+    pub fn create_market(
+        ctx: Context<CreateMarket>,
         treasury_wallet: Pubkey,
+        params: CurveParams,
+        affiliate_program: Pubkey,
     ) -> Result<()> {
+        params.validate()?;
+
         let bonding_curve = &mut ctx.accounts.bonding_curve;
         let clock = Clock::get()?;
 
         bonding_curve.authority = ctx.accounts.authority.key();
         bonding_curve.treasury_wallet = treasury_wallet;
-        bonding_curve.x = INITIAL_X; // USDC in treasury
-        bonding_curve.y = INITIAL_Y; // EVER in reserve
+        bonding_curve.x = params.initial_x; // USDC in treasury
+        bonding_curve.y = params.initial_y; // EVER in reserve
         // Calculate K = X * Y using u128 to handle overflow
-        bonding_curve.k = (INITIAL_X as u128).checked_mul(INITIAL_Y as u128).ok_or(ErrorCode::MathOverflow)?; // K = X * Y
+        bonding_curve.k = (params.initial_x as u128).checked_mul(params.initial_y as u128).ok_or(ErrorCode::MathOverflow)?; // K = X * Y
         bonding_curve.last_daily_boost = clock.unix_timestamp;
-        bonding_curve.total_volume_24h = 0;
-        bonding_curve.sell_queue_head = 0;
+        bonding_curve.total_volume_24h = 0; // Trailing 24h buy volume, see VolumeStats
+        bonding_curve.sell_queue_head = 0; // Empty queues
         bonding_curve.sell_queue_tail = 0;
         bonding_curve.buy_queue_head = 0;
         bonding_curve.buy_queue_tail = 0;
+        bonding_curve.next_order_id = 1; // 0 means "no order"
         bonding_curve.cumulative_bonus = 0;
-        // Calculate initial price: X / Y * 10^3 (to convert from 6 decimals to 9 decimals)
-        // Price = (10,000 USDC / 100,000,000 EVER) * 10^3 = 0.1 * 10^3 = 100
-        bonding_curve.current_price = 100; // 0.0001 USDC per EVER in proper decimals
+        // Initial price is the organic X / Y price (e.g. 10,000 USDC / 100,000,000 EVER = 100)
+        bonding_curve.current_price = calculate_organic_price(bonding_curve);
+        require!(bonding_curve.current_price > 0, ErrorCode::InvalidCurveParams);
         bonding_curve.last_price_update = clock.unix_timestamp;
         bonding_curve.daily_boost_applied = false;
-        bonding_curve.circulating_supply = 0;
+        // Whatever EVER exists beyond the initial reserves is already in wallets
+        bonding_curve.circulating_supply = ctx.accounts.base_mint.supply.saturating_sub(params.initial_y);
         bonding_curve.bump = ctx.bumps.bonding_curve;
+        bonding_curve.pending_authority = Pubkey::default();
+        bonding_curve.paused_operations = 0;
+        bonding_curve.config = params;
+        bonding_curve.day_start_price = bonding_curve.current_price;
+        bonding_curve.day_start_ts = everrise_math::day_start(clock.unix_timestamp);
+        bonding_curve.version = LAYOUT_VERSION;
+        bonding_curve.sell_settlement = SELL_SETTLEMENT_BURN;
+        bonding_curve.total_burned = 0;
+        bonding_curve.escrowed_sell_ever = 0;
+        bonding_curve.affiliate_program = affiliate_program;
+
+        emit!(MarketCreatedEvent {
+            bonding_curve: bonding_curve.key(),
+            base_mint: bonding_curve.config.base_mint,
+            quote_mint: bonding_curve.config.quote_mint,
+            authority: bonding_curve.authority,
+            timestamp: clock.unix_timestamp,
+        });
 
-        msg!("EverRise DEX initialized with K={}, X={}, Y={}", 
-             bonding_curve.k, bonding_curve.x, bonding_curve.y);
+        msg!("EverRise market created for {} with K={}, X={}, Y={}", 
+             bonding_curve.config.base_mint, bonding_curve.k, bonding_curve.x, bonding_curve.y);
+
+        let bonding_curve_key = bonding_curve.key();
+        init_volume_stats(&mut ctx.accounts.volume_stats, bonding_curve_key, ctx.bumps.volume_stats, clock.unix_timestamp);
 
         Ok(())
     }
 
     /// Buy EVER tokens using USDC from the bonding curve. This is now an atomic operation.
-    pub fn buy(ctx: Context<Buy>, usdc_amount: u64) -> Result<()> {
+    /// Fails if fewer than `min_ever_out` tokens would be received or `deadline` has passed.
+    pub fn buy(ctx: Context<Buy>, usdc_amount: u64, min_ever_out: u64, deadline: Option<i64>) -> Result<()> {
+        require!(!ctx.accounts.bonding_curve.is_paused(PAUSE_BUY), ErrorCode::OperationPaused);
+
         // Validate input parameters
         require!(usdc_amount > 0, ErrorCode::InvalidAmount);
-        require!(usdc_amount <= 10_000_000_000_000, ErrorCode::AmountTooLarge); // Max 10M USDC per transaction
+        require!(usdc_amount <= ctx.accounts.bonding_curve.config.max_quote_amount, ErrorCode::AmountTooLarge); // Configured max USDC per transaction
 
         let bonding_curve = &mut ctx.accounts.bonding_curve;
         let clock = Clock::get()?;
+        check_deadline(deadline, clock.unix_timestamp)?;
 
         // Apply daily boost if needed
         apply_daily_boost(bonding_curve, clock.unix_timestamp)?;
@@ -71,9 +125,13 @@ pub mod everrise_dex {
         // Calculate exact tokens to receive
         let tokens_to_receive = calculate_buy_amount(bonding_curve, usdc_amount)?;
         require!(tokens_to_receive > 0, ErrorCode::InvalidAmount);
+        require!(tokens_to_receive >= min_ever_out, ErrorCode::SlippageExceeded);
 
-        // Check if program has enough EVER tokens
-        require!(ctx.accounts.program_ever_account.amount >= tokens_to_receive, ErrorCode::InsufficientFunds);
+        // Check if program has enough EVER tokens outside the sell-order escrow
+        require!(
+            bonding_curve.available_reserve(ctx.accounts.program_ever_account.amount) >= tokens_to_receive,
+            ErrorCode::InsufficientLiquidity
+        );
 
         // 1. Transfer USDC from user to treasury
         let cpi_accounts_usdc = token::Transfer {
@@ -86,7 +144,8 @@ pub mod everrise_dex {
         token::transfer(cpi_ctx_usdc, usdc_amount)?;
 
         // 2. Transfer EVER tokens from program to user
-        let seeds = &[&b"bonding_curve"[..], &[bonding_curve.bump]];
+        let base_mint = bonding_curve.config.base_mint;
+        let seeds = &[&b"bonding_curve"[..], base_mint.as_ref(), &[bonding_curve.bump]];
         let signer_seeds = &[&seeds[..]];
         let cpi_accounts_ever = token::Transfer {
             from: ctx.accounts.program_ever_account.to_account_info(),
@@ -98,11 +157,9 @@ pub mod everrise_dex {
         token::transfer(cpi_ctx_ever, tokens_to_receive)?;
 
         // 3. Update bonding curve state
-        bonding_curve.x = bonding_curve.x.checked_add(usdc_amount).ok_or(ErrorCode::MathOverflow)?;
-        bonding_curve.y = bonding_curve.y.checked_sub(tokens_to_receive).ok_or(ErrorCode::MathOverflow)?;
-        bonding_curve.k = u128::from(bonding_curve.x).checked_mul(u128::from(bonding_curve.y)).ok_or(ErrorCode::MathOverflow)?;
-        bonding_curve.circulating_supply = bonding_curve.circulating_supply.checked_add(tokens_to_receive).ok_or(ErrorCode::MathOverflow)?;
-        bonding_curve.total_volume_24h = bonding_curve.total_volume_24h.checked_add(usdc_amount).ok_or(ErrorCode::MathOverflow)?;
+        transition::reserve_buy(bonding_curve, usdc_amount, tokens_to_receive)?;
+        let volume = Volume { buy: usdc_amount, reserve: usdc_amount, ..Volume::default() };
+        record_volume(&mut ctx.accounts.volume_stats, bonding_curve, clock.unix_timestamp, volume)?;
         bonding_curve.current_price = calculate_effective_price(bonding_curve);
         bonding_curve.last_price_update = clock.unix_timestamp;
         
@@ -111,20 +168,33 @@ pub mod everrise_dex {
             usdc_amount,
             ever_received: tokens_to_receive,
             new_price: bonding_curve.current_price,
+            appreciation_bonus: 0,
             timestamp: clock.unix_timestamp,
         });
 
         Ok(())
     }
 
-    /// Smart buy that processes sell orders first, then buys from reserves if needed
-    pub fn buy_smart(ctx: Context<BuyWithSellProcessing>, usdc_amount: u64) -> Result<()> {
+    /// Smart buy that processes sell orders first, then buys from reserves if needed.
+    /// Fails if fewer than `min_ever_out` tokens are received in total or `deadline` has passed.
+    /// The last `upline_accounts` remaining accounts are the referrer's upline, forwarded to
+    /// affiliate_program::process_commission.
+    pub fn buy_smart<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BuyWithSellProcessing<'info>>,
+        usdc_amount: u64,
+        min_ever_out: u64,
+        deadline: Option<i64>,
+        upline_accounts: u8,
+    ) -> Result<()> {
+        require!(!ctx.accounts.bonding_curve.is_paused(PAUSE_BUY), ErrorCode::OperationPaused);
+
         // Validate input parameters
         require!(usdc_amount > 0, ErrorCode::InvalidAmount);
-        require!(usdc_amount <= 10_000_000_000_000, ErrorCode::AmountTooLarge); // Max 10M USDC per transaction
+        require!(usdc_amount <= ctx.accounts.bonding_curve.config.max_quote_amount, ErrorCode::AmountTooLarge); // Configured max USDC per transaction
 
         let bonding_curve = &mut ctx.accounts.bonding_curve;
         let clock = Clock::get()?;
+        check_deadline(deadline, clock.unix_timestamp)?;
 
         // Apply daily boost if needed
         apply_daily_boost(bonding_curve, clock.unix_timestamp)?;
@@ -132,204 +202,88 @@ pub mod everrise_dex {
         let mut remaining_usdc = usdc_amount;
         let mut total_ever_received = 0u64;
 
-        // First, try to fulfill from sell orders if any exist
-        msg!("DEBUG: Checking sell queue - Head: {}, Tail: {}", bonding_curve.sell_queue_head, bonding_curve.sell_queue_tail);
-        if bonding_curve.sell_queue_head < bonding_curve.sell_queue_tail {
-            msg!("DEBUG: Found sell orders in queue, processing...");
-            // Process sell orders (similar logic to process_buy_queue)
-            let sell_order_info = ctx.accounts.sell_order.to_account_info();
-            msg!("DEBUG: Sell order account: {}", sell_order_info.key());
-            msg!("DEBUG: Sell order data length: {}", sell_order_info.data_len());
-            if sell_order_info.data_len() > 0 {
-                msg!("DEBUG: Attempting to deserialize and process sell order...");
-                
-                // Use a single mutable borrow to avoid conflicts
-                let mut sell_order_data = sell_order_info.try_borrow_mut_data()?;
-                if let Ok(mut sell_order) = SellOrder::try_deserialize(&mut sell_order_data.as_ref()) {
-                    msg!("DEBUG: Successfully deserialized sell order - processed: {}, remaining: {}", sell_order.processed, sell_order.remaining_amount);
-                    
-                    if !sell_order.processed && sell_order.remaining_amount > 0 {
-                        // Calculate how much USDC we can spend on this sell order
-                        let usdc_for_this_sell = sell_order.remaining_amount
-                            .checked_mul(sell_order.locked_price)
-                            .unwrap_or(0)
-                            .checked_div(1_000_000_000) // Convert from 9 decimals to 6
-                            .unwrap_or(0);
-
-                        msg!("DEBUG: USDC for this sell: {}, remaining USDC: {}", usdc_for_this_sell, remaining_usdc);
-                        
-                        let mut sell_order_updated = false;
-                        
-                        if usdc_for_this_sell > 0 && usdc_for_this_sell <= remaining_usdc {
-                            msg!("DEBUG: Processing sell order - transferring {} USDC for {} EVER", usdc_for_this_sell, sell_order.remaining_amount);
-                            // Full fill of this sell order
-                            let ever_from_sell = sell_order.remaining_amount;
-                            
-                            // Release the borrow before making transfers
-                            drop(sell_order_data);
-                            
-                            // Transfer USDC from buyer to seller
-                            let cpi_accounts_usdc = token::Transfer {
-                                from: ctx.accounts.user_usdc_account.to_account_info(),
-                                to: ctx.accounts.seller_usdc_account.to_account_info(),
-                                authority: ctx.accounts.user.to_account_info(),
-                            };
-                            let cpi_program_usdc = ctx.accounts.token_program.to_account_info();
-                            let cpi_ctx_usdc = CpiContext::new(cpi_program_usdc, cpi_accounts_usdc);
-                            token::transfer(cpi_ctx_usdc, usdc_for_this_sell)?;
-
-                            // Transfer EVER tokens from program to buyer
-                            let seeds = &[&b"bonding_curve"[..], &[bonding_curve.bump]];
-                            let signer_seeds = &[&seeds[..]];
-                            let cpi_accounts_ever = token::Transfer {
-                                from: ctx.accounts.program_ever_account.to_account_info(),
-                                to: ctx.accounts.user_ever_account.to_account_info(),
-                                authority: bonding_curve.to_account_info(),
-                            };
-                            let cpi_program_ever = ctx.accounts.token_program.to_account_info();
-                            let cpi_ctx_ever = CpiContext::new_with_signer(cpi_program_ever, cpi_accounts_ever, signer_seeds);
-                            token::transfer(cpi_ctx_ever, ever_from_sell)?;
-
-                            // Update tracking
-                            remaining_usdc = remaining_usdc.checked_sub(usdc_for_this_sell).unwrap();
-                            total_ever_received = total_ever_received.checked_add(ever_from_sell).unwrap();
-
-                            // Mark sell order as processed and advance queue
-                            sell_order.processed = true;
-                            sell_order.remaining_amount = 0;
-                            bonding_curve.sell_queue_head = bonding_curve.sell_queue_head.checked_add(1).unwrap();
-                            sell_order_updated = true;
-                            
-                        } else if remaining_usdc > 0 {
-                            // Partial fill of this sell order
-                            msg!("DEBUG: Partial fill - using all remaining USDC: {}", remaining_usdc);
-                            let ever_for_partial = remaining_usdc
-                                .checked_mul(1_000_000_000) // Convert to 9 decimals
-                                .unwrap_or(0)
-                                .checked_div(sell_order.locked_price)
-                                .unwrap_or(0);
-
-                            if ever_for_partial > 0 && ever_for_partial <= sell_order.remaining_amount {
-                                msg!("DEBUG: Processing partial sell - transferring {} USDC for {} EVER", remaining_usdc, ever_for_partial);
-                                
-                                // Release the borrow before making transfers
-                                drop(sell_order_data);
-                                
-                                // Transfer USDC from buyer to seller
-                                let cpi_accounts_usdc = token::Transfer {
-                                    from: ctx.accounts.user_usdc_account.to_account_info(),
-                                    to: ctx.accounts.seller_usdc_account.to_account_info(),
-                                    authority: ctx.accounts.user.to_account_info(),
-                                };
-                                let cpi_program_usdc = ctx.accounts.token_program.to_account_info();
-                                let cpi_ctx_usdc = CpiContext::new(cpi_program_usdc, cpi_accounts_usdc);
-                                token::transfer(cpi_ctx_usdc, remaining_usdc)?;
-
-                                // Transfer EVER tokens from program to buyer
-                                let seeds = &[&b"bonding_curve"[..], &[bonding_curve.bump]];
-                                let signer_seeds = &[&seeds[..]];
-                                let cpi_accounts_ever = token::Transfer {
-                                    from: ctx.accounts.program_ever_account.to_account_info(),
-                                    to: ctx.accounts.user_ever_account.to_account_info(),
-                                    authority: bonding_curve.to_account_info(),
-                                };
-                                let cpi_program_ever = ctx.accounts.token_program.to_account_info();
-                                let cpi_ctx_ever = CpiContext::new_with_signer(cpi_program_ever, cpi_accounts_ever, signer_seeds);
-                                token::transfer(cpi_ctx_ever, ever_for_partial)?;
-
-                                // Update tracking
-                                total_ever_received = total_ever_received.checked_add(ever_for_partial).unwrap();
-
-                                // Update sell order remaining amount (partial fill)
-                                sell_order.remaining_amount = sell_order.remaining_amount.checked_sub(ever_for_partial).unwrap();
-                                
-                                // If sell order is completely filled, mark as processed and advance queue
-                                if sell_order.remaining_amount == 0 {
-                                    sell_order.processed = true;
-                                    bonding_curve.sell_queue_head = bonding_curve.sell_queue_head.checked_add(1).unwrap();
-                                }
-
-                                remaining_usdc = 0; // All USDC used for this sell order
-                                msg!("DEBUG: Partial fill completed - remaining sell order amount: {}", sell_order.remaining_amount);
-                                sell_order_updated = true;
-                            } else {
-                                msg!("DEBUG: Invalid partial fill calculation - ever_for_partial: {}, sell_remaining: {}", ever_for_partial, sell_order.remaining_amount);
-                            }
-                        } else {
-                            msg!("DEBUG: Sell order not processed - usdc_for_this_sell: {}, remaining_usdc: {}", usdc_for_this_sell, remaining_usdc);
-                        }
-                        
-                        // Persist the updated sell order back to the account if it was modified
-                        if sell_order_updated {
-                            // Re-borrow and update the sell order account data
-                            let mut final_sell_order_data = sell_order_info.try_borrow_mut_data()?;
-                            sell_order.try_serialize(&mut final_sell_order_data.as_mut())?;
-                            msg!("DEBUG: Sell order persisted to blockchain - new remaining: {}", sell_order.remaining_amount);
-                            
-                            // Verify the data was written correctly
-                            drop(final_sell_order_data);
-                            let verify_data = sell_order_info.try_borrow_data()?;
-                            if let Ok(verify_order) = SellOrder::try_deserialize(&mut verify_data.as_ref()) {
-                                msg!("DEBUG: Verification - sell order remaining amount after persistence: {}", verify_order.remaining_amount);
-                            } else {
-                                msg!("DEBUG: Verification failed - could not deserialize after persistence");
-                            }
-                        }
-                    } else {
-                        msg!("DEBUG: Sell order already processed or no remaining amount - processed: {}, remaining: {}", sell_order.processed, sell_order.remaining_amount);
-                    }
-                } else {
-                    msg!("DEBUG: Failed to deserialize sell order");
-                }
-            } else {
-                msg!("DEBUG: Sell order account has no data");
-            }
-        } else {
-            msg!("DEBUG: No sell orders in queue");
-        }
+        let sell_order_pairs = ctx.remaining_accounts.len()
+            .checked_sub(upline_accounts as usize)
+            .ok_or(ErrorCode::InvalidRemainingAccounts)?;
+        let (sell_order_remaining, upline) = ctx.remaining_accounts.split_at(sell_order_pairs);
+
+        // First, fill FIFO from consecutive sell orders (named sell_order pair + remaining_accounts pairs)
+        let mut sell_order_accounts = vec![
+            ctx.accounts.sell_order.to_account_info(),
+            ctx.accounts.seller_usdc_account.to_account_info(),
+        ];
+        sell_order_accounts.extend_from_slice(sell_order_remaining);
+
+        let fill = fill_from_sell_queue(
+            bonding_curve,
+            &sell_order_accounts,
+            ctx.accounts.user_usdc_account.to_account_info(),
+            ctx.accounts.user.to_account_info(),
+            ctx.accounts.user_ever_account.to_account_info(),
+            ctx.accounts.program_ever_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            usdc_amount,
+            clock.unix_timestamp,
+        )?;
+        remaining_usdc = remaining_usdc.checked_sub(fill.usdc_spent).ok_or(ErrorCode::MathOverflow)?;
+        total_ever_received = total_ever_received.checked_add(fill.ever_filled).ok_or(ErrorCode::MathOverflow)?;
 
         // If there's still USDC remaining, buy from reserves using bonding curve
+        let mut reserve_volume = 0u64;
         if remaining_usdc > 0 {
-            msg!("DEBUG: Processing buy from reserves - Remaining USDC: {}", remaining_usdc);
-            
-            // Process affiliate commission (5% of remaining USDC)
-            let commission_amount = (remaining_usdc * 500) / 10000; // 5% = 500 basis points
-            let reserve_usdc = remaining_usdc - commission_amount;
-            
-            msg!("DEBUG: Commission amount: {} USDC, Reserve amount: {} USDC", commission_amount, reserve_usdc);
-            
+            // Process affiliate commission. Buyers with a Referral pay through the market's affiliate
+            // program, which rejects any referrer but the one recorded there and picks the rate from
+            // its tiers; everyone else pays the treasury at the configured basis points
+            let referred = bonding_curve.affiliate_program != Pubkey::default()
+                && ctx.accounts.referral.owner == &bonding_curve.affiliate_program;
+            let commission_amount = if referred {
+                let commission_amount = affiliate::process_commission(
+                    affiliate::ProcessCommission {
+                        affiliate_state: ctx.accounts.affiliate_state.to_account_info(),
+                        referral: ctx.accounts.referral.to_account_info(),
+                        referral_registry: ctx.accounts.referral_registry.to_account_info(),
+                        referrer: ctx.accounts.referrer.to_account_info(),
+                        buyer: ctx.accounts.user.to_account_info(),
+                        buyer_usdc_account: ctx.accounts.user_usdc_account.to_account_info(),
+                        commission_vault: ctx.accounts.commission_vault.to_account_info(),
+                        commission_authority: ctx.accounts.affiliate_authority.to_account_info(),
+                        token_program: ctx.accounts.token_program.to_account_info(),
+                        affiliate_program: ctx.accounts.affiliate_program.to_account_info(),
+                        upline,
+                    },
+                    remaining_usdc,
+                    &bonding_curve.key(),
+                    ctx.bumps.affiliate_authority,
+                )?;
+                msg!("Affiliate commission accrued for referrer {}: {} USDC", ctx.accounts.referrer.key(), commission_amount);
+                commission_amount
+            } else {
+                let commission_amount = u64::try_from(
+                    remaining_usdc as u128 * bonding_curve.config.commission_bps as u128 / BASIS_POINTS as u128
+                ).map_err(|_| ErrorCode::MathOverflow)?;
                 if commission_amount > 0 {
-                    // Transfer commission to referrer (or treasury if no referrer)
-                    let (commission_recipient, is_referrer) = {
-                        let account_info = ctx.accounts.referrer_usdc_account.to_account_info();
-                        
-                        // Check if this is a valid token account (not a system program or dummy account)
-                        if account_info.owner == &token::ID && account_info.key() != Pubkey::from_str("11111111111111111111111111111111").unwrap() {
-                            (account_info, true)
-                        } else {
-                            (ctx.accounts.treasury_usdc_account.to_account_info(), false)
-                        }
+                    let cpi_accounts_commission = token::Transfer {
+                        from: ctx.accounts.user_usdc_account.to_account_info(),
+                        to: ctx.accounts.treasury_usdc_account.to_account_info(),
+                        authority: ctx.accounts.user.to_account_info(),
                     };
-                
-                let cpi_accounts_commission = token::Transfer {
-                    from: ctx.accounts.user_usdc_account.to_account_info(),
-                    to: commission_recipient,
-                    authority: ctx.accounts.user.to_account_info(),
-                };
-                let cpi_program_commission = ctx.accounts.token_program.to_account_info();
-                let cpi_ctx_commission = CpiContext::new(cpi_program_commission, cpi_accounts_commission);
-                token::transfer(cpi_ctx_commission, commission_amount)?;
-                
-                if is_referrer {
-                    msg!("DEBUG: Affiliate commission paid to referrer: {} USDC", commission_amount);
-                } else {
-                    msg!("DEBUG: Affiliate commission paid to treasury: {} USDC", commission_amount);
+                    let cpi_program_commission = ctx.accounts.token_program.to_account_info();
+                    let cpi_ctx_commission = CpiContext::new(cpi_program_commission, cpi_accounts_commission);
+                    token::transfer(cpi_ctx_commission, commission_amount)?;
+                    msg!("Affiliate commission paid to treasury: {} USDC", commission_amount);
                 }
-            }
+                commission_amount
+            };
+            let reserve_usdc = remaining_usdc.checked_sub(commission_amount).ok_or(ErrorCode::MathOverflow)?;
             
             let tokens_from_reserves = calculate_buy_amount(bonding_curve, reserve_usdc)?;
             require!(tokens_from_reserves > 0, ErrorCode::InvalidAmount);
-            require!(ctx.accounts.program_ever_account.amount >= tokens_from_reserves, ErrorCode::InsufficientFunds);
+            // Queue fills above already left the vault
+            ctx.accounts.program_ever_account.reload()?;
+            require!(
+                bonding_curve.available_reserve(ctx.accounts.program_ever_account.amount) >= tokens_from_reserves,
+                ErrorCode::InsufficientLiquidity
+            );
 
             // Transfer remaining USDC to treasury (after commission)
             let cpi_accounts_usdc = token::Transfer {
@@ -342,7 +296,8 @@ pub mod everrise_dex {
             token::transfer(cpi_ctx_usdc, reserve_usdc)?;
 
             // Transfer EVER tokens from reserves to buyer
-            let seeds = &[&b"bonding_curve"[..], &[bonding_curve.bump]];
+            let base_mint = bonding_curve.config.base_mint;
+            let seeds = &[&b"bonding_curve"[..], base_mint.as_ref(), &[bonding_curve.bump]];
             let signer_seeds = &[&seeds[..]];
             let cpi_accounts_ever = token::Transfer {
                 from: ctx.accounts.program_ever_account.to_account_info(),
@@ -354,16 +309,18 @@ pub mod everrise_dex {
             token::transfer(cpi_ctx_ever, tokens_from_reserves)?;
 
             // Update bonding curve state for reserve purchase (after commission)
-            bonding_curve.x = bonding_curve.x.checked_add(reserve_usdc).ok_or(ErrorCode::MathOverflow)?;
-            bonding_curve.y = bonding_curve.y.checked_sub(tokens_from_reserves).ok_or(ErrorCode::MathOverflow)?;
-            bonding_curve.k = u128::from(bonding_curve.x).checked_mul(u128::from(bonding_curve.y)).ok_or(ErrorCode::MathOverflow)?;
-            bonding_curve.circulating_supply = bonding_curve.circulating_supply.checked_add(tokens_from_reserves).ok_or(ErrorCode::MathOverflow)?;
+            transition::reserve_buy(bonding_curve, reserve_usdc, tokens_from_reserves)?;
+            reserve_volume = reserve_usdc;
             
-            total_ever_received = total_ever_received.checked_add(tokens_from_reserves).unwrap();
+            total_ever_received = total_ever_received.checked_add(tokens_from_reserves).ok_or(ErrorCode::MathOverflow)?;
         }
 
+        // Queue and reserve fills together must meet the buyer's minimum
+        require!(total_ever_received >= min_ever_out, ErrorCode::SlippageExceeded);
+
         // Update global state
-        bonding_curve.total_volume_24h = bonding_curve.total_volume_24h.checked_add(usdc_amount).ok_or(ErrorCode::MathOverflow)?;
+        let volume = Volume { buy: usdc_amount, sell: fill.usdc_spent, queue: fill.usdc_spent, reserve: reserve_volume };
+        record_volume(&mut ctx.accounts.volume_stats, bonding_curve, clock.unix_timestamp, volume)?;
         bonding_curve.current_price = calculate_effective_price(bonding_curve);
         bonding_curve.last_price_update = clock.unix_timestamp;
         
@@ -372,20 +329,25 @@ pub mod everrise_dex {
             usdc_amount,
             ever_received: total_ever_received,
             new_price: bonding_curve.current_price,
+            appreciation_bonus: fill.appreciation_bonus,
             timestamp: clock.unix_timestamp,
         });
 
         Ok(())
     }
 
-    /// Sell EVER tokens to the queue system with enhanced transaction safety
-    pub fn sell(ctx: Context<Sell>, ever_amount: u64) -> Result<()> {
+    /// Sell EVER tokens to the queue system with enhanced transaction safety.
+    /// Fails if the locked price would be below `min_locked_price` or `deadline` has passed.
+    pub fn sell(ctx: Context<Sell>, ever_amount: u64, min_locked_price: u64, deadline: Option<i64>) -> Result<()> {
+        require!(!ctx.accounts.bonding_curve.is_paused(PAUSE_SELL), ErrorCode::OperationPaused);
+
         // Validate input parameters for transaction safety
         require!(ever_amount > 0, ErrorCode::InvalidAmount);
-        require!(ever_amount <= 10_000_000_000_000_000, ErrorCode::AmountTooLarge); // Max 10M EVER per transaction
+        require!(ever_amount <= ctx.accounts.bonding_curve.config.max_base_amount, ErrorCode::AmountTooLarge); // Configured max EVER per transaction
 
         let bonding_curve = &mut ctx.accounts.bonding_curve;
         let clock = Clock::get()?;
+        check_deadline(deadline, clock.unix_timestamp)?;
 
         // Apply daily boost if needed before calculating price
         apply_daily_boost(bonding_curve, clock.unix_timestamp)?;
@@ -393,17 +355,14 @@ pub mod everrise_dex {
         // Calculate current effective price including all bonuses
         let current_price = calculate_effective_price(bonding_curve);
         require!(current_price > 0, ErrorCode::PriceCalculationFailed);
+        require!(current_price >= min_locked_price, ErrorCode::SlippageExceeded);
         
-        // Calculate USDC value with overflow protection
-        let usdc_value = ever_amount
-            .checked_mul(current_price)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_div(1_000_000_000) // Convert from 9 decimals to 6
-            .ok_or(ErrorCode::MathOverflow)?;
+        // Calculate USDC value with overflow protection (9 decimals to 6)
+        let usdc_value = everrise_math::usdc_value(ever_amount, current_price).map_err(ErrorCode::from)?;
 
         // Validate that the sell order has reasonable value
         require!(usdc_value > 0, ErrorCode::InvalidAmount);
-        require!(usdc_value <= 10_000_000_000_000, ErrorCode::AmountTooLarge); // Max 10M USDC value
+        require!(usdc_value <= bonding_curve.config.max_quote_amount, ErrorCode::AmountTooLarge); // Configured max USDC value
 
         // Check if there's sufficient liquidity in the bonding curve
         let organic_price = calculate_organic_price(bonding_curve);
@@ -419,9 +378,20 @@ pub mod everrise_dex {
         sell_order.timestamp = clock.unix_timestamp;
         sell_order.processed = false;
         sell_order.bump = ctx.bumps.sell_order;
-
-        let queue_position = bonding_curve.sell_queue_tail + 1;
-        bonding_curve.sell_queue_tail = queue_position;
+        sell_order.version = LAYOUT_VERSION;
+
+        // Link the new order behind the current tail
+        let order_id = bonding_curve.take_order_id()?;
+        sell_order.id = order_id;
+        sell_order.prev = bonding_curve.sell_queue_tail;
+        sell_order.next = 0;
+        if bonding_curve.sell_queue_tail == 0 {
+            bonding_curve.sell_queue_head = order_id;
+        } else {
+            let tail_sell_order = ctx.accounts.tail_sell_order.as_mut().ok_or(ErrorCode::QueueLinkMissing)?;
+            tail_sell_order.next = order_id;
+        }
+        bonding_curve.sell_queue_tail = order_id;
 
         // Transfer EVER tokens from user to program (atomic operation)
         let cpi_accounts = token::Transfer {
@@ -436,34 +406,161 @@ pub mod everrise_dex {
         );
 
         token::transfer(cpi_ctx, ever_amount)?;
+        transition::escrow_sell(bonding_curve, ever_amount)?;
 
         // Emit sell queue event
         emit!(SellQueueEvent {
             seller: ctx.accounts.user.key(),
             ever_amount,
             locked_price: current_price,
-            queue_position: queue_position - 1,
+            order_id,
             timestamp: clock.unix_timestamp,
         });
 
-        msg!("Sell: {} EVER tokens queued for {} USDC at price {} (position: {})", 
-             ever_amount, usdc_value, current_price, queue_position - 1);
+        msg!("Sell: {} EVER tokens queued for {} USDC at price {} (order: {})", 
+             ever_amount, usdc_value, current_price, order_id);
+
+        Ok(())
+    }
+
+    /// Cancel an open sell order - returns the unfilled EVER to the seller, unlinks the order
+    /// from the queue and closes the PDA
+    pub fn cancel_sell_order(ctx: Context<CancelSellOrder>, order_id: u64) -> Result<()> {
+        let clock = Clock::get()?;
+
+        // Only open orders can be cancelled
+        require!(!ctx.accounts.sell_order.processed, ErrorCode::OrderAlreadyProcessed);
+        require!(ctx.accounts.sell_order.remaining_amount > 0, ErrorCode::OrderAlreadyProcessed);
+
+        // Extract values before mutable borrows
+        let ever_amount = ctx.accounts.sell_order.remaining_amount;
+        let seller = ctx.accounts.sell_order.seller;
+        let bonding_curve_bump = ctx.accounts.bonding_curve.bump;
+        let base_mint = ctx.accounts.bonding_curve.config.base_mint;
+
+        // Return escrowed EVER from program to seller
+        let seeds = &[&b"bonding_curve"[..], base_mint.as_ref(), &[bonding_curve_bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.program_ever_account.to_account_info(),
+            to: ctx.accounts.seller_ever_account.to_account_info(),
+            authority: ctx.accounts.bonding_curve.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token::transfer(cpi_ctx, ever_amount)?;
+
+        let bonding_curve = &mut ctx.accounts.bonding_curve;
+        let sell_order = &mut ctx.accounts.sell_order;
+
+        transition::release_escrow(bonding_curve, ever_amount)?;
+
+        // Mark as processed (the account is closed to the seller on exit)
+        sell_order.remaining_amount = 0;
+        sell_order.processed = true;
+
+        // Unlink the order - its neighbours are only needed when it is not at that end of the queue
+        // (`prev` is stale on the head order, so the head is identified by id instead)
+        let is_head = bonding_curve.sell_queue_head == order_id;
+        let prev = if is_head { 0 } else { sell_order.prev };
+        let next = sell_order.next;
+        if is_head {
+            bonding_curve.sell_queue_head = next;
+        } else {
+            let prev_sell_order = ctx.accounts.prev_sell_order.as_mut().ok_or(ErrorCode::QueueLinkMissing)?;
+            prev_sell_order.next = next;
+        }
+        if bonding_curve.sell_queue_tail == order_id {
+            bonding_curve.sell_queue_tail = prev;
+        } else {
+            let next_sell_order = ctx.accounts.next_sell_order.as_mut().ok_or(ErrorCode::QueueLinkMissing)?;
+            next_sell_order.prev = prev;
+        }
+
+        emit!(SellOrderCancelledEvent {
+            seller,
+            ever_amount,
+            order_id,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Sell order {} cancelled: {} EVER returned to {}", order_id, ever_amount, seller);
+
+        Ok(())
+    }
+
+    /// Queue a buy order - escrows USDC in the program vault until process_buy_queue fills it
+    pub fn queue_buy(ctx: Context<QueueBuy>, usdc_amount: u64) -> Result<()> {
+        require!(!ctx.accounts.bonding_curve.is_paused(PAUSE_BUY), ErrorCode::OperationPaused);
+
+        // Validate input parameters
+        require!(usdc_amount > 0, ErrorCode::InvalidAmount);
+        require!(usdc_amount <= ctx.accounts.bonding_curve.config.max_quote_amount, ErrorCode::AmountTooLarge); // Configured max USDC per transaction
+
+        let bonding_curve = &mut ctx.accounts.bonding_curve;
+        let clock = Clock::get()?;
+
+        // Apply daily boost if needed before estimating tokens
+        apply_daily_boost(bonding_curve, clock.unix_timestamp)?;
+
+        // Estimate tokens at the current curve state (actual fill happens at processing time)
+        let estimated_tokens = calculate_buy_amount(bonding_curve, usdc_amount)?;
+
+        // Escrow USDC from buyer into the program vault
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.user_usdc_account.to_account_info(),
+            to: ctx.accounts.program_usdc_account.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, usdc_amount)?;
+
+        // Add to buy queue
+        let buy_order = &mut ctx.accounts.buy_order;
+
+        buy_order.buyer = ctx.accounts.user.key();
+        buy_order.usdc_amount = usdc_amount;
+        buy_order.expected_tokens = estimated_tokens;
+        buy_order.timestamp = clock.unix_timestamp;
+        buy_order.processed = false;
+        buy_order.bump = ctx.bumps.buy_order;
+        buy_order.version = LAYOUT_VERSION;
+
+        // Link the new order behind the current tail
+        let order_id = bonding_curve.take_order_id()?;
+        buy_order.id = order_id;
+        buy_order.next = 0;
+        if bonding_curve.buy_queue_tail == 0 {
+            bonding_curve.buy_queue_head = order_id;
+        } else {
+            let tail_buy_order = ctx.accounts.tail_buy_order.as_mut().ok_or(ErrorCode::QueueLinkMissing)?;
+            tail_buy_order.next = order_id;
+        }
+        bonding_curve.buy_queue_tail = order_id;
+
+        // Emit buy queue event
+        emit!(BuyQueueEvent {
+            buyer: ctx.accounts.user.key(),
+            usdc_amount,
+            estimated_tokens,
+            order_id,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Buy: {} USDC queued for ~{} EVER (order: {})",
+             usdc_amount, estimated_tokens, order_id);
 
         Ok(())
     }
 
     /// Process buy orders from the queue with partial fill support and transaction safety
-    pub fn process_buy_queue(ctx: Context<ProcessBuyQueue>) -> Result<()> {
-        // CRITICAL DEBUG: Check program EVER account at the ABSOLUTE BEGINNING
-        msg!("START");
-        msg!("ADDR: {}", ctx.accounts.program_ever_account.key());
-        msg!("BAL: {}", ctx.accounts.program_ever_account.amount);
-        msg!("END");
-        
+    pub fn process_buy_queue<'info>(ctx: Context<'_, '_, 'info, 'info, ProcessBuyQueue<'info>>) -> Result<()> {
+        require!(!ctx.accounts.bonding_curve.is_paused(PAUSE_QUEUE_PROCESSING), ErrorCode::OperationPaused);
+
         let clock = Clock::get()?;
 
         // Check if there are buy orders to process
-        if ctx.accounts.bonding_curve.buy_queue_head >= ctx.accounts.bonding_curve.buy_queue_tail {
+        if ctx.accounts.bonding_curve.buy_queue_head == 0 {
             return Err(ErrorCode::QueueEmpty.into());
         }
 
@@ -481,9 +578,17 @@ pub mod everrise_dex {
         let buyer = ctx.accounts.buy_order.buyer;
         let bonding_curve_bump = ctx.accounts.bonding_curve.bump;
 
+        // Consecutive sell orders to fill: named sell_order pair first, then remaining_accounts pairs
+        let mut sell_order_accounts = vec![
+            ctx.accounts.sell_order.to_account_info(),
+            ctx.accounts.seller_usdc_account.to_account_info(),
+        ];
+        sell_order_accounts.extend_from_slice(ctx.remaining_accounts);
+
         // Try to process with sell queue first, then reserves
         let result = process_buy_with_sell_queue(
-            &ctx.accounts,
+            ctx.accounts,
+            &sell_order_accounts,
             usdc_amount,
             buyer,
             bonding_curve_bump,
@@ -495,54 +600,20 @@ pub mod everrise_dex {
         let buy_order = &mut ctx.accounts.buy_order;
 
         // Update bonding curve state
-        bonding_curve.x = bonding_curve.x.checked_add(result.reserve_usdc).unwrap();
-        bonding_curve.y = bonding_curve.y.checked_sub(result.reserve_ever).unwrap();
-        bonding_curve.k = (bonding_curve.x as u128).checked_mul(bonding_curve.y as u128).unwrap();
-
-        // Update cumulative bonus
-        bonding_curve.cumulative_bonus = bonding_curve.cumulative_bonus
-            .checked_add(result.appreciation_bonus)
-            .unwrap();
-
-        // Update sell order if it was processed
-        if result.queue_usdc > 0 && bonding_curve.sell_queue_head < bonding_curve.sell_queue_tail {
-            // We know a sell order was processed, so deserialize and update it
-            let sell_order_info = ctx.accounts.sell_order.to_account_info();
-            let mut sell_order_data = sell_order_info.try_borrow_mut_data()?;
-            let sell_order = SellOrder::try_deserialize(&mut sell_order_data.as_ref())?;
-            
-            // Calculate how much of the sell order was consumed
-            let usdc_consumed = result.queue_usdc;
-            let ever_consumed = usdc_consumed
-                .checked_mul(1_000_000_000)
-                .unwrap_or(0)
-                .checked_div(sell_order.locked_price)
-                .unwrap_or(0);
-            
-            // Create updated sell order
-            let mut updated_sell_order = sell_order;
-            updated_sell_order.remaining_amount = updated_sell_order.remaining_amount
-                .checked_sub(ever_consumed)
-                .unwrap();
-            
-            // Mark as processed if fully consumed
-            if updated_sell_order.remaining_amount == 0 {
-                updated_sell_order.processed = true;
-                bonding_curve.sell_queue_head = bonding_curve.sell_queue_head.checked_add(1).unwrap();
-            }
-            
-            // Serialize the updated sell order back
-            updated_sell_order.try_serialize(&mut sell_order_data.as_mut())?;
-        }
+        transition::reserve_buy(bonding_curve, result.reserve_usdc, result.reserve_ever)?;
 
         // Mark buy order as processed
         buy_order.processed = true;
-        bonding_curve.buy_queue_head = bonding_curve.buy_queue_head.checked_add(1).unwrap();
-
-        // Update total volume
-        bonding_curve.total_volume_24h = bonding_curve.total_volume_24h
-            .checked_add(usdc_amount)
-            .unwrap();
+        bonding_curve.pop_buy_order(buy_order);
+
+        // Update volume
+        let volume = Volume {
+            buy: usdc_amount,
+            sell: result.queue_usdc,
+            queue: result.queue_usdc,
+            reserve: result.reserve_usdc,
+        };
+        record_volume(&mut ctx.accounts.volume_stats, bonding_curve, clock.unix_timestamp, volume)?;
 
         // Emit processed event
         emit!(BuyProcessedEvent {
@@ -551,6 +622,7 @@ pub mod everrise_dex {
             ever_tokens: result.total_ever_received,
             queue_transactions: result.queue_usdc,
             reserve_transactions: result.reserve_usdc,
+            appreciation_bonus: result.appreciation_bonus,
             timestamp: clock.unix_timestamp,
         });
 
@@ -562,13 +634,23 @@ pub mod everrise_dex {
 
     /// Process sell orders from the queue - handles matching with buy orders or direct processing
     pub fn process_sell_queue(ctx: Context<ProcessSellQueue>) -> Result<()> {
+        require!(!ctx.accounts.bonding_curve.is_paused(PAUSE_QUEUE_PROCESSING), ErrorCode::OperationPaused);
+
         let clock = Clock::get()?;
 
         // Check if there are sell orders to process
-        if ctx.accounts.bonding_curve.sell_queue_head >= ctx.accounts.bonding_curve.sell_queue_tail {
+        if ctx.accounts.bonding_curve.sell_queue_head == 0 {
             return Err(ErrorCode::QueueEmpty.into());
         }
 
+        // The sell order must be the PDA at the queue head
+        let bonding_curve_key = ctx.accounts.bonding_curve.key();
+        require_keys_eq!(
+            ctx.accounts.sell_order.key(),
+            sell_order_address(&bonding_curve_key, ctx.accounts.bonding_curve.sell_queue_head),
+            ErrorCode::InvalidSellOrder
+        );
+
         // Get the next sell order to process
         if ctx.accounts.sell_order.processed {
             return Err(ErrorCode::InvalidAmount.into()); // Already processed
@@ -584,6 +666,7 @@ pub mod everrise_dex {
         let locked_price = ctx.accounts.sell_order.locked_price;
         let seller = ctx.accounts.sell_order.seller;
         let bonding_curve_bump = ctx.accounts.bonding_curve.bump;
+        let base_mint = ctx.accounts.bonding_curve.config.base_mint;
 
         // Calculate USDC value for this sell order
         let usdc_value = ever_amount
@@ -593,7 +676,7 @@ pub mod everrise_dex {
             .ok_or(ErrorCode::MathOverflow)?;
 
         // Check if there are buy orders waiting
-        if ctx.accounts.bonding_curve.buy_queue_head < ctx.accounts.bonding_curve.buy_queue_tail {
+        if ctx.accounts.bonding_curve.buy_queue_head != 0 {
             // There are buy orders - this sell order will be matched when buy orders are processed
             // For now, we just validate the sell order and leave it in the queue
             msg!("Sell order {} EVER at price {} waiting for buy order matching", 
@@ -624,7 +707,7 @@ pub mod everrise_dex {
                     .ok_or(ErrorCode::MathOverflow)?;
 
                 // Prepare CPI accounts and signer
-                let seeds = &[b"bonding_curve", &[bonding_curve_bump][..]];
+                let seeds = &[&b"bonding_curve"[..], base_mint.as_ref(), &[bonding_curve_bump]];
                 let signer = &[&seeds[..]];
 
                 // Transfer USDC from treasury to seller
@@ -637,32 +720,32 @@ pub mod everrise_dex {
                 let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
                 token::transfer(cpi_ctx, usdc_to_pay)?;
 
-                // Burn EVER tokens (transfer to a burn address or reduce supply)
-                // For now, we'll transfer to the program's EVER account (effectively removing from circulation)
-                let cpi_accounts = token::Transfer {
-                    from: ctx.accounts.program_ever_account.to_account_info(),
-                    to: ctx.accounts.burn_ever_account.to_account_info(),
-                    authority: ctx.accounts.bonding_curve.to_account_info(),
-                };
-                let cpi_program = ctx.accounts.token_program.to_account_info();
-                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-                token::transfer(cpi_ctx, ever_to_sell)?;
+                // The escrowed EVER is already in program_ever_account - burn it or leave it there as reserve
+                let sell_settlement = ctx.accounts.bonding_curve.sell_settlement;
+                if sell_settlement == SELL_SETTLEMENT_BURN {
+                    let cpi_accounts = token::Burn {
+                        mint: ctx.accounts.ever_mint.to_account_info(),
+                        from: ctx.accounts.program_ever_account.to_account_info(),
+                        authority: ctx.accounts.bonding_curve.to_account_info(),
+                    };
+                    let cpi_program = ctx.accounts.token_program.to_account_info();
+                    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                    token::burn(cpi_ctx, ever_to_sell)?;
+                }
 
-                // Update bonding curve state
+                // Update bonding curve state - X decreases; Y only grows when the EVER is kept as reserve
                 let bonding_curve = &mut ctx.accounts.bonding_curve;
                 let sell_order = &mut ctx.accounts.sell_order;
-
-                // Update bonding curve (X decreases, Y increases)
-                bonding_curve.x = bonding_curve.x.checked_sub(usdc_to_pay).ok_or(ErrorCode::MathOverflow)?;
-                bonding_curve.y = bonding_curve.y.checked_add(ever_to_sell).ok_or(ErrorCode::MathOverflow)?;
-                bonding_curve.k = (bonding_curve.x as u128).checked_mul(bonding_curve.y as u128).ok_or(ErrorCode::MathOverflow)?;
+                transition::reserve_sell(bonding_curve, usdc_to_pay, ever_to_sell)?;
+                let volume = Volume { sell: usdc_to_pay, reserve: usdc_to_pay, ..Volume::default() };
+                record_volume(&mut ctx.accounts.volume_stats, bonding_curve, clock.unix_timestamp, volume)?;
 
                 // Update sell order
                 sell_order.remaining_amount = sell_order.remaining_amount.checked_sub(ever_to_sell).ok_or(ErrorCode::MathOverflow)?;
                 
                 if sell_order.remaining_amount == 0 {
                     sell_order.processed = true;
-                    bonding_curve.sell_queue_head = bonding_curve.sell_queue_head.checked_add(1).unwrap();
+                    bonding_curve.pop_sell_order(sell_order);
                 }
 
                 // Emit sell processed event
@@ -672,11 +755,13 @@ pub mod everrise_dex {
                     usdc_amount: usdc_to_pay,
                     locked_price,
                     processing_type: 1, // Direct to reserves
+                    appreciation_bonus: 0,
                     timestamp: clock.unix_timestamp,
                 });
 
-                msg!("Sell processed: {} EVER -> {} USDC (direct to reserves)", 
-                     ever_to_sell, usdc_to_pay);
+                msg!("Sell processed: {} EVER -> {} USDC (direct to reserves, {})", 
+                     ever_to_sell, usdc_to_pay,
+                     if sell_settlement == SELL_SETTLEMENT_BURN { "burned" } else { "kept as reserve" });
             }
         }
 
@@ -684,47 +769,76 @@ pub mod everrise_dex {
     }
 
 
-    /// Get smart contract version for debugging
-    pub fn get_version(ctx: Context<GetVersion>) -> Result<u32> {
-        Ok(16) // Version 16 - make sell_order optional in process_buy_queue
-    }
-    /// Bump buy_queue_tail by 1 to skip an occupied PDA
-    pub fn bump_buy_tail(ctx: Context<BumpBuyTail>) -> Result<()> {
-        let bonding_curve = &mut ctx.accounts.bonding_curve;
-        bonding_curve.buy_queue_tail = bonding_curve.buy_queue_tail.checked_add(1).unwrap();
-        Ok(())
+    /// Get smart contract code version and account layout version for debugging
+    pub fn get_version(ctx: Context<GetVersion>) -> Result<VersionInfo> {
+        Ok(VersionInfo {
+            code_version: 43, // Version 43 - affiliate program configured per market (layout 5)
+            layout_version: LAYOUT_VERSION,
+        })
     }
 
-    /// Bump sell_queue_tail by 1 to skip an occupied sell_order PDA
-    pub fn bump_sell_tail(ctx: Context<BumpSellTail>) -> Result<()> {
-        let bonding_curve = &mut ctx.accounts.bonding_curve;
-        bonding_curve.sell_queue_tail = bonding_curve.sell_queue_tail.checked_add(1).unwrap();
-        Ok(())
+    /// Check X/Y/K, reserve and escrow balances and queue ordering (read-only).
+    /// Pass every open sell order, head first, as remaining accounts.
+    pub fn verify_invariants(ctx: Context<VerifyInvariants>) -> Result<InvariantReport> {
+        invariants::handle_verify_invariants(ctx)
     }
 
-    /// Skip orphaned buy order accounts (emergency function)
-    pub fn skip_orphaned_buy_orders(ctx: Context<SkipOrphanedBuyOrders>, count: u32) -> Result<()> {
-        let bonding_curve = &mut ctx.accounts.bonding_curve;
-        
-        // Increment buy queue tail to skip orphaned accounts
-        bonding_curve.buy_queue_tail = bonding_curve.buy_queue_tail.checked_add(count as u64).unwrap();
-        
-        msg!("Skipped {} orphaned buy order accounts", count);
-        Ok(())
+    /// Create the VolumeStats account for a market opened before volume tracking
+    pub fn create_volume_stats(ctx: Context<CreateVolumeStats>) -> Result<()> {
+        volume::handle_create_volume_stats(ctx)
     }
 
+    /// Convert a bonding curve (and any order accounts passed as remaining accounts)
+    /// from an older layout to LAYOUT_VERSION in place (authority only)
+    pub fn migrate_bonding_curve<'info>(ctx: Context<'_, '_, 'info, 'info, MigrateBondingCurve<'info>>) -> Result<()> {
+        migration::handle_migrate_bonding_curve(ctx)
+    }
 
-    /// Manually apply daily boost (for testing and maintenance)
+    /// Move the curve of the original single-market deployment from [b"bonding_curve"]
+    /// to its per-market address, converting it to LAYOUT_VERSION (authority only, queues drained)
+    pub fn migrate_legacy_bonding_curve(ctx: Context<MigrateLegacyBondingCurve>) -> Result<()> {
+        migration::handle_migrate_legacy_bonding_curve(ctx)
+    }
+    /// Manually apply daily boost (for testing and maintenance, authority only)
     pub fn apply_daily_boost_manual(ctx: Context<ApplyDailyBoost>) -> Result<()> {
-        let bonding_curve = &mut ctx.accounts.bonding_curve;
-        let clock = Clock::get()?;
+        admin::handle_apply_daily_boost_manual(ctx)
+    }
 
-        // Apply daily boost
-        apply_daily_boost(bonding_curve, clock.unix_timestamp)?;
+    /// Propose a new authority - takes effect once the new key calls accept_authority
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        admin::handle_propose_authority(ctx, new_authority)
+    }
 
-        msg!("Daily boost manually applied at timestamp: {}", clock.unix_timestamp);
+    /// Accept a pending authority transfer (signed by the proposed authority)
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        admin::handle_accept_authority(ctx)
+    }
 
-        Ok(())
+    /// Update the treasury wallet that receives USDC (authority only)
+    pub fn set_treasury_wallet(ctx: Context<SetTreasuryWallet>, treasury_wallet: Pubkey) -> Result<()> {
+        admin::handle_set_treasury_wallet(ctx, treasury_wallet)
+    }
+
+    /// Update the affiliate program buy_smart pays commissions through - Pubkey::default()
+    /// sends every commission to the treasury. Referred buys fail until that program allow-lists
+    /// the market's [b"affiliate_authority", bonding_curve] PDA (authority only)
+    pub fn set_affiliate_program(ctx: Context<SetAffiliateProgram>, affiliate_program: Pubkey) -> Result<()> {
+        admin::handle_set_affiliate_program(ctx, affiliate_program)
+    }
+
+    /// Pause the given operations (PAUSE_* flags, authority only)
+    pub fn pause(ctx: Context<SetPaused>, operations: u8) -> Result<()> {
+        admin::handle_pause(ctx, operations)
+    }
+
+    /// Resume the given operations (PAUSE_* flags, authority only)
+    pub fn unpause(ctx: Context<SetPaused>, operations: u8) -> Result<()> {
+        admin::handle_unpause(ctx, operations)
+    }
+
+    /// Choose whether direct sells burn EVER or return it to reserve (SELL_SETTLEMENT_* mode, authority only)
+    pub fn set_sell_settlement(ctx: Context<SetSellSettlement>, sell_settlement: u8) -> Result<()> {
+        admin::handle_set_sell_settlement(ctx, sell_settlement)
     }
 
     /// Emergency refund function - refunds USDC to buyer if transaction fails
@@ -734,6 +848,7 @@ pub mod everrise_dex {
 
         // Extract values before mutable borrows
         let bonding_curve_bump = ctx.accounts.bonding_curve.bump;
+        let base_mint = ctx.accounts.bonding_curve.config.base_mint;
         let usdc_amount = ctx.accounts.buy_order.usdc_amount;
         let buyer = ctx.accounts.buy_order.buyer;
         let timestamp = ctx.accounts.buy_order.timestamp;
@@ -747,7 +862,7 @@ pub mod everrise_dex {
         require!(time_elapsed >= 3600, ErrorCode::RefundNotReady); // 1 hour = 3600 seconds
 
         // Prepare CPI accounts and signer for refund
-        let seeds = &[b"bonding_curve", &[bonding_curve_bump][..]];
+        let seeds = &[&b"bonding_curve"[..], base_mint.as_ref(), &[bonding_curve_bump]];
         let signer = &[&seeds[..]];
 
         // Refund USDC from program to buyer
@@ -766,7 +881,7 @@ pub mod everrise_dex {
 
         // Mark buy order as processed (refunded)
         buy_order.processed = true;
-        bonding_curve.buy_queue_head = bonding_curve.buy_queue_head.checked_add(1).unwrap();
+        bonding_curve.pop_buy_order(buy_order);
 
         // Emit emergency refund event
         emit!(EmergencyRefundEvent {
@@ -781,6 +896,20 @@ pub mod everrise_dex {
 
         Ok(())
     }
+
+    /// Close a processed order and return its rent to the original seller/buyer (permissionless crank).
+    /// Further (order, owner) pairs passed in remaining accounts are closed in the same call.
+    pub fn close_order<'info>(ctx: Context<'_, '_, 'info, 'info, CloseOrder<'info>>) -> Result<()> {
+        require!(ctx.remaining_accounts.len() % 2 == 0, ErrorCode::InvalidCloseAccounts);
+
+        close_processed_order(&ctx.accounts.order.to_account_info(), &ctx.accounts.owner.to_account_info())?;
+        for pair in ctx.remaining_accounts.chunks(2) {
+            close_processed_order(&pair[0], &pair[1])?;
+        }
+
+        msg!("Closed {} processed orders", 1 + ctx.remaining_accounts.len() / 2);
+        Ok(())
+    }
 }
 
 // Result struct for buy processing
@@ -793,177 +922,202 @@ struct BuyProcessingResult {
     appreciation_bonus: u64,
 }
 
-// Helper function to process buy with sell queue (one sell order at a time)
-fn process_buy_with_sell_queue(
-    accounts: &ProcessBuyQueue,
+// Result struct for sell queue fills
+#[derive(Debug, Default)]
+struct SellQueueFill {
+    ever_filled: u64,
+    usdc_spent: u64,
+    appreciation_bonus: u64,
+}
+
+/// Fill a buy FIFO against consecutive sell orders starting at sell_queue_head.
+/// `sell_order_accounts` holds (sell_order, seller_usdc_account) pairs in queue order
+/// (following each order's `next` link); filling stops when the USDC is exhausted, the
+/// queue is empty or the pairs run out. Each sell order must be the PDA at the current
+/// head and each seller USDC account must be a USDC token account owned by that order's seller.
+/// Every fill adds its appreciation bonus to cumulative_bonus.
+#[allow(clippy::too_many_arguments)]
+fn fill_from_sell_queue<'info>(
+    bonding_curve: &mut Account<'info, BondingCurve>,
+    sell_order_accounts: &[AccountInfo<'info>],
+    usdc_from: AccountInfo<'info>,
+    usdc_authority: AccountInfo<'info>,
+    buyer_ever_account: AccountInfo<'info>,
+    program_ever_account: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
     usdc_amount: u64,
-    _buyer: Pubkey,
-    bonding_curve_bump: u8,
-    _timestamp: i64,
-) -> Result<BuyProcessingResult> {
+    timestamp: i64,
+) -> Result<SellQueueFill> {
+    let pairs = sell_order_accounts.chunks_exact(2);
+    require!(pairs.remainder().is_empty(), ErrorCode::InvalidRemainingAccounts);
+
+    let mut fill = SellQueueFill::default();
     let mut remaining_usdc = usdc_amount;
-    let mut total_ever_received = 0u64;
-    let mut queue_usdc = 0u64;
-    let mut appreciation_bonus = 0u64;
 
-    // Prepare CPI accounts and signer
-    let seeds = &[b"bonding_curve", &[bonding_curve_bump][..]];
+    // Prepare CPI signer (bonding curve PDA owns the escrowed EVER)
+    let bonding_curve_info = bonding_curve.to_account_info();
+    let bonding_curve_key = bonding_curve.key();
+    let bonding_curve_bump = bonding_curve.bump;
+    let base_mint = bonding_curve.config.base_mint;
+    let seeds = &[&b"bonding_curve"[..], base_mint.as_ref(), &[bonding_curve_bump]];
     let signer = &[&seeds[..]];
 
-    // Try to fill from the current sell order if available
-    if accounts.bonding_curve.sell_queue_head < accounts.bonding_curve.sell_queue_tail {
-        // Try to deserialize the sell order
-        let sell_order_info = accounts.sell_order.to_account_info();
-        if sell_order_info.data_len() > 0 {
+    for pair in pairs {
+        if remaining_usdc == 0 || bonding_curve.sell_queue_head == 0 {
+            break;
+        }
+
+        let sell_order_info = &pair[0];
+        let seller_usdc_account = &pair[1];
+
+        // The sell order must be this program's PDA at the queue head
+        require_keys_eq!(
+            sell_order_info.key(),
+            sell_order_address(&bonding_curve_key, bonding_curve.sell_queue_head),
+            ErrorCode::InvalidSellOrder
+        );
+        require_keys_eq!(*sell_order_info.owner, crate::ID, ErrorCode::InvalidSellOrder);
+        let mut sell_order = {
             let sell_order_data = sell_order_info.try_borrow_data()?;
-            if let Ok(sell_order) = SellOrder::try_deserialize(&mut sell_order_data.as_ref()) {
-                if !sell_order.processed && sell_order.remaining_amount > 0 {
-                    // Get seller USDC account
-                    let seller_usdc_account = accounts.seller_usdc_account.to_account_info();
-                    // Calculate how much USDC we can spend on this sell order
-                    let usdc_for_this_sell = sell_order.remaining_amount
-                        .checked_mul(sell_order.locked_price)
-                        .unwrap_or(0)
-                        .checked_div(1_000_000_000) // Convert from 9 decimals to 6
-                        .unwrap_or(0);
-
-                    if usdc_for_this_sell > 0 {
-                        if usdc_for_this_sell <= remaining_usdc {
-                            // Full fill of this sell order
-                            let ever_from_sell = sell_order.remaining_amount;
-                            
-                            // Transfer USDC from program to seller
-                            let cpi_accounts = token::Transfer {
-                                from: accounts.program_usdc_account.to_account_info(),
-                                to: seller_usdc_account,
-                                authority: accounts.bonding_curve.to_account_info(),
-                            };
-                            let cpi_program = accounts.token_program.to_account_info();
-                            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-                            token::transfer(cpi_ctx, usdc_for_this_sell)?;
-
-                            // Transfer EVER tokens from program to buyer
-                            let cpi_accounts = token::Transfer {
-                                from: accounts.program_ever_account.to_account_info(),
-                                to: accounts.buyer_ever_account.to_account_info(),
-                                authority: accounts.bonding_curve.to_account_info(),
-                            };
-                            let cpi_program = accounts.token_program.to_account_info();
-                            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-                            token::transfer(cpi_ctx, ever_from_sell)?;
-
-                            // Update tracking
-                            remaining_usdc = remaining_usdc.checked_sub(usdc_for_this_sell).unwrap();
-                            total_ever_received = total_ever_received.checked_add(ever_from_sell).unwrap();
-                            queue_usdc = queue_usdc.checked_add(usdc_for_this_sell).unwrap();
-
-                            // Apply appreciation bonus for queue transaction
-                            let bonus = calculate_appreciation_bonus(
-                                &accounts.bonding_curve,
-                                usdc_for_this_sell,
-                                sell_order.locked_price
-                            )?;
-                            appreciation_bonus = appreciation_bonus.checked_add(bonus).unwrap();
-
-                        } else {
-                            // Partial fill of this sell order
-                            let ever_for_partial = remaining_usdc
-                                .checked_mul(1_000_000_000) // Convert to 9 decimals
-                                .unwrap_or(0)
-                                .checked_div(sell_order.locked_price)
-                                .unwrap_or(0);
-
-                            if ever_for_partial > 0 {
-                                // Transfer USDC from program to seller
-                                let cpi_accounts = token::Transfer {
-                                    from: accounts.program_usdc_account.to_account_info(),
-                                    to: seller_usdc_account,
-                                    authority: accounts.bonding_curve.to_account_info(),
-                                };
-                                let cpi_program = accounts.token_program.to_account_info();
-                                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-                                token::transfer(cpi_ctx, remaining_usdc)?;
-
-                                // Transfer EVER tokens from program to buyer
-                                let cpi_accounts = token::Transfer {
-                                    from: accounts.program_ever_account.to_account_info(),
-                                    to: accounts.buyer_ever_account.to_account_info(),
-                                    authority: accounts.bonding_curve.to_account_info(),
-                                };
-                                let cpi_program = accounts.token_program.to_account_info();
-                                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-                                token::transfer(cpi_ctx, ever_for_partial)?;
-
-                                // Update tracking
-                                total_ever_received = total_ever_received.checked_add(ever_for_partial).unwrap();
-                                queue_usdc = queue_usdc.checked_add(remaining_usdc).unwrap();
-
-                                // Apply appreciation bonus for queue transaction
-                                let bonus = calculate_appreciation_bonus(
-                                    &accounts.bonding_curve,
-                                    remaining_usdc,
-                                    sell_order.locked_price
-                                )?;
-                                appreciation_bonus = appreciation_bonus.checked_add(bonus).unwrap();
-
-                                remaining_usdc = 0; // Buy order fully satisfied
-                            }
-                        }
-                    }
-                }
-            }
+            SellOrder::try_deserialize(&mut sell_order_data.as_ref())?
+        };
+        if sell_order.processed || sell_order.remaining_amount == 0 {
+            break;
+        }
+
+        // Seller proceeds must go to the seller's own USDC account
+        require_keys_eq!(*seller_usdc_account.owner, token::ID, ErrorCode::InvalidSellerUsdcAccount);
+        let seller_usdc = {
+            let seller_usdc_data = seller_usdc_account.try_borrow_data()?;
+            TokenAccount::try_deserialize(&mut seller_usdc_data.as_ref())
+                .map_err(|_| ErrorCode::InvalidSellerUsdcAccount)?
+        };
+        require_keys_eq!(seller_usdc.owner, sell_order.seller, ErrorCode::SellerUsdcOwnerMismatch);
+        require_keys_eq!(seller_usdc.mint, bonding_curve.config.quote_mint, ErrorCode::InvalidSellerUsdcMint);
+
+        // Full fill when affordable, otherwise spend all remaining USDC on a partial fill
+        let order_fill = everrise_math::fill_sell_order(
+            &everrise_math::SellOrderQuote {
+                remaining_amount: sell_order.remaining_amount,
+                locked_price: sell_order.locked_price,
+            },
+            remaining_usdc,
+        )
+        .map_err(ErrorCode::from)?;
+        let (ever_from_sell, usdc_to_seller) = (order_fill.ever_amount, order_fill.usdc_amount);
+        if ever_from_sell == 0 {
+            break;
+        }
+
+        // Transfer USDC from buyer funds to seller
+        if usdc_to_seller > 0 {
+            let cpi_accounts = token::Transfer {
+                from: usdc_from.clone(),
+                to: seller_usdc_account.clone(),
+                authority: usdc_authority.clone(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer);
+            token::transfer(cpi_ctx, usdc_to_seller)?;
+        }
+
+        // Transfer escrowed EVER tokens from program to buyer
+        let cpi_accounts = token::Transfer {
+            from: program_ever_account.clone(),
+            to: buyer_ever_account.clone(),
+            authority: bonding_curve_info.clone(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer);
+        token::transfer(cpi_ctx, ever_from_sell)?;
+
+        // Apply appreciation bonus for queue transaction (X/Y is unchanged by queue fills)
+        let bonus = calculate_appreciation_bonus(bonding_curve, usdc_to_seller)?;
+        bonding_curve.cumulative_bonus = bonding_curve.cumulative_bonus.checked_add(bonus).ok_or(ErrorCode::MathOverflow)?;
+
+        // Update tracking
+        remaining_usdc = remaining_usdc.checked_sub(usdc_to_seller).ok_or(ErrorCode::MathOverflow)?;
+        fill.ever_filled = fill.ever_filled.checked_add(ever_from_sell).ok_or(ErrorCode::MathOverflow)?;
+        fill.usdc_spent = fill.usdc_spent.checked_add(usdc_to_seller).ok_or(ErrorCode::MathOverflow)?;
+        fill.appreciation_bonus = fill.appreciation_bonus.checked_add(bonus).ok_or(ErrorCode::MathOverflow)?;
+
+        // Update sell order, advancing the queue once it is fully consumed
+        sell_order.remaining_amount = sell_order.remaining_amount
+            .checked_sub(ever_from_sell)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if sell_order.remaining_amount == 0 {
+            sell_order.processed = true;
+            bonding_curve.pop_sell_order(&sell_order);
+        }
+        {
+            let mut sell_order_data = sell_order_info.try_borrow_mut_data()?;
+            sell_order.try_serialize(&mut sell_order_data.as_mut())?;
+        }
+
+        emit!(SellProcessedEvent {
+            seller: sell_order.seller,
+            ever_amount: ever_from_sell,
+            usdc_amount: usdc_to_seller,
+            locked_price: sell_order.locked_price,
+            processing_type: 0, // Queue matching
+            appreciation_bonus: bonus,
+            timestamp,
+        });
+
+        // A partial fill means the buy is exhausted
+        if sell_order.remaining_amount > 0 {
+            break;
         }
     }
 
+    // Bonuses above use the circulating supply from before the buy, like everrise_math::simulate_fill
+    transition::release_escrow(bonding_curve, fill.ever_filled)?;
+
+    Ok(fill)
+}
+
+// Helper function to process buy with sell queue (consecutive sell orders, FIFO)
+fn process_buy_with_sell_queue<'info>(
+    accounts: &mut ProcessBuyQueue<'info>,
+    sell_order_accounts: &[AccountInfo<'info>],
+    usdc_amount: u64,
+    _buyer: Pubkey,
+    bonding_curve_bump: u8,
+    timestamp: i64,
+) -> Result<BuyProcessingResult> {
+    // Prepare CPI accounts and signer
+    let base_mint = accounts.bonding_curve.config.base_mint;
+    let seeds = &[&b"bonding_curve"[..], base_mint.as_ref(), &[bonding_curve_bump]];
+    let signer = &[&seeds[..]];
+
+    // Fill from the sell queue first - escrowed USDC is paid out by the bonding curve PDA
+    let bonding_curve_info = accounts.bonding_curve.to_account_info();
+    let fill = fill_from_sell_queue(
+        &mut accounts.bonding_curve,
+        sell_order_accounts,
+        accounts.program_usdc_account.to_account_info(),
+        bonding_curve_info,
+        accounts.buyer_ever_account.to_account_info(),
+        accounts.program_ever_account.to_account_info(),
+        accounts.token_program.to_account_info(),
+        usdc_amount,
+        timestamp,
+    )?;
+    let remaining_usdc = usdc_amount.checked_sub(fill.usdc_spent).ok_or(ErrorCode::MathOverflow)?;
+    let mut total_ever_received = fill.ever_filled;
+    let queue_usdc = fill.usdc_spent;
+    let appreciation_bonus = fill.appreciation_bonus;
+
     // If there's still USDC remaining, buy from reserves
     let mut reserve_usdc = 0u64;
-    // CRITICAL DEBUG: Check program EVER account at the VERY BEGINNING
-    msg!("CRITICAL DEBUG: Starting process_buy_queue");
-    msg!("CRITICAL DEBUG: Program EVER account address: {}", accounts.program_ever_account.key());
-    msg!("CRITICAL DEBUG: Program EVER account balance: {}", accounts.program_ever_account.amount);
-    
     let mut reserve_ever = 0u64;
     
     if remaining_usdc > 0 {
-        msg!("CRITICAL DEBUG: About to calculate buy amount for USDC: {}", remaining_usdc);
-        msg!("CRITICAL DEBUG: Bonding curve X: {}", accounts.bonding_curve.x);
-        msg!("CRITICAL DEBUG: Bonding curve Y: {}", accounts.bonding_curve.y);
-        msg!("CRITICAL DEBUG: Bonding curve K: {}", accounts.bonding_curve.k);
         reserve_ever = calculate_buy_amount(&accounts.bonding_curve, remaining_usdc)?;
-        msg!("CRITICAL DEBUG: Calculated reserve EVER needed: {}", reserve_ever);
-        msg!("CRITICAL DEBUG: Sufficient balance: {}", accounts.program_ever_account.amount >= reserve_ever);
-        
-        // Check if this is the correct account
-        let expected_program_ever_account = Pubkey::from_str("2zxSEQRegNfZddGHC7xBcCtXzfafnMKdEhh2rp3KDzrz").unwrap();
-        msg!("CRITICAL DEBUG: Expected program EVER account: {}", expected_program_ever_account);
-        msg!("CRITICAL DEBUG: Account matches expected: {}", accounts.program_ever_account.key() == expected_program_ever_account);
-        
-        // Check if the account is owned by the bonding curve PDA
-        msg!("CRITICAL DEBUG: Program EVER account owner: {}", accounts.program_ever_account.owner);
-        msg!("CRITICAL DEBUG: Bonding curve PDA: {}", accounts.bonding_curve.key());
-        msg!("CRITICAL DEBUG: Account owned by bonding curve: {}", accounts.program_ever_account.owner == accounts.bonding_curve.key());
-        
-        // Check if the account is a token account
-        msg!("CRITICAL DEBUG: Program EVER account is token account: {}", accounts.program_ever_account.owner == token::ID);
-        msg!("CRITICAL DEBUG: Program EVER account data length: {}", accounts.program_ever_account.to_account_info().data_len());
-        
-        // Check if the account is empty
-        msg!("CRITICAL DEBUG: Program EVER account is empty: {}", accounts.program_ever_account.amount == 0);
-        msg!("CRITICAL DEBUG: Program EVER account has tokens: {}", accounts.program_ever_account.amount > 0);
-        
-        // Check if the account is the right mint
-        msg!("CRITICAL DEBUG: Program EVER account mint: {}", accounts.program_ever_account.mint);
-        let expected_ever_mint = Pubkey::from_str("3q4YFYMKHrdYw5FPANQ7nrCQMT4t12XKgzYX8JaTeEx8").unwrap();
-        msg!("CRITICAL DEBUG: Expected EVER mint: {}", expected_ever_mint);
-        msg!("CRITICAL DEBUG: Mint matches expected: {}", accounts.program_ever_account.mint == expected_ever_mint);
-        
-        // Safety check: cap the transfer at available balance
-        let actual_transfer_amount = if reserve_ever > accounts.program_ever_account.amount {
-            msg!("Capping transfer amount from {} to {}", reserve_ever, accounts.program_ever_account.amount);
-            accounts.program_ever_account.amount
-        } else {
-            reserve_ever
-        };
+        // The vault also escrows open sell orders, which must not be sold from reserves
+        accounts.program_ever_account.reload()?;
+        require!(
+            reserve_ever <= accounts.bonding_curve.available_reserve(accounts.program_ever_account.amount),
+            ErrorCode::InsufficientLiquidity
+        );
 
         // Transfer USDC from program to treasury
         let cpi_accounts = token::Transfer {
@@ -975,42 +1129,18 @@ fn process_buy_with_sell_queue(
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
         token::transfer(cpi_ctx, remaining_usdc)?;
         
-        // Debug logging before transfer
-        msg!("About to transfer EVER tokens:");
-        msg!("  From: {}", accounts.program_ever_account.key());
-        msg!("  To: {}", accounts.buyer_ever_account.key());
-        msg!("  Amount: {}", actual_transfer_amount);
-        msg!("  Authority: {}", accounts.bonding_curve.key());
-        msg!("  Program EVER balance before transfer: {}", accounts.program_ever_account.amount);
-        msg!("  Buyer EVER balance before transfer: {}", accounts.buyer_ever_account.amount);
-        msg!("About to transfer {} EVER tokens from program account", actual_transfer_amount);
-        msg!("Transfer amount in EVER (with decimals): {}", actual_transfer_amount / 1_000_000_000);
-        // Ensure we have a meaningful amount to transfer (at least 1 EVER token)
-        if actual_transfer_amount == 0 {
-            msg!("WARNING: Calculated transfer amount is 0, skipping transfer");
-            msg!("  USDC amount: {}", remaining_usdc);
-            msg!("  Bonding curve X: {}", accounts.bonding_curve.x);
-            msg!("  Bonding curve Y: {}", accounts.bonding_curve.y);
-            msg!("  Bonding curve K: {}", accounts.bonding_curve.k);
-            // Don't fail, just skip the transfer
+        // Dust too small to buy any EVER still joins X
+        if reserve_ever == 0 {
+            msg!("{} USDC buys no EVER from reserves, skipping transfer", remaining_usdc);
             return Ok(BuyProcessingResult {
-                total_ever_received: 0,
-                queue_usdc: 0,
+                total_ever_received,
+                queue_usdc,
                 reserve_usdc: remaining_usdc,
                 reserve_ever: 0,
-                appreciation_bonus: 0,
+                appreciation_bonus,
             });
         }
         
-        // Check if the program EVER account has enough balance
-        if accounts.program_ever_account.amount < actual_transfer_amount {
-            msg!("ERROR: Program EVER account has insufficient balance!");
-            msg!("  Required: {}", actual_transfer_amount);
-            msg!("  Available: {}", accounts.program_ever_account.amount);
-            msg!("  Shortfall: {}", actual_transfer_amount - accounts.program_ever_account.amount);
-            return Err(ErrorCode::InsufficientFunds.into());
-        }
-        
         // Transfer EVER tokens from program account to buyer using bonding curve PDA as authority
         let cpi_accounts = token::Transfer {
             from: accounts.program_ever_account.to_account_info(),
@@ -1018,12 +1148,12 @@ fn process_buy_with_sell_queue(
             authority: accounts.bonding_curve.to_account_info(),
         };
         let cpi_program = accounts.token_program.to_account_info();
-        let signer_seeds: &[&[&[u8]]] = &[&[b"bonding_curve", &[accounts.bonding_curve.bump]]];
+        let signer_seeds: &[&[&[u8]]] = &[&[b"bonding_curve", base_mint.as_ref(), &[accounts.bonding_curve.bump]]];
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
-        token::transfer(cpi_ctx, actual_transfer_amount)?;
+        token::transfer(cpi_ctx, reserve_ever)?;
 
         reserve_usdc = remaining_usdc;
-        total_ever_received = total_ever_received.checked_add(actual_transfer_amount).unwrap();
+        total_ever_received = total_ever_received.checked_add(reserve_ever).ok_or(ErrorCode::MathOverflow)?;
     }
 
     Ok(BuyProcessingResult {
@@ -1036,15 +1166,40 @@ fn process_buy_with_sell_queue(
 }
 
 #[derive(Accounts)]
-pub struct Initialize<'info> {
+#[instruction(treasury_wallet: Pubkey, params: CurveParams)]
+pub struct CreateMarket<'info> {
     #[account(
         init,
         payer = authority,
         space = 8 + BondingCurve::INIT_SPACE,
-        seeds = [b"bonding_curve"],
+        seeds = [b"bonding_curve", params.base_mint.as_ref()],
         bump
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
+
+    // Token being launched - only its mint authority can open a market for it, so the
+    // one market per mint can't be squatted (see create_market)
+    #[account(
+        address = params.base_mint @ ErrorCode::InvalidCurveParams,
+        constraint = base_mint.decimals == 9 @ ErrorCode::InvalidCurveParams,
+        constraint = base_mint.mint_authority == COption::Some(authority.key()) @ ErrorCode::Unauthorized
+    )]
+    pub base_mint: Account<'info, Mint>,
+
+    #[account(
+        address = params.quote_mint @ ErrorCode::InvalidCurveParams,
+        constraint = quote_mint.decimals == 6 @ ErrorCode::InvalidCurveParams
+    )]
+    pub quote_mint: Account<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + VolumeStats::INIT_SPACE,
+        seeds = [b"volume_stats", bonding_curve.key().as_ref()],
+        bump
+    )]
+    pub volume_stats: Box<Account<'info, VolumeStats>>,
     
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -1057,7 +1212,7 @@ pub struct Initialize<'info> {
 pub struct Buy<'info> {
     #[account(
         mut,
-        seeds = [b"bonding_curve"],
+        seeds = [b"bonding_curve", bonding_curve.config.base_mint.as_ref()],
         bump = bonding_curve.bump
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
@@ -1069,7 +1224,7 @@ pub struct Buy<'info> {
     #[account(
         mut,
         constraint = user_usdc_account.owner == user.key(),
-        constraint = user_usdc_account.mint == USDC_MINT
+        constraint = user_usdc_account.mint == bonding_curve.config.quote_mint
     )]
     pub user_usdc_account: Account<'info, TokenAccount>,
     
@@ -1077,7 +1232,7 @@ pub struct Buy<'info> {
     #[account(
         mut,
         constraint = user_ever_account.owner == user.key(),
-        constraint = user_ever_account.mint == EVER_MINT
+        constraint = user_ever_account.mint == bonding_curve.config.base_mint
     )]
     pub user_ever_account: Account<'info, TokenAccount>,
     
@@ -1085,7 +1240,7 @@ pub struct Buy<'info> {
     #[account(
         mut,
         constraint = treasury_usdc_account.owner == bonding_curve.treasury_wallet,
-        constraint = treasury_usdc_account.mint == USDC_MINT
+        constraint = treasury_usdc_account.mint == bonding_curve.config.quote_mint
     )]
     pub treasury_usdc_account: Account<'info, TokenAccount>,
     
@@ -1093,10 +1248,18 @@ pub struct Buy<'info> {
     #[account(
         mut,
         constraint = program_ever_account.owner == bonding_curve.key(),
-        constraint = program_ever_account.mint == EVER_MINT
+        constraint = program_ever_account.mint == bonding_curve.config.base_mint
     )]
     pub program_ever_account: Account<'info, TokenAccount>,
     
+    // Hourly volume buckets for this curve
+    #[account(
+        mut,
+        seeds = [b"volume_stats", bonding_curve.key().as_ref()],
+        bump = volume_stats.bump
+    )]
+    pub volume_stats: Box<Account<'info, VolumeStats>>,
+    
     pub token_program: Program<'info, Token>,
 }
 
@@ -1104,7 +1267,7 @@ pub struct Buy<'info> {
 pub struct BuyWithSellProcessing<'info> {
     #[account(
         mut,
-        seeds = [b"bonding_curve"],
+        seeds = [b"bonding_curve", bonding_curve.config.base_mint.as_ref()],
         bump = bonding_curve.bump
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
@@ -1116,7 +1279,7 @@ pub struct BuyWithSellProcessing<'info> {
     #[account(
         mut,
         constraint = user_usdc_account.owner == user.key(),
-        constraint = user_usdc_account.mint == USDC_MINT
+        constraint = user_usdc_account.mint == bonding_curve.config.quote_mint
     )]
     pub user_usdc_account: Account<'info, TokenAccount>,
     
@@ -1124,7 +1287,7 @@ pub struct BuyWithSellProcessing<'info> {
     #[account(
         mut,
         constraint = user_ever_account.owner == user.key(),
-        constraint = user_ever_account.mint == EVER_MINT
+        constraint = user_ever_account.mint == bonding_curve.config.base_mint
     )]
     pub user_ever_account: Account<'info, TokenAccount>,
     
@@ -1132,7 +1295,7 @@ pub struct BuyWithSellProcessing<'info> {
     #[account(
         mut,
         constraint = treasury_usdc_account.owner == bonding_curve.treasury_wallet,
-        constraint = treasury_usdc_account.mint == USDC_MINT
+        constraint = treasury_usdc_account.mint == bonding_curve.config.quote_mint
     )]
     pub treasury_usdc_account: Account<'info, TokenAccount>,
     
@@ -1140,26 +1303,70 @@ pub struct BuyWithSellProcessing<'info> {
     #[account(
         mut,
         constraint = program_ever_account.owner == bonding_curve.key(),
-        constraint = program_ever_account.mint == EVER_MINT
+        constraint = program_ever_account.mint == bonding_curve.config.base_mint
     )]
     pub program_ever_account: Account<'info, TokenAccount>,
     
-    // Sell order account - only used when sell queue is not empty
-    /// CHECK: This account is only validated/used when sell queue is not empty
+    // Sell order account at sell_queue_head - further (sell_order, seller_usdc_account) pairs go in remaining_accounts,
+    // followed by the referrer's upline accounts
+    /// CHECK: Validated in fill_from_sell_queue as the SellOrder PDA at sell_queue_head when the sell queue is not empty
+    #[account(mut)]
     pub sell_order: UncheckedAccount<'info>,
     
     // Seller's USDC account - only used when processing sell orders
-    /// CHECK: This account is only validated/used when processing a sell order
+    /// CHECK: Validated in fill_from_sell_queue as a USDC account owned by sell_order.seller
+    #[account(mut)]
     pub seller_usdc_account: UncheckedAccount<'info>,
     
-    // Referrer account - always required but may be dummy if no referrer
-    /// CHECK: This account is validated in the instruction logic
+    // Buyer's Referral in the affiliate program - commissions go to the treasury while it does not exist
+    /// CHECK: PDA of the affiliate program; process_commission checks it names `referrer`
+    #[account(
+        seeds = [b"referral", user.key().as_ref()],
+        bump,
+        seeds::program = bonding_curve.affiliate_program
+    )]
+    pub referral: UncheckedAccount<'info>,
+    
+    // Buyer's referrer, as recorded in the Referral - any key (e.g. the buyer) when there is none
+    /// CHECK: Only seeds referral_registry; the affiliate program checks it against the Referral
     pub referrer: UncheckedAccount<'info>,
     
-    // Referrer's USDC account - always required but may be treasury if no referrer
-    /// CHECK: This account is validated in the instruction logic
-    #[account(mut)]
-    pub referrer_usdc_account: UncheckedAccount<'info>,
+    // Affiliate commission vault - referral commissions accrue here until the referrers claim them
+    /// CHECK: PDA of the affiliate program; validated by process_commission as its USDC vault
+    #[account(mut, seeds = [b"commission_vault"], bump, seeds::program = bonding_curve.affiliate_program)]
+    pub commission_vault: UncheckedAccount<'info>,
+    
+    // Referrer's stats in the affiliate program, updated with each commission
+    /// CHECK: PDA of the affiliate program; its contents are validated by process_commission
+    #[account(
+        mut,
+        seeds = [b"referral_registry", referrer.key().as_ref()],
+        bump,
+        seeds::program = bonding_curve.affiliate_program
+    )]
+    pub referral_registry: UncheckedAccount<'info>,
+    
+    /// CHECK: The affiliate program's global stats PDA, updated by process_commission
+    #[account(mut, seeds = [b"affiliate_program"], bump, seeds::program = bonding_curve.affiliate_program)]
+    pub affiliate_state: UncheckedAccount<'info>,
+    
+    /// CHECK: The market's affiliate program, invoked for commissions
+    #[account(address = bonding_curve.affiliate_program)]
+    pub affiliate_program: UncheckedAccount<'info>,
+    
+    // Signs process_commission for this market - the affiliate program accepts commissions only
+    // from the markets' PDAs it has allow-listed
+    /// CHECK: PDA of this program with no data
+    #[account(seeds = [b"affiliate_authority", bonding_curve.key().as_ref()], bump)]
+    pub affiliate_authority: UncheckedAccount<'info>,
+    
+    // Hourly volume buckets for this curve
+    #[account(
+        mut,
+        seeds = [b"volume_stats", bonding_curve.key().as_ref()],
+        bump = volume_stats.bump
+    )]
+    pub volume_stats: Box<Account<'info, VolumeStats>>,
     
     pub token_program: Program<'info, Token>,
 }
@@ -1168,7 +1375,7 @@ pub struct BuyWithSellProcessing<'info> {
 pub struct Sell<'info> {
     #[account(
         mut,
-        seeds = [b"bonding_curve"],
+        seeds = [b"bonding_curve", bonding_curve.config.base_mint.as_ref()],
         bump = bonding_curve.bump
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
@@ -1177,10 +1384,18 @@ pub struct Sell<'info> {
         init,
         payer = user,
         space = 8 + SellOrder::INIT_SPACE,
-        seeds = [b"sell_order", (bonding_curve.sell_queue_tail + 1).to_le_bytes().as_ref()],
+        seeds = [b"sell_order", bonding_curve.key().as_ref(), bonding_curve.next_order_id.to_le_bytes().as_ref()],
         bump
     )]
     pub sell_order: Account<'info, SellOrder>,
+
+    // Current last sell order - required unless the sell queue is empty, so it can link to the new order
+    #[account(
+        mut,
+        seeds = [b"sell_order", bonding_curve.key().as_ref(), bonding_curve.sell_queue_tail.to_le_bytes().as_ref()],
+        bump = tail_sell_order.bump
+    )]
+    pub tail_sell_order: Option<Account<'info, SellOrder>>,
     
     #[account(mut)]
     pub user: Signer<'info>,
@@ -1188,49 +1403,189 @@ pub struct Sell<'info> {
     #[account(mut)]
     pub user_ever_account: Account<'info, TokenAccount>,
     
-    #[account(mut)]
+    // Program EVER account - escrows the EVER until the sell order settles
+    #[account(
+        mut,
+        constraint = program_ever_account.owner == bonding_curve.key(),
+        constraint = program_ever_account.mint == bonding_curve.config.base_mint
+    )]
     pub program_ever_account: Account<'info, TokenAccount>,
     
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct CancelSellOrder<'info> {
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", bonding_curve.config.base_mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+        mut,
+        close = seller,
+        has_one = seller,
+        seeds = [b"sell_order", bonding_curve.key().as_ref(), order_id.to_le_bytes().as_ref()],
+        bump = sell_order.bump
+    )]
+    pub sell_order: Account<'info, SellOrder>,
+
+    // Queue neighbours - the previous order is required unless sell_order is the head,
+    // the next order unless it is the tail
+    #[account(
+        mut,
+        seeds = [b"sell_order", bonding_curve.key().as_ref(), sell_order.prev.to_le_bytes().as_ref()],
+        bump = prev_sell_order.bump
+    )]
+    pub prev_sell_order: Option<Account<'info, SellOrder>>,
+
+    #[account(
+        mut,
+        seeds = [b"sell_order", bonding_curve.key().as_ref(), sell_order.next.to_le_bytes().as_ref()],
+        bump = next_sell_order.bump
+    )]
+    pub next_sell_order: Option<Account<'info, SellOrder>>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    // Seller's EVER account (receives the unfilled tokens)
+    #[account(
+        mut,
+        constraint = seller_ever_account.owner == seller.key(),
+        constraint = seller_ever_account.mint == bonding_curve.config.base_mint
+    )]
+    pub seller_ever_account: Account<'info, TokenAccount>,
+
+    // Program EVER account (holds escrowed sell order tokens)
+    #[account(
+        mut,
+        constraint = program_ever_account.owner == bonding_curve.key(),
+        constraint = program_ever_account.mint == bonding_curve.config.base_mint
+    )]
+    pub program_ever_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct QueueBuy<'info> {
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", bonding_curve.config.base_mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + BuyOrder::INIT_SPACE,
+        seeds = [b"buy_order", bonding_curve.key().as_ref(), bonding_curve.next_order_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub buy_order: Account<'info, BuyOrder>,
+
+    // Current last buy order - required unless the buy queue is empty, so it can link to the new order
+    #[account(
+        mut,
+        seeds = [b"buy_order", bonding_curve.key().as_ref(), bonding_curve.buy_queue_tail.to_le_bytes().as_ref()],
+        bump = tail_buy_order.bump
+    )]
+    pub tail_buy_order: Option<Account<'info, BuyOrder>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    // User's USDC account
+    #[account(
+        mut,
+        constraint = user_usdc_account.owner == user.key(),
+        constraint = user_usdc_account.mint == bonding_curve.config.quote_mint
+    )]
+    pub user_usdc_account: Account<'info, TokenAccount>,
+
+    // Program USDC account (escrows USDC for queued buy orders)
+    #[account(
+        mut,
+        constraint = program_usdc_account.owner == bonding_curve.key(),
+        constraint = program_usdc_account.mint == bonding_curve.config.quote_mint
+    )]
+    pub program_usdc_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct ProcessBuyQueue<'info> {
     #[account(
         mut,
-        seeds = [b"bonding_curve"],
+        seeds = [b"bonding_curve", bonding_curve.config.base_mint.as_ref()],
         bump = bonding_curve.bump
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
     
     #[account(
         mut,
-        seeds = [b"buy_order", bonding_curve.buy_queue_head.to_le_bytes().as_ref()],
+        seeds = [b"buy_order", bonding_curve.key().as_ref(), bonding_curve.buy_queue_head.to_le_bytes().as_ref()],
         bump = buy_order.bump
     )]
     pub buy_order: Account<'info, BuyOrder>,
     
-    // Sell order account - will be validated in the instruction if needed
-    /// CHECK: This account is only validated/used when sell queue is not empty
+    // Sell order account at sell_queue_head - further (sell_order, seller_usdc_account) pairs go in remaining_accounts
+    /// CHECK: Validated in fill_from_sell_queue as the SellOrder PDA at sell_queue_head when the sell queue is not empty
+    #[account(mut)]
     pub sell_order: UncheckedAccount<'info>,
     
-    #[account(mut)]
+    // Program USDC account (escrows USDC for queued buy orders)
+    #[account(
+        mut,
+        constraint = program_usdc_account.owner == bonding_curve.key(),
+        constraint = program_usdc_account.mint == bonding_curve.config.quote_mint
+    )]
     pub program_usdc_account: Account<'info, TokenAccount>,
     
-    #[account(mut)]
+    // Program EVER account (holds reserves and escrowed sell orders)
+    #[account(
+        mut,
+        constraint = program_ever_account.owner == bonding_curve.key(),
+        constraint = program_ever_account.mint == bonding_curve.config.base_mint
+    )]
     pub program_ever_account: Account<'info, TokenAccount>,
     
-    #[account(mut)]
+    // The queued buyer's EVER account - receives the fill
+    #[account(
+        mut,
+        constraint = buyer_ever_account.owner == buy_order.buyer,
+        constraint = buyer_ever_account.mint == bonding_curve.config.base_mint
+    )]
     pub buyer_ever_account: Account<'info, TokenAccount>,
     
-    // Seller account - will be validated in the instruction if needed
-    /// CHECK: This account is only validated/used when processing a sell order
+    // Seller's USDC account - only used when processing sell orders
+    /// CHECK: Validated in fill_from_sell_queue as a USDC account owned by sell_order.seller
+    #[account(mut)]
     pub seller_usdc_account: UncheckedAccount<'info>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = treasury_usdc_account.owner == bonding_curve.treasury_wallet,
+        constraint = treasury_usdc_account.mint == bonding_curve.config.quote_mint
+    )]
     pub treasury_usdc_account: Account<'info, TokenAccount>,
     
+    // Hourly volume buckets for this curve
+    #[account(
+        mut,
+        seeds = [b"volume_stats", bonding_curve.key().as_ref()],
+        bump = volume_stats.bump
+    )]
+    pub volume_stats: Box<Account<'info, VolumeStats>>,
+    
     pub token_program: Program<'info, Token>,
 }
 
@@ -1238,44 +1593,51 @@ pub struct ProcessBuyQueue<'info> {
 pub struct ProcessSellQueue<'info> {
     #[account(
         mut,
-        seeds = [b"bonding_curve"],
+        seeds = [b"bonding_curve", bonding_curve.config.base_mint.as_ref()],
         bump = bonding_curve.bump
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
     
+    // Sell order at sell_queue_head - validated against the head in process_sell_queue
+    #[account(mut)]
+    pub sell_order: Account<'info, SellOrder>,
+    
+    // Program EVER account (holds reserves and escrowed sell orders) - burned from under SELL_SETTLEMENT_BURN
     #[account(
         mut,
-        seeds = [b"sell_order", bonding_curve.sell_queue_head.to_le_bytes().as_ref()],
-        bump = sell_order.bump
+        constraint = program_ever_account.owner == bonding_curve.key(),
+        constraint = program_ever_account.mint == bonding_curve.config.base_mint
     )]
-    pub sell_order: Account<'info, SellOrder>,
-    
-    #[account(mut)]
     pub program_ever_account: Account<'info, TokenAccount>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = seller_usdc_account.owner == sell_order.seller @ ErrorCode::SellerUsdcOwnerMismatch,
+        constraint = seller_usdc_account.mint == bonding_curve.config.quote_mint @ ErrorCode::InvalidSellerUsdcMint
+    )]
     pub seller_usdc_account: Account<'info, TokenAccount>,
     
-    #[account(mut)]
+    // Treasury USDC account - pays the seller for direct sales from reserves
+    #[account(
+        mut,
+        constraint = treasury_usdc_account.owner == bonding_curve.treasury_wallet,
+        constraint = treasury_usdc_account.mint == bonding_curve.config.quote_mint
+    )]
     pub treasury_usdc_account: Account<'info, TokenAccount>,
     
-    #[account(mut)]
-    pub burn_ever_account: Account<'info, TokenAccount>, // Account to burn EVER tokens
+    // EVER mint - supply shrinks when sold EVER is burned
+    #[account(mut, address = bonding_curve.config.base_mint)]
+    pub ever_mint: Account<'info, Mint>,
     
-    pub token_program: Program<'info, Token>,
-}
-
-#[derive(Accounts)]
-pub struct ApplyDailyBoost<'info> {
+    // Hourly volume buckets for this curve
     #[account(
         mut,
-        seeds = [b"bonding_curve"],
-        bump = bonding_curve.bump
+        seeds = [b"volume_stats", bonding_curve.key().as_ref()],
+        bump = volume_stats.bump
     )]
-    pub bonding_curve: Account<'info, BondingCurve>,
+    pub volume_stats: Box<Account<'info, VolumeStats>>,
     
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
@@ -1283,61 +1645,53 @@ pub struct GetVersion {
     // No accounts needed for version check
 }
 
-#[derive(Accounts)]
-pub struct BumpBuyTail<'info> {
-    #[account(
-        mut,
-        seeds = [b"bonding_curve"],
-        bump
-    )]
-    pub bonding_curve: Account<'info, BondingCurve>,
-    pub user: Signer<'info>,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct VersionInfo {
+    pub code_version: u32, // Bumped with every program change
+    pub layout_version: u8, // LAYOUT_VERSION of accounts written by this code
 }
 
 #[derive(Accounts)]
-pub struct BumpSellTail<'info> {
-    #[account(
-        mut,
-        seeds = [b"bonding_curve"],
-        bump
-    )]
-    pub bonding_curve: Account<'info, BondingCurve>,
-    pub user: Signer<'info>,
-}
+pub struct CloseOrder<'info> {
+    /// CHECK: Validated in close_processed_order as a processed SellOrder or BuyOrder
+    #[account(mut)]
+    pub order: UncheckedAccount<'info>,
 
-#[derive(Accounts)]
-pub struct SkipOrphanedBuyOrders<'info> {
-    #[account(
-        mut,
-        seeds = [b"bonding_curve"],
-        bump
-    )]
-    pub bonding_curve: Account<'info, BondingCurve>,
-    
-    /// Anyone can call this function to skip orphaned accounts
-    pub user: Signer<'info>,
+    /// CHECK: Validated in close_processed_order as the order's seller/buyer
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
 pub struct EmergencyRefund<'info> {
     #[account(
         mut,
-        seeds = [b"bonding_curve"],
+        seeds = [b"bonding_curve", bonding_curve.config.base_mint.as_ref()],
         bump = bonding_curve.bump
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
     
     #[account(
         mut,
-        seeds = [b"buy_order", bonding_curve.buy_queue_head.to_le_bytes().as_ref()],
+        seeds = [b"buy_order", bonding_curve.key().as_ref(), bonding_curve.buy_queue_head.to_le_bytes().as_ref()],
         bump = buy_order.bump
     )]
     pub buy_order: Account<'info, BuyOrder>,
     
-    #[account(mut)]
+    // Program USDC account (escrows USDC for queued buy orders)
+    #[account(
+        mut,
+        constraint = program_usdc_account.owner == bonding_curve.key(),
+        constraint = program_usdc_account.mint == bonding_curve.config.quote_mint
+    )]
     pub program_usdc_account: Account<'info, TokenAccount>,
     
-    #[account(mut)]
+    // The queued buyer's USDC account - receives the refund
+    #[account(
+        mut,
+        constraint = buyer_usdc_account.owner == buy_order.buyer @ ErrorCode::BuyerUsdcOwnerMismatch,
+        constraint = buyer_usdc_account.mint == bonding_curve.config.quote_mint
+    )]
     pub buyer_usdc_account: Account<'info, TokenAccount>,
     
     pub token_program: Program<'info, Token>,
@@ -1352,17 +1706,109 @@ pub struct BondingCurve {
     pub y: u64, // EVER in reserve
     pub k: u128, // K = X * Y (constant)
     pub last_daily_boost: i64,
-    pub total_volume_24h: u64,
-    pub sell_queue_head: u64,
-    pub sell_queue_tail: u64,
-    pub buy_queue_head: u64,
-    pub buy_queue_tail: u64,
+    pub total_volume_24h: u64, // Trailing 24h buy volume as of the last trade (hourly buckets in VolumeStats)
+    pub sell_queue_head: u64, // Order id of the oldest open sell order (0 = queue empty)
+    pub sell_queue_tail: u64, // Order id of the newest open sell order (0 = queue empty)
+    pub buy_queue_head: u64, // Order id of the oldest open buy order (0 = queue empty)
+    pub buy_queue_tail: u64, // Order id of the newest open buy order (0 = queue empty)
     pub cumulative_bonus: u64, // Sum of all historical bonuses
     pub current_price: u64, // Current locked price
     pub last_price_update: i64, // Timestamp of last price update
     pub daily_boost_applied: bool, // Whether daily boost was applied today
-    pub circulating_supply: u64, // Total EVER tokens in circulation
+    pub circulating_supply: u64, // SC - EVER held outside the program (mint supply - Y - sell-order escrow)
     pub bump: u8,
+    pub pending_authority: Pubkey, // Proposed authority awaiting accept_authority (default = none)
+    pub paused_operations: u8, // Bitmask of PAUSE_* flags
+    pub config: CurveParams, // Mints, initial reserves and limits supplied at initialize
+    pub day_start_price: u64, // P_start - effective price at day_start_ts
+    pub day_start_ts: i64, // T_start - midnight UTC of the current day
+    pub version: u8, // Layout version (LAYOUT_VERSION once migrated)
+    pub next_order_id: u64, // Seeds the next SellOrder/BuyOrder PDA - ids are never reused
+    pub sell_settlement: u8, // SELL_SETTLEMENT_* mode for direct sells to reserves
+    pub total_burned: u64, // EVER burned by process_sell_queue under SELL_SETTLEMENT_BURN
+    pub escrowed_sell_ever: u64, // EVER in program_ever_account held for open sell orders (not reserves)
+    pub affiliate_program: Pubkey, // Program buy_smart pays referral commissions through (default = none, treasury only)
+    pub reserved: [u8; 71], // Room for new fields without a realloc - carve from the front
+}
+
+/// Per-curve parameters supplied at initialize (replaces the old compile-time constants)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub struct CurveParams {
+    pub quote_mint: Pubkey, // USDC mint (6 decimals)
+    pub base_mint: Pubkey, // EVER mint (9 decimals)
+    pub initial_x: u64, // Initial USDC in treasury
+    pub initial_y: u64, // Initial EVER in reserve
+    pub daily_growth_bps: u16, // Guaranteed daily price growth (2 = 0.02%)
+    pub max_quote_amount: u64, // Max USDC per buy / USDC value per sell
+    pub max_base_amount: u64, // Max EVER per sell
+    pub commission_bps: u16, // Treasury commission on buy_smart reserve purchases by buyers without a referrer (500 = 5%)
+}
+
+impl CurveParams {
+    /// Reject parameters that would produce an unusable curve
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.quote_mint != Pubkey::default()
+                && self.base_mint != Pubkey::default()
+                && self.quote_mint != self.base_mint,
+            ErrorCode::InvalidCurveParams
+        );
+        require!(self.initial_x > 0 && self.initial_y > 0, ErrorCode::InvalidCurveParams);
+        require!(self.max_quote_amount > 0 && self.max_base_amount > 0, ErrorCode::InvalidCurveParams);
+        require!(self.daily_growth_bps <= MAX_DAILY_GROWTH_BPS, ErrorCode::InvalidCurveParams);
+        require!((self.commission_bps as u64) <= BASIS_POINTS, ErrorCode::InvalidCurveParams);
+        Ok(())
+    }
+}
+
+impl BondingCurve {
+    /// Whether any of the given PAUSE_* operations is currently paused
+    pub fn is_paused(&self, operations: u8) -> bool {
+        self.paused_operations & operations != 0
+    }
+
+    /// EVER in the reserve vault that is free to sell - its balance less the sell-order escrow
+    pub fn available_reserve(&self, program_ever_balance: u64) -> u64 {
+        program_ever_balance.saturating_sub(self.escrowed_sell_ever)
+    }
+
+    /// Hand out the id for a new order - ids only ever grow, so order PDAs cannot collide
+    pub fn take_order_id(&mut self) -> Result<u64> {
+        let order_id = self.next_order_id;
+        self.next_order_id = order_id.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        Ok(order_id)
+    }
+
+    /// Unlink the sell order at the head once it is fully consumed
+    pub fn pop_sell_order(&mut self, sell_order: &SellOrder) {
+        self.sell_queue_head = sell_order.next;
+        if sell_order.next == 0 {
+            self.sell_queue_tail = 0;
+        }
+    }
+
+    /// Unlink the buy order at the head once it is processed or refunded
+    pub fn pop_buy_order(&mut self, buy_order: &BuyOrder) {
+        self.buy_queue_head = buy_order.next;
+        if buy_order.next == 0 {
+            self.buy_queue_tail = 0;
+        }
+    }
+
+    /// Snapshot of the state used by the everrise-math curve functions
+    pub fn curve(&self) -> Curve {
+        Curve {
+            x: self.x,
+            y: self.y,
+            k: self.k,
+            cumulative_bonus: self.cumulative_bonus,
+            circulating_supply: self.circulating_supply,
+            current_price: self.current_price,
+            daily_growth_bps: self.config.daily_growth_bps,
+            day_start_price: self.day_start_price,
+            day_start_ts: self.day_start_ts,
+        }
+    }
 }
 
 #[account]
@@ -1375,6 +1821,11 @@ pub struct SellOrder {
     pub timestamp: i64,
     pub processed: bool, // true when remaining_amount = 0
     pub bump: u8,
+    pub version: u8, // Layout version (LAYOUT_VERSION once migrated)
+    pub id: u64, // Order id used in the PDA seeds
+    pub prev: u64, // Previous order in the sell queue (0 = none; stale once this order is the head)
+    pub next: u64, // Next order in the sell queue (0 = none)
+    pub reserved: [u8; 8], // Room for new fields without a realloc - carve from the front
 }
 
 #[account]
@@ -1386,15 +1837,28 @@ pub struct BuyOrder {
     pub timestamp: i64,
     pub processed: bool,
     pub bump: u8,
+    pub version: u8, // Layout version (LAYOUT_VERSION once migrated)
+    pub id: u64, // Order id used in the PDA seeds
+    pub next: u64, // Next order in the buy queue (0 = none)
+    pub reserved: [u8; 16], // Room for new fields without a realloc - carve from the front
 }
 
 // Events
+#[event]
+pub struct MarketCreatedEvent {
+    pub bonding_curve: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct BuyQueueEvent {
     pub buyer: Pubkey,
     pub usdc_amount: u64,
     pub estimated_tokens: u64,
-    pub queue_position: u64,
+    pub order_id: u64,
     pub timestamp: i64,
 }
 
@@ -1405,6 +1869,7 @@ pub struct BuyProcessedEvent {
     pub ever_tokens: u64,
     pub queue_transactions: u64,
     pub reserve_transactions: u64,
+    pub appreciation_bonus: u64, // Total bonus from this buy's queue fills
     pub timestamp: i64,
 }
 
@@ -1413,7 +1878,24 @@ pub struct SellQueueEvent {
     pub seller: Pubkey,
     pub ever_amount: u64,
     pub locked_price: u64,
-    pub queue_position: u64,
+    pub order_id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrderClosedEvent {
+    pub order: Pubkey,
+    pub owner: Pubkey, // Seller or buyer that received the rent
+    pub order_type: u8, // 0 = sell order, 1 = buy order
+    pub rent_reclaimed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SellOrderCancelledEvent {
+    pub seller: Pubkey,
+    pub ever_amount: u64, // Unfilled EVER returned to the seller
+    pub order_id: u64,
     pub timestamp: i64,
 }
 
@@ -1424,6 +1906,7 @@ pub struct DailyBoostEvent {
     pub final_price: u64,
     pub days_passed: i64,
     pub boost_amount: u64,
+    pub day_start_ts: i64, // Midnight UTC the new day_start_price applies from
     pub timestamp: i64,
 }
 
@@ -1442,6 +1925,7 @@ pub struct SellProcessedEvent {
     pub usdc_amount: u64,
     pub locked_price: u64,
     pub processing_type: u8, // 0 = queue matching, 1 = direct to reserves
+    pub appreciation_bonus: u64, // Added to cumulative_bonus by this fill
     pub timestamp: i64,
 }
 
@@ -1451,31 +1935,80 @@ pub struct AtomicBuyEvent {
     pub usdc_amount: u64,
     pub ever_received: u64,
     pub new_price: u64,
+    pub appreciation_bonus: u64, // Total bonus from this buy's queue fills (0 for reserve-only buys)
     pub timestamp: i64,
 }
 
 // Helper functions
+/// Reject the transaction if the caller's optional unix-timestamp deadline has passed
+fn check_deadline(deadline: Option<i64>, current_timestamp: i64) -> Result<()> {
+    if let Some(deadline) = deadline {
+        require!(current_timestamp <= deadline, ErrorCode::DeadlineExpired);
+    }
+    Ok(())
+}
+
 /// Calculate how many EVER tokens a user will receive for a given USDC amount
 fn calculate_buy_amount(bonding_curve: &BondingCurve, usdc_amount: u64) -> Result<u64> {
-    let new_x = bonding_curve.x.checked_add(usdc_amount).ok_or(ErrorCode::MathOverflow)?;
-    let new_y = bonding_curve.k.checked_div(new_x as u128).ok_or(ErrorCode::MathOverflow)? as u64;
-    let tokens_received = bonding_curve.y.checked_sub(new_y).ok_or(ErrorCode::MathOverflow)?;
+    let tokens_received = bonding_curve.curve().buy_quote(usdc_amount).map_err(ErrorCode::from)?;
     
-    msg!("calculate_buy_amount: usdc_amount={}, new_x={}, new_y={}, tokens_received={}", 
-         usdc_amount, new_x, new_y, tokens_received);
+    msg!("calculate_buy_amount: usdc_amount={}, tokens_received={}", usdc_amount, tokens_received);
     
     Ok(tokens_received)
 }
 
+/// Derive a curve's SellOrder PDA for an order id
+fn sell_order_address(bonding_curve: &Pubkey, order_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"sell_order", bonding_curve.as_ref(), order_id.to_le_bytes().as_ref()],
+        &crate::ID,
+    )
+    .0
+}
+
+/// Close a processed SellOrder or BuyOrder, returning its lamports to the order's seller/buyer.
+/// Processed orders sit behind their queue head, so closing one never affects queue processing.
+fn close_processed_order<'info>(order: &AccountInfo<'info>, owner: &AccountInfo<'info>) -> Result<()> {
+    require_keys_eq!(*order.owner, crate::ID, ErrorCode::InvalidOrderAccount);
+
+    let (order_owner, order_type) = {
+        let data = order.try_borrow_data()?;
+        if data.starts_with(SellOrder::DISCRIMINATOR) {
+            let sell_order = SellOrder::try_deserialize(&mut &data[..])?;
+            require!(sell_order.processed && sell_order.remaining_amount == 0, ErrorCode::OrderNotProcessed);
+            (sell_order.seller, 0)
+        } else if data.starts_with(BuyOrder::DISCRIMINATOR) {
+            let buy_order = BuyOrder::try_deserialize(&mut &data[..])?;
+            require!(buy_order.processed, ErrorCode::OrderNotProcessed);
+            (buy_order.buyer, 1)
+        } else {
+            return err!(ErrorCode::InvalidOrderAccount);
+        }
+    };
+    require_keys_eq!(order_owner, owner.key(), ErrorCode::OrderOwnerMismatch);
+
+    // Same steps as Anchor's `close` constraint
+    let rent_reclaimed = order.lamports();
+    **owner.try_borrow_mut_lamports()? = owner.lamports().checked_add(rent_reclaimed).ok_or(ErrorCode::MathOverflow)?;
+    **order.try_borrow_mut_lamports()? = 0;
+    order.assign(&System::id());
+    order.resize(0)?;
+
+    emit!(OrderClosedEvent {
+        order: order.key(),
+        owner: order_owner,
+        order_type,
+        rent_reclaimed,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
 /// Calculate current effective price including all bonuses and daily boosts
 fn calculate_effective_price(bonding_curve: &BondingCurve) -> u64 {
-    // Start with organic price from bonding curve
-    let organic_price = calculate_organic_price(bonding_curve);
-    
-    // Add cumulative bonus (from queue transactions and daily boosts)
-    organic_price
-        .checked_add(bonding_curve.cumulative_bonus)
-        .unwrap_or(organic_price)
+    // Organic price plus cumulative bonus (from queue transactions and daily boosts)
+    bonding_curve.curve().effective_price()
 }
 
 /// Calculate current price using bonding curve formula (legacy function)
@@ -1483,120 +2016,63 @@ fn calculate_price(bonding_curve: &BondingCurve) -> u64 {
     calculate_organic_price(bonding_curve)
 }
 
-/// Apply daily boost if needed - ensures minimum 0.02% daily price growth
+/// Apply daily boost if needed - settles every UTC day closed since day_start_ts,
+/// ensuring at least the configured daily growth over day_start_price
 fn apply_daily_boost(bonding_curve: &mut BondingCurve, current_timestamp: i64) -> Result<()> {
-    let days_since_last_boost = (current_timestamp - bonding_curve.last_daily_boost) / 86400; // 86400 seconds in a day
-    
-    if days_since_last_boost > 0 {
-        // Reset daily boost flag for new day
-        bonding_curve.daily_boost_applied = false;
-        
-        // Calculate organic growth from bonding curve
-        let organic_price = calculate_organic_price(bonding_curve);
-        
-        // Calculate minimum required price (0.02% daily growth)
-        let minimum_price = calculate_minimum_daily_price(bonding_curve, days_since_last_boost)?;
-        
-        // Use the higher of organic price or minimum price
-        let boosted_price = if organic_price >= minimum_price {
-            organic_price
-        } else {
-            minimum_price
-        };
-        
-        // Apply the boost to cumulative bonus
-        let price_difference = boosted_price.checked_sub(organic_price).unwrap_or(0);
-        if price_difference > 0 {
-            bonding_curve.cumulative_bonus = bonding_curve.cumulative_bonus
-                .checked_add(price_difference)
-                .ok_or(ErrorCode::MathOverflow)?;
-        }
-        
-        // Update current price and state
-        bonding_curve.current_price = boosted_price;
-        bonding_curve.last_daily_boost = current_timestamp;
-        bonding_curve.daily_boost_applied = true;
-        
-        // Emit daily boost event
-        emit!(DailyBoostEvent {
-            organic_price,
-            minimum_price,
-            final_price: boosted_price,
-            days_passed: days_since_last_boost,
-            boost_amount: price_difference,
-            timestamp: current_timestamp,
-        });
-        
-        msg!("Daily boost applied: organic={}, minimum={}, final={}", 
-             organic_price, minimum_price, boosted_price);
-    }
-    
+    let mut curve = bonding_curve.curve();
+    let Some(boost) = curve.apply_daily_boost(current_timestamp).map_err(ErrorCode::from)? else {
+        return Ok(()); // Still inside the current UTC day
+    };
+
+    // Lift the price to the higher of organic price or minimum daily growth price,
+    // carrying the difference in the cumulative bonus
+    bonding_curve.cumulative_bonus = curve.cumulative_bonus;
+    bonding_curve.current_price = curve.current_price;
+    bonding_curve.day_start_price = curve.day_start_price;
+    bonding_curve.day_start_ts = curve.day_start_ts;
+    bonding_curve.last_daily_boost = current_timestamp;
+    bonding_curve.daily_boost_applied = boost.bonus_added > 0;
+
+    // Emit daily boost event
+    emit!(DailyBoostEvent {
+        organic_price: boost.organic_price,
+        minimum_price: boost.minimum_price,
+        final_price: boost.boosted_price,
+        days_passed: boost.days_passed,
+        boost_amount: boost.bonus_added,
+        day_start_ts: boost.day_start_ts,
+        timestamp: current_timestamp,
+    });
+
+    msg!("Daily boost applied: organic={}, minimum={}, final={}", 
+         boost.organic_price, boost.minimum_price, boost.boosted_price);
+
     Ok(())
 }
 
 /// Calculate organic price from bonding curve (X/Y)
 fn calculate_organic_price(bonding_curve: &BondingCurve) -> u64 {
-    if bonding_curve.y == 0 {
-        return 0;
-    }
-    
-    bonding_curve.x
-        .checked_mul(1_000_000_000) // 9 decimals for EVER
-        .unwrap_or(0)
-        .checked_div(bonding_curve.y)
-        .unwrap_or(0)
+    bonding_curve.curve().organic_price()
 }
 
-/// Calculate minimum daily price based on 0.02% daily growth guarantee
-fn calculate_minimum_daily_price(bonding_curve: &BondingCurve, days_passed: i64) -> Result<u64> {
-    if days_passed <= 0 {
-        return Ok(bonding_curve.current_price);
-    }
-    
-    // Calculate compound growth: price * (1.0002)^days
-    // For small percentages, we can approximate: price * (1 + 0.0002 * days)
-    let growth_factor = 1_000_000u64 // 1.0 in 6 decimal places
-        .checked_add(
-            (days_passed as u64)
-                .checked_mul(200) // 0.02% = 200 basis points in 6 decimals
-                .ok_or(ErrorCode::MathOverflow)?
-        )
-        .ok_or(ErrorCode::MathOverflow)?;
-    
-    let minimum_price = bonding_curve.current_price
-        .checked_mul(growth_factor)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_div(1_000_000) // Convert back from 6 decimal places
-        .ok_or(ErrorCode::MathOverflow)?;
-    
-    Ok(minimum_price)
+/// Calculate appreciation bonus for queue-based transactions
+/// Formula: (0.001 × V) / ((X/Y) × SC)
+/// Where V = transaction volume, X/Y = organic price, SC = circulating supply
+fn calculate_appreciation_bonus(bonding_curve: &BondingCurve, transaction_volume: u64) -> Result<u64> {
+    Ok(everrise_math::appreciation_bonus(
+        transaction_volume,
+        calculate_organic_price(bonding_curve),
+        bonding_curve.circulating_supply,
+    )
+    .map_err(ErrorCode::from)?)
 }
 
-/// Calculate appreciation bonus for queue-based transactions
-/// Formula: (0.001 × V) / (current_price × SC)
-/// Where V = transaction volume, current_price = sell order locked price, SC = supply cap
-fn calculate_appreciation_bonus(
-    bonding_curve: &BondingCurve,
-    transaction_volume: u64,
-    current_price: u64
-) -> Result<u64> {
-    // Supply cap is the total supply (1 billion tokens)
-    let supply_cap = 1_000_000_000u64;
-    
-    // Calculate: (0.001 × V) / (current_price × SC)
-    let numerator = transaction_volume
-        .checked_mul(1) // 0.001 = 1/1000, but we'll use 1 for simplicity
-        .ok_or(ErrorCode::MathOverflow)?;
-    
-    let denominator = current_price
-        .checked_mul(supply_cap)
-        .ok_or(ErrorCode::MathOverflow)?;
-    
-    let bonus = numerator
-        .checked_div(denominator)
-        .unwrap_or(0);
-    
-    Ok(bonus)
+impl From<MathError> for ErrorCode {
+    fn from(error: MathError) -> Self {
+        match error {
+            MathError::Overflow => ErrorCode::MathOverflow,
+        }
+    }
 }
 
 // Error codes
@@ -1622,4 +2098,52 @@ pub enum ErrorCode {
     PriceCalculationFailed,
     #[msg("Insufficient liquidity")]
     InsufficientLiquidity,
+    #[msg("Remaining accounts must be (sell_order, seller_usdc_account) pairs")]
+    InvalidRemainingAccounts,
+    #[msg("Order already processed")]
+    OrderAlreadyProcessed,
+    #[msg("Sell order account is not the PDA at the queue head")]
+    InvalidSellOrder,
+    #[msg("Seller USDC account is not a valid token account")]
+    InvalidSellerUsdcAccount,
+    #[msg("Seller USDC account does not belong to the sell order's seller")]
+    SellerUsdcOwnerMismatch,
+    #[msg("Seller USDC account is not a quote mint account")]
+    InvalidSellerUsdcMint,
+    #[msg("Operation is paused")]
+    OperationPaused,
+    #[msg("Slippage tolerance exceeded")]
+    SlippageExceeded,
+    #[msg("Transaction deadline expired")]
+    DeadlineExpired,
+    #[msg("Invalid curve parameters")]
+    InvalidCurveParams,
+    #[msg("Account layout is not recognised by this program version")]
+    UnsupportedAccountLayout,
+    #[msg("Order has not been fully processed")]
+    OrderNotProcessed,
+    #[msg("Account is not a SellOrder or BuyOrder")]
+    InvalidOrderAccount,
+    #[msg("Rent recipient is not the order's seller/buyer")]
+    OrderOwnerMismatch,
+    #[msg("Remaining accounts must be (order, owner) pairs")]
+    InvalidCloseAccounts,
+    #[msg("A neighbouring queue order is required to relink the queue")]
+    QueueLinkMissing,
+    #[msg("Order queues must be drained before migrating to this layout")]
+    QueueNotDrained,
+    #[msg("K does not equal X * Y")]
+    InvariantKMismatch,
+    #[msg("Treasury USDC balance is below X")]
+    TreasuryUnderfunded,
+    #[msg("Program EVER balance is below Y")]
+    ReserveUnderfunded,
+    #[msg("Program EVER beyond Y or the tracked escrow does not match the open sell orders")]
+    EscrowMismatch,
+    #[msg("Queue head is past its tail or the queue does not end at its tail")]
+    QueueOrderInvalid,
+    #[msg("Affiliate program did not return the commission paid")]
+    AffiliateCommissionMissing,
+    #[msg("Buyer USDC account does not belong to the buy order's buyer")]
+    BuyerUsdcOwnerMismatch,
 }
\ No newline at end of file
@@ -0,0 +1,616 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::{Discriminator, Space};
+use anchor_spl::token::{self, spl_token::instruction::AuthorityType, Mint, Token, TokenAccount};
+
+use crate::{
+    init_volume_stats, BondingCurve, BuyOrder, CurveParams, ErrorCode, SellOrder, VolumeStats, LAYOUT_VERSION,
+    SELL_SETTLEMENT_BURN,
+};
+
+// Account layout migrations - every layout is identified by its data length, and
+// versioned layouts additionally carry a version byte.
+//
+// Layout 0: the deployed single-market program - one BondingCurve at [b"bonding_curve"] with
+//           compile-time curve constants and no `version` or `reserved`. It is not at the
+//           per-market address, so it moves with migrate_legacy_bonding_curve.
+// Layout 1: adds `version` and `reserved`.
+// Layout 2: linked order queues - carves `next_order_id` (BondingCurve) and `id`/`prev`/`next`
+//           (SellOrder, BuyOrder) from `reserved`; queue head/tail become order ids.
+// Layout 3: carves `sell_settlement` and `total_burned` (BondingCurve) from `reserved`;
+//           zeroed bytes read as SELL_SETTLEMENT_BURN with nothing burned yet.
+// Layout 4: carves `escrowed_sell_ever` (BondingCurve) from `reserved`; zero is only
+//           right with no open sell orders, so the sell queue must be drained.
+// Layout 5: carves `affiliate_program` (BondingCurve) from `reserved`; zeroed bytes read as
+//           no affiliate program until set_affiliate_program.
+//
+// New fields should be carved from the front of `reserved` and LAYOUT_VERSION bumped;
+// the account size is unchanged, so migrating is a version check plus any backfill.
+
+/// BondingCurve as written by the deployed single-market program (layout 0)
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+struct BondingCurveV0 {
+    authority: Pubkey,
+    treasury_wallet: Pubkey,
+    x: u64,
+    y: u64,
+    k: u128,
+    last_daily_boost: i64,
+    total_volume_24h: u64,
+    sell_queue_head: u64,
+    sell_queue_tail: u64,
+    buy_queue_head: u64,
+    buy_queue_tail: u64,
+    cumulative_bonus: u64,
+    current_price: u64,
+    last_price_update: i64,
+    daily_boost_applied: bool,
+    circulating_supply: u64,
+    bump: u8,
+}
+
+// Compile-time curve constants of the layout 0 program - they become the migrated market's CurveParams
+const LEGACY_QUOTE_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+pub const LEGACY_BASE_MINT: Pubkey = pubkey!("3q4YFYMKHrdYw5FPANQ7nrCQMT4t12XKgzYX8JaTeEx8");
+
+fn legacy_curve_params() -> CurveParams {
+    CurveParams {
+        quote_mint: LEGACY_QUOTE_MINT,
+        base_mint: LEGACY_BASE_MINT,
+        initial_x: 10_000_000_000, // 10K USDC
+        initial_y: 100_000_000_000_000_000, // 100M EVER
+        daily_growth_bps: 2, // 0.02%
+        max_quote_amount: 10_000_000_000_000, // 10M USDC
+        max_base_amount: 10_000_000_000_000_000, // 10M EVER
+        commission_bps: 500, // 5%
+    }
+}
+
+/// Rebuild a layout 0 curve as the legacy market's curve at LAYOUT_VERSION.
+/// Layout 0 orders were seeded by queue position, so both queues must be drained first.
+fn upgrade_legacy_curve(legacy: BondingCurveV0, base_mint_supply: u64, timestamp: i64, bump: u8) -> Result<BondingCurve> {
+    require!(
+        legacy.sell_queue_head >= legacy.sell_queue_tail && legacy.buy_queue_head >= legacy.buy_queue_tail,
+        ErrorCode::QueueNotDrained
+    );
+
+    Ok(BondingCurve {
+        authority: legacy.authority,
+        treasury_wallet: legacy.treasury_wallet,
+        x: legacy.x,
+        y: legacy.y,
+        k: legacy.k,
+        last_daily_boost: legacy.last_daily_boost,
+        total_volume_24h: legacy.total_volume_24h,
+        sell_queue_head: 0,
+        sell_queue_tail: 0,
+        buy_queue_head: 0,
+        buy_queue_tail: 0,
+        cumulative_bonus: legacy.cumulative_bonus,
+        current_price: legacy.current_price,
+        last_price_update: legacy.last_price_update,
+        daily_boost_applied: legacy.daily_boost_applied,
+        // No sell escrow with the queues drained, so SC is everything beyond Y
+        circulating_supply: base_mint_supply.saturating_sub(legacy.y),
+        bump,
+        pending_authority: Pubkey::default(),
+        paused_operations: 0,
+        config: legacy_curve_params(),
+        day_start_price: legacy.current_price,
+        day_start_ts: everrise_math::day_start(timestamp),
+        version: LAYOUT_VERSION,
+        next_order_id: 1, // Order PDAs are now seeded by the curve, so ids restart
+        sell_settlement: SELL_SETTLEMENT_BURN,
+        total_burned: 0,
+        escrowed_sell_ever: 0,
+        affiliate_program: Pubkey::default(), // The layout 0 program had none
+        reserved: [0; 71],
+    })
+}
+
+/// SellOrder as written before layout versioning
+#[derive(AnchorDeserialize, InitSpace)]
+struct SellOrderV0 {
+    seller: Pubkey,
+    ever_amount: u64,
+    remaining_amount: u64,
+    locked_price: u64,
+    timestamp: i64,
+    processed: bool,
+    bump: u8,
+}
+
+/// BuyOrder as written before layout versioning
+#[derive(AnchorDeserialize, InitSpace)]
+struct BuyOrderV0 {
+    buyer: Pubkey,
+    usdc_amount: u64,
+    expected_tokens: u64,
+    timestamp: i64,
+    processed: bool,
+    bump: u8,
+}
+
+impl From<SellOrderV0> for SellOrder {
+    fn from(v0: SellOrderV0) -> Self {
+        SellOrder {
+            seller: v0.seller,
+            ever_amount: v0.ever_amount,
+            remaining_amount: v0.remaining_amount,
+            locked_price: v0.locked_price,
+            timestamp: v0.timestamp,
+            processed: v0.processed,
+            bump: v0.bump,
+            version: 0,
+            id: 0,
+            prev: 0,
+            next: 0,
+            reserved: [0; 8],
+        }
+    }
+}
+
+impl From<BuyOrderV0> for BuyOrder {
+    fn from(v0: BuyOrderV0) -> Self {
+        BuyOrder {
+            buyer: v0.buyer,
+            usdc_amount: v0.usdc_amount,
+            expected_tokens: v0.expected_tokens,
+            timestamp: v0.timestamp,
+            processed: v0.processed,
+            bump: v0.bump,
+            version: 0,
+            id: 0,
+            next: 0,
+            reserved: [0; 16],
+        }
+    }
+}
+
+/// Accounts that carry a layout version byte
+trait Versioned {
+    fn version(&self) -> u8;
+    fn set_version(&mut self, version: u8);
+
+    /// Backfill fields whose meaning changed since `from_version`
+    fn upgrade(&mut self, _from_version: u8) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Versioned for BondingCurve {
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
+    }
+
+    fn upgrade(&mut self, from_version: u8) -> Result<()> {
+        if from_version < 2 {
+            // Open orders were seeded by queue position (sell: position + 1) and cannot be
+            // relinked in place, so both queues must be empty (head caught up with tail)
+            require!(
+                self.sell_queue_head >= self.sell_queue_tail && self.buy_queue_head >= self.buy_queue_tail,
+                ErrorCode::QueueNotDrained
+            );
+            // Start order ids past every position-seeded PDA so none can collide
+            self.next_order_id = self.sell_queue_tail.max(self.buy_queue_tail).checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+            self.sell_queue_head = 0;
+            self.sell_queue_tail = 0;
+            self.buy_queue_head = 0;
+            self.buy_queue_tail = 0;
+        }
+        if from_version < 4 {
+            // escrowed_sell_ever reads as zero, which only holds with no open sell orders
+            require!(self.sell_queue_head == 0, ErrorCode::QueueNotDrained);
+        }
+        Ok(())
+    }
+}
+
+impl Versioned for SellOrder {
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
+    }
+}
+
+impl Versioned for BuyOrder {
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
+    }
+}
+
+/// Convert the bonding curve to LAYOUT_VERSION, then every SellOrder / BuyOrder passed
+/// in remaining accounts. Accounts already at LAYOUT_VERSION are left untouched, and
+/// the authority pays any extra rent for the larger layout. Layout 0 curves live at the
+/// legacy address and go through handle_migrate_legacy_bonding_curve instead.
+pub fn handle_migrate_bonding_curve<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MigrateBondingCurve<'info>>,
+) -> Result<()> {
+    let bonding_curve_info = ctx.accounts.bonding_curve.to_account_info();
+    let authority = ctx.accounts.authority.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+
+    let bonding_curve = read_current::<BondingCurve>(&bonding_curve_info)?;
+    require_keys_eq!(bonding_curve.authority, authority.key(), ErrorCode::Unauthorized);
+
+    // Same check as the seeds constraint on every other bonding_curve account
+    let expected_address = Pubkey::create_program_address(
+        &[&b"bonding_curve"[..], bonding_curve.config.base_mint.as_ref(), &[bonding_curve.bump]],
+        &crate::ID,
+    )
+    .map_err(|_| ErrorCode::UnsupportedAccountLayout)?;
+    require_keys_eq!(bonding_curve_info.key(), expected_address, ErrorCode::UnsupportedAccountLayout);
+
+    // Orders written before order ids carry no curve, and only the legacy market has any
+    let is_legacy_market = bonding_curve.config.base_mint == LEGACY_BASE_MINT;
+    let bonding_curve_key = bonding_curve_info.key();
+
+    migrate(&bonding_curve_info, bonding_curve, &authority, &system_program)?;
+
+    for order_info in ctx.remaining_accounts.iter() {
+        require!(order_info.is_writable, ErrorCode::InvalidRemainingAccounts);
+        require_keys_eq!(*order_info.owner, crate::ID, ErrorCode::UnsupportedAccountLayout);
+
+        let data = order_info.try_borrow_data()?;
+        let is_sell_order = data.starts_with(SellOrder::DISCRIMINATOR);
+        let is_buy_order = data.starts_with(BuyOrder::DISCRIMINATOR);
+        drop(data);
+
+        if is_sell_order {
+            let sell_order = read_versioned::<SellOrder, SellOrderV0>(order_info)?;
+            let (id, bump) = (sell_order.id, sell_order.bump);
+            check_order_curve(order_info, b"sell_order", &bonding_curve_key, id, bump, is_legacy_market)?;
+            migrate(order_info, sell_order, &authority, &system_program)?;
+        } else if is_buy_order {
+            let buy_order = read_versioned::<BuyOrder, BuyOrderV0>(order_info)?;
+            let (id, bump) = (buy_order.id, buy_order.bump);
+            check_order_curve(order_info, b"buy_order", &bonding_curve_key, id, bump, is_legacy_market)?;
+            migrate(order_info, buy_order, &authority, &system_program)?;
+        } else {
+            return err!(ErrorCode::UnsupportedAccountLayout);
+        }
+    }
+
+    Ok(())
+}
+
+/// Require an order account to belong to the curve being migrated - the order PDA for its
+/// id, or (for orders written before order ids) any order of the legacy market
+fn check_order_curve(
+    order_info: &AccountInfo,
+    seed: &[u8],
+    bonding_curve: &Pubkey,
+    id: u64,
+    bump: u8,
+    is_legacy_market: bool,
+) -> Result<()> {
+    if id == 0 {
+        require!(is_legacy_market, ErrorCode::InvalidOrderAccount);
+        return Ok(());
+    }
+
+    let expected_address = Pubkey::create_program_address(
+        &[seed, bonding_curve.as_ref(), id.to_le_bytes().as_ref(), &[bump]],
+        &crate::ID,
+    )
+    .map_err(|_| ErrorCode::InvalidOrderAccount)?;
+    require_keys_eq!(order_info.key(), expected_address, ErrorCode::InvalidOrderAccount);
+    Ok(())
+}
+
+/// Move the layout 0 curve at [b"bonding_curve"] to the legacy market's per-mint address.
+/// The reserve vaults (and the treasury, when the old curve PDA was the treasury wallet) are
+/// handed to the new curve PDA, and the old account is closed to the authority.
+pub fn handle_migrate_legacy_bonding_curve(ctx: Context<MigrateLegacyBondingCurve>) -> Result<()> {
+    let legacy_info = ctx.accounts.legacy_bonding_curve.to_account_info();
+    require_keys_eq!(*legacy_info.owner, crate::ID, ErrorCode::UnsupportedAccountLayout);
+
+    let legacy = {
+        let data = legacy_info.try_borrow_data()?;
+        require!(data.starts_with(BondingCurve::DISCRIMINATOR), ErrorCode::UnsupportedAccountLayout);
+        require!(
+            data.len() == BondingCurve::DISCRIMINATOR.len() + BondingCurveV0::INIT_SPACE,
+            ErrorCode::UnsupportedAccountLayout
+        );
+        BondingCurveV0::deserialize(&mut &data[BondingCurve::DISCRIMINATOR.len()..])?
+    };
+    require_keys_eq!(legacy.authority, ctx.accounts.authority.key(), ErrorCode::Unauthorized);
+
+    let clock = Clock::get()?;
+    let legacy_key = legacy_info.key();
+    let bonding_curve_key = ctx.accounts.bonding_curve.key();
+    let legacy_treasury_wallet = legacy.treasury_wallet;
+
+    let mut bonding_curve = upgrade_legacy_curve(
+        legacy,
+        ctx.accounts.base_mint.supply,
+        clock.unix_timestamp,
+        ctx.bumps.bonding_curve,
+    )?;
+
+    // Hand the vaults over to the new curve PDA
+    let seeds = &[&b"bonding_curve"[..], &[ctx.bumps.legacy_bonding_curve]];
+    let signer = &[&seeds[..]];
+    let mut vaults = vec![
+        ctx.accounts.program_ever_account.to_account_info(),
+        ctx.accounts.program_usdc_account.to_account_info(),
+    ];
+    if legacy_treasury_wallet == legacy_key {
+        let treasury_usdc_account = ctx.accounts.treasury_usdc_account.as_ref().ok_or(ErrorCode::InvalidRemainingAccounts)?;
+        vaults.push(treasury_usdc_account.to_account_info());
+        bonding_curve.treasury_wallet = bonding_curve_key;
+    }
+    for vault in vaults {
+        let cpi_accounts = token::SetAuthority {
+            current_authority: legacy_info.clone(),
+            account_or_mint: vault,
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+        token::set_authority(cpi_ctx, AuthorityType::AccountOwner, Some(bonding_curve_key))?;
+    }
+
+    ctx.accounts.bonding_curve.set_inner(bonding_curve);
+    init_volume_stats(&mut ctx.accounts.volume_stats, bonding_curve_key, ctx.bumps.volume_stats, clock.unix_timestamp);
+
+    // Same steps as Anchor's `close` constraint
+    let authority = ctx.accounts.authority.to_account_info();
+    let rent_reclaimed = legacy_info.lamports();
+    **authority.try_borrow_mut_lamports()? = authority.lamports().checked_add(rent_reclaimed).ok_or(ErrorCode::MathOverflow)?;
+    **legacy_info.try_borrow_mut_lamports()? = 0;
+    legacy_info.assign(&System::id());
+    legacy_info.resize(0)?;
+
+    emit!(AccountMigratedEvent {
+        account: bonding_curve_key,
+        from_version: 0,
+        to_version: LAYOUT_VERSION,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Migrated legacy bonding curve {} to {}", legacy_key, bonding_curve_key);
+    Ok(())
+}
+
+/// Deserialize an account that is already at a versioned layout (the current size)
+fn read_current<T>(info: &AccountInfo) -> Result<T>
+where
+    T: AccountDeserialize + Discriminator + Space + Versioned,
+{
+    require_keys_eq!(*info.owner, crate::ID, ErrorCode::UnsupportedAccountLayout);
+
+    let data = info.try_borrow_data()?;
+    require!(data.starts_with(T::DISCRIMINATOR), ErrorCode::UnsupportedAccountLayout);
+    require!(data.len() - T::DISCRIMINATOR.len() == T::INIT_SPACE, ErrorCode::UnsupportedAccountLayout);
+
+    let account = T::try_deserialize(&mut &data[..])?;
+    require!(account.version() <= LAYOUT_VERSION, ErrorCode::UnsupportedAccountLayout);
+    Ok(account)
+}
+
+/// Deserialize an account in either its current or its layout 0 form.
+/// Layout 0 accounts come back converted to the current struct with version 0.
+fn read_versioned<T, V0>(info: &AccountInfo) -> Result<T>
+where
+    T: AccountDeserialize + Discriminator + Space + Versioned,
+    V0: AnchorDeserialize + Space + Into<T>,
+{
+    require_keys_eq!(*info.owner, crate::ID, ErrorCode::UnsupportedAccountLayout);
+
+    let data = info.try_borrow_data()?;
+    require!(data.starts_with(T::DISCRIMINATOR), ErrorCode::UnsupportedAccountLayout);
+
+    let data_len = data.len() - T::DISCRIMINATOR.len();
+    if data_len == T::INIT_SPACE {
+        drop(data);
+        read_current(info)
+    } else if data_len == V0::INIT_SPACE {
+        Ok(V0::deserialize(&mut &data[T::DISCRIMINATOR.len()..])?.into())
+    } else {
+        err!(ErrorCode::UnsupportedAccountLayout)
+    }
+}
+
+/// Rewrite an account at LAYOUT_VERSION, growing it and topping up rent as needed
+fn migrate<'info, T>(
+    info: &AccountInfo<'info>,
+    mut account: T,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()>
+where
+    T: AccountSerialize + Discriminator + Space + Versioned,
+{
+    let from_version = account.version();
+    if from_version == LAYOUT_VERSION {
+        msg!("{} already at layout version {}", info.key(), LAYOUT_VERSION);
+        return Ok(());
+    }
+
+    let new_len = T::DISCRIMINATOR.len() + T::INIT_SPACE;
+    let rent_shortfall = Rent::get()?.minimum_balance(new_len).saturating_sub(info.lamports());
+    if rent_shortfall > 0 {
+        let cpi_accounts = system_program::Transfer {
+            from: payer.clone(),
+            to: info.clone(),
+        };
+        system_program::transfer(CpiContext::new(system_program.clone(), cpi_accounts), rent_shortfall)?;
+    }
+    if info.data_len() != new_len {
+        info.resize(new_len)?;
+    }
+
+    account.upgrade(from_version)?;
+    account.set_version(LAYOUT_VERSION);
+    let mut data = info.try_borrow_mut_data()?;
+    account.try_serialize(&mut &mut data[..])?;
+
+    emit!(AccountMigratedEvent {
+        account: info.key(),
+        from_version,
+        to_version: LAYOUT_VERSION,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Migrated {} from layout version {} to {}", info.key(), from_version, LAYOUT_VERSION);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateBondingCurve<'info> {
+    /// CHECK: Older layouts cannot be deserialized as BondingCurve; the owner,
+    /// discriminator, PDA and authority are validated in handle_migrate_bonding_curve
+    #[account(mut)]
+    pub bonding_curve: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateLegacyBondingCurve<'info> {
+    /// CHECK: Layout 0 cannot be deserialized as BondingCurve; the owner, discriminator, size
+    /// and authority are validated in handle_migrate_legacy_bonding_curve
+    #[account(mut, seeds = [b"bonding_curve"], bump)]
+    pub legacy_bonding_curve: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BondingCurve::INIT_SPACE,
+        seeds = [b"bonding_curve", base_mint.key().as_ref()],
+        bump
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(address = LEGACY_BASE_MINT @ ErrorCode::InvalidCurveParams)]
+    pub base_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + VolumeStats::INIT_SPACE,
+        seeds = [b"volume_stats", bonding_curve.key().as_ref()],
+        bump
+    )]
+    pub volume_stats: Box<Account<'info, VolumeStats>>,
+
+    // Reserve vaults owned by the legacy curve PDA - reassigned to the new one
+    #[account(
+        mut,
+        constraint = program_ever_account.owner == legacy_bonding_curve.key(),
+        constraint = program_ever_account.mint == LEGACY_BASE_MINT
+    )]
+    pub program_ever_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = program_usdc_account.owner == legacy_bonding_curve.key(),
+        constraint = program_usdc_account.mint == LEGACY_QUOTE_MINT
+    )]
+    pub program_usdc_account: Box<Account<'info, TokenAccount>>,
+
+    // Required when the legacy curve PDA is the treasury wallet
+    #[account(
+        mut,
+        constraint = treasury_usdc_account.owner == legacy_bonding_curve.key(),
+        constraint = treasury_usdc_account.mint == LEGACY_QUOTE_MINT
+    )]
+    pub treasury_usdc_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+// Events
+#[event]
+pub struct AccountMigratedEvent {
+    pub account: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub timestamp: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A layout 0 account exactly as the deployed program serialized it
+    fn legacy_account_data() -> Vec<u8> {
+        let mut data = BondingCurve::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&[1; 32]); // authority
+        data.extend_from_slice(&[2; 32]); // treasury_wallet
+        data.extend_from_slice(&20_000_000_000u64.to_le_bytes()); // x
+        data.extend_from_slice(&50_000_000_000_000_000u64.to_le_bytes()); // y
+        data.extend_from_slice(&(20_000_000_000u128 * 50_000_000_000_000_000).to_le_bytes()); // k
+        data.extend_from_slice(&1_700_000_000i64.to_le_bytes()); // last_daily_boost
+        data.extend_from_slice(&123u64.to_le_bytes()); // total_volume_24h
+        data.extend_from_slice(&7u64.to_le_bytes()); // sell_queue_head
+        data.extend_from_slice(&7u64.to_le_bytes()); // sell_queue_tail
+        data.extend_from_slice(&4u64.to_le_bytes()); // buy_queue_head
+        data.extend_from_slice(&4u64.to_le_bytes()); // buy_queue_tail
+        data.extend_from_slice(&55u64.to_le_bytes()); // cumulative_bonus
+        data.extend_from_slice(&400u64.to_le_bytes()); // current_price
+        data.extend_from_slice(&1_700_000_100i64.to_le_bytes()); // last_price_update
+        data.push(1); // daily_boost_applied
+        data.extend_from_slice(&9_000u64.to_le_bytes()); // circulating_supply
+        data.push(254); // bump
+        data
+    }
+
+    #[test]
+    fn legacy_layout_matches_deployed_size() {
+        assert_eq!(BondingCurveV0::INIT_SPACE, 178);
+        assert_eq!(legacy_account_data().len(), 8 + 178);
+    }
+
+    #[test]
+    fn legacy_curve_upgrades_to_legacy_market() {
+        let data = legacy_account_data();
+        let legacy = BondingCurveV0::deserialize(&mut &data[8..]).unwrap();
+        let supply = 50_000_000_000_000_000 + 1_000_000_000;
+        let curve = upgrade_legacy_curve(legacy, supply, 1_700_050_000, 253).unwrap();
+
+        assert_eq!(curve.authority, Pubkey::new_from_array([1; 32]));
+        assert_eq!(curve.treasury_wallet, Pubkey::new_from_array([2; 32]));
+        assert_eq!((curve.x, curve.y), (20_000_000_000, 50_000_000_000_000_000));
+        assert_eq!(curve.k, curve.x as u128 * curve.y as u128);
+        assert_eq!(curve.cumulative_bonus, 55);
+        assert_eq!(curve.current_price, 400);
+        assert!(curve.daily_boost_applied);
+        assert_eq!(curve.circulating_supply, 1_000_000_000);
+        assert_eq!(curve.bump, 253);
+        assert_eq!(curve.config, legacy_curve_params());
+        assert_eq!((curve.sell_queue_head, curve.sell_queue_tail, curve.buy_queue_head, curve.buy_queue_tail), (0, 0, 0, 0));
+        assert_eq!(curve.next_order_id, 1);
+        assert_eq!(curve.day_start_price, 400);
+        assert_eq!(curve.day_start_ts, everrise_math::day_start(1_700_050_000));
+        assert_eq!(curve.version, LAYOUT_VERSION);
+        assert!(legacy_curve_params().validate().is_ok());
+    }
+
+    #[test]
+    fn legacy_curve_with_open_orders_is_rejected() {
+        let mut data = legacy_account_data();
+        // sell_queue_tail one past the head - an order is still queued
+        let sell_queue_tail = 8 + 32 + 32 + 8 + 8 + 16 + 8 + 8 + 8;
+        data[sell_queue_tail..sell_queue_tail + 8].copy_from_slice(&8u64.to_le_bytes());
+        let legacy = BondingCurveV0::deserialize(&mut &data[8..]).unwrap();
+        assert!(upgrade_legacy_curve(legacy, 0, 0, 255).is_err());
+    }
+}
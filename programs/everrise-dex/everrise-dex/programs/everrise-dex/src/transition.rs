@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use everrise_math::Curve;
+
+use crate::{BondingCurve, ErrorCode, SELL_SETTLEMENT_BURN};
+
+// Supply accounting - every instruction that moves EVER into or out of the program books
+// it here, so X, Y, K, circulating_supply and total_burned change in exactly one place.
+//
+// circulating_supply is the EVER held outside the program: the mint supply minus the
+// reserves (Y) minus the EVER escrowed for open sell orders (escrowed_sell_ever). The
+// arithmetic lives in everrise-math, whose tests check that it always reconciles with the mint.
+
+/// buy, buy_smart and process_buy_queue - `usdc_amount` bought `ever_amount` from the reserves
+pub fn reserve_buy(bonding_curve: &mut BondingCurve, usdc_amount: u64, ever_amount: u64) -> Result<()> {
+    apply(bonding_curve, |curve| curve.reserve_buy(usdc_amount, ever_amount))
+}
+
+/// sell - the seller's EVER moved into escrow
+pub fn escrow_sell(bonding_curve: &mut BondingCurve, ever_amount: u64) -> Result<()> {
+    apply(bonding_curve, |curve| curve.escrow_sell(ever_amount))?;
+    bonding_curve.escrowed_sell_ever = bonding_curve.escrowed_sell_ever.checked_add(ever_amount).ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Queue fills and cancel_sell_order - escrowed EVER went to a buyer or back to its seller
+pub fn release_escrow(bonding_curve: &mut BondingCurve, ever_amount: u64) -> Result<()> {
+    apply(bonding_curve, |curve| curve.release_escrow(ever_amount))?;
+    bonding_curve.escrowed_sell_ever = bonding_curve.escrowed_sell_ever.checked_sub(ever_amount).ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// process_sell_queue - escrowed EVER sold to the reserves for `usdc_amount`,
+/// then burned or kept in Y according to sell_settlement
+pub fn reserve_sell(bonding_curve: &mut BondingCurve, usdc_amount: u64, ever_amount: u64) -> Result<()> {
+    let burn = bonding_curve.sell_settlement == SELL_SETTLEMENT_BURN;
+    apply(bonding_curve, |curve| curve.reserve_sell(usdc_amount, ever_amount, !burn))?;
+    bonding_curve.escrowed_sell_ever = bonding_curve.escrowed_sell_ever.checked_sub(ever_amount).ok_or(ErrorCode::MathOverflow)?;
+    if burn {
+        bonding_curve.total_burned = bonding_curve.total_burned.checked_add(ever_amount).ok_or(ErrorCode::MathOverflow)?;
+    }
+    Ok(())
+}
+
+/// Run a supply transition on the curve snapshot and store the reserves and supply back
+fn apply(
+    bonding_curve: &mut BondingCurve,
+    transition: impl FnOnce(&mut Curve) -> everrise_math::Result<()>,
+) -> Result<()> {
+    let mut curve = bonding_curve.curve();
+    transition(&mut curve).map_err(ErrorCode::from)?;
+
+    bonding_curve.x = curve.x;
+    bonding_curve.y = curve.y;
+    bonding_curve.k = curve.k;
+    bonding_curve.circulating_supply = curve.circulating_supply;
+    Ok(())
+}
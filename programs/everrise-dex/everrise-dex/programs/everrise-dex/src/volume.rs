@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+
+use crate::{BondingCurve, ErrorCode, LAYOUT_VERSION};
+
+// Trading volume - hourly USDC buckets in a companion VolumeStats PDA, rolled forward on
+// every trade, plus lifetime totals. Each trade is counted once by side (buy / sell) and
+// once by venue (sell queue / reserves), so a queue fill shows up as both a buy and a sell.
+
+pub const VOLUME_BUCKETS: usize = 24; // One per hour of the trailing day
+pub const SECONDS_PER_HOUR: i64 = 3_600;
+
+/// USDC volume, by side and by venue
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct Volume {
+    pub buy: u64, // USDC spent by buyers (buy, buy_smart, process_buy_queue)
+    pub sell: u64, // USDC paid to sellers (queue fills and direct sales to reserves)
+    pub queue: u64, // USDC matched against the sell queue
+    pub reserve: u64, // USDC traded against the reserves (X)
+}
+
+impl Volume {
+    fn checked_add(&self, other: &Volume) -> Option<Volume> {
+        Some(Volume {
+            buy: self.buy.checked_add(other.buy)?,
+            sell: self.sell.checked_add(other.sell)?,
+            queue: self.queue.checked_add(other.queue)?,
+            reserve: self.reserve.checked_add(other.reserve)?,
+        })
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct VolumeStats {
+    pub bonding_curve: Pubkey,
+    pub current_hour: i64, // Unix hour of the newest bucket - buckets cover the 24 hours up to it
+    pub hourly: [Volume; VOLUME_BUCKETS], // Indexed by unix hour % 24
+    pub lifetime: Volume, // Every trade since the stats account was created
+    pub bump: u8,
+    pub version: u8, // Layout version (LAYOUT_VERSION once migrated)
+    pub reserved: [u8; 64], // Room for new fields without a realloc - carve from the front
+}
+
+impl VolumeStats {
+    /// Roll the buckets forward to `timestamp`'s hour, clearing hours with no trades
+    fn roll(&mut self, timestamp: i64) {
+        let hour = timestamp.div_euclid(SECONDS_PER_HOUR);
+        if hour <= self.current_hour {
+            return; // Same hour (validator clocks can step back slightly - keep the newest bucket)
+        }
+
+        let hours_passed = (hour - self.current_hour).min(VOLUME_BUCKETS as i64);
+        for passed in 1..=hours_passed {
+            self.hourly[bucket_index(self.current_hour + passed)] = Volume::default();
+        }
+        self.current_hour = hour;
+    }
+
+    /// Volume over the 24 hours up to `timestamp` (only the buckets still inside that window)
+    pub fn trailing_24h(&self, timestamp: i64) -> Volume {
+        let hour = timestamp.div_euclid(SECONDS_PER_HOUR).max(self.current_hour);
+        let mut volume = Volume::default();
+        for bucket_hour in (hour - VOLUME_BUCKETS as i64 + 1)..=self.current_hour {
+            let bucket = &self.hourly[bucket_index(bucket_hour)];
+            volume.buy = volume.buy.saturating_add(bucket.buy);
+            volume.sell = volume.sell.saturating_add(bucket.sell);
+            volume.queue = volume.queue.saturating_add(bucket.queue);
+            volume.reserve = volume.reserve.saturating_add(bucket.reserve);
+        }
+        volume
+    }
+}
+
+fn bucket_index(hour: i64) -> usize {
+    hour.rem_euclid(VOLUME_BUCKETS as i64) as usize
+}
+
+/// Add a trade's volume at `timestamp`, keeping BondingCurve.total_volume_24h at the
+/// trailing 24h buy volume
+pub fn record_volume(
+    volume_stats: &mut VolumeStats,
+    bonding_curve: &mut BondingCurve,
+    timestamp: i64,
+    volume: Volume,
+) -> Result<()> {
+    volume_stats.roll(timestamp);
+
+    let bucket = &mut volume_stats.hourly[bucket_index(volume_stats.current_hour)];
+    *bucket = bucket.checked_add(&volume).ok_or(ErrorCode::MathOverflow)?;
+    volume_stats.lifetime = volume_stats.lifetime.checked_add(&volume).ok_or(ErrorCode::MathOverflow)?;
+
+    bonding_curve.total_volume_24h = volume_stats.trailing_24h(timestamp).buy;
+    Ok(())
+}
+
+/// Set up a fresh VolumeStats for a bonding curve
+pub fn init_volume_stats(volume_stats: &mut VolumeStats, bonding_curve: Pubkey, bump: u8, timestamp: i64) {
+    volume_stats.bonding_curve = bonding_curve;
+    volume_stats.current_hour = timestamp.div_euclid(SECONDS_PER_HOUR);
+    volume_stats.hourly = [Volume::default(); VOLUME_BUCKETS];
+    volume_stats.lifetime = Volume::default();
+    volume_stats.bump = bump;
+    volume_stats.version = LAYOUT_VERSION;
+}
+
+/// Create the VolumeStats account for a market opened before volume tracking (anyone may pay)
+pub fn handle_create_volume_stats(ctx: Context<CreateVolumeStats>) -> Result<()> {
+    let bonding_curve = ctx.accounts.bonding_curve.key();
+    init_volume_stats(
+        &mut ctx.accounts.volume_stats,
+        bonding_curve,
+        ctx.bumps.volume_stats,
+        Clock::get()?.unix_timestamp,
+    );
+
+    msg!("Volume stats created for {}", bonding_curve);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateVolumeStats<'info> {
+    #[account(
+        seeds = [b"bonding_curve", bonding_curve.config.base_mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + VolumeStats::INIT_SPACE,
+        seeds = [b"volume_stats", bonding_curve.key().as_ref()],
+        bump
+    )]
+    pub volume_stats: Account<'info, VolumeStats>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
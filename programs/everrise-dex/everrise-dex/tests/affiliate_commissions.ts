@@ -0,0 +1,525 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { EverriseDex } from "../target/types/everrise_dex";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  Transaction,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, createMint, createAccount, mintTo, getAccount } from "@solana/spl-token";
+import { createHash } from "crypto";
+import { expect } from "chai";
+
+// buy_smart referral commissions, paid through the affiliate program (loaded as a genesis
+// program, see Anchor.toml). The affiliate program lives in its own workspace on an older
+// Anchor, so its instructions are built by hand here.
+describe("EverRiseDEX Affiliate Commissions", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.EverriseDex as Program<EverriseDex>;
+  const provider = anchor.getProvider();
+
+  const AFFILIATE_PROGRAM_ID = new PublicKey("2vxcei86d8uh3NWcZKqJui9uFwMEe71KZbRmbFFv5ayP");
+  const DEFAULT_RATE_BPS = 300; // First default commission tier
+
+  let authority: Keypair;
+  let referrer: Keypair;
+  let buyer: Keypair;
+  let stranger: Keypair;
+  let upline: Keypair; // The referrer's own referrer, once registered
+
+  let everMint: PublicKey;
+  let usdcMint: PublicKey;
+  let bondingCurve: PublicKey;
+  let volumeStats: PublicKey;
+  let treasuryUsdcAccount: PublicKey;
+  let programEverAccount: PublicKey;
+  let buyerUsdcAccount: PublicKey;
+  let buyerEverAccount: PublicKey;
+  let strangerUsdcAccount: PublicKey;
+  let strangerEverAccount: PublicKey;
+  let referrerUsdcAccount: PublicKey;
+
+  const affiliatePda = (...seeds: Buffer[]) =>
+    PublicKey.findProgramAddressSync(seeds, AFFILIATE_PROGRAM_ID)[0];
+  const affiliateState = affiliatePda(Buffer.from("affiliate_program"));
+  const commissionVault = affiliatePda(Buffer.from("commission_vault"));
+  const referralPda = (wallet: PublicKey) => affiliatePda(Buffer.from("referral"), wallet.toBuffer());
+  const registryPda = (wallet: PublicKey) => affiliatePda(Buffer.from("referral_registry"), wallet.toBuffer());
+  // Each market signs process_commission with its own PDA
+  const affiliateAuthorityPda = (curve: PublicKey) =>
+    PublicKey.findProgramAddressSync([Buffer.from("affiliate_authority"), curve.toBuffer()], program.programId)[0];
+
+  // Anchor instruction data: sighash of "global:<name>", then the borsh-encoded args
+  const affiliateIx = (
+    name: string,
+    keys: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[],
+    args: Buffer = Buffer.alloc(0)
+  ) =>
+    new TransactionInstruction({
+      programId: AFFILIATE_PROGRAM_ID,
+      keys,
+      data: Buffer.concat([createHash("sha256").update(`global:${name}`).digest().subarray(0, 8), args]),
+    });
+
+  const send = (ix: TransactionInstruction, signers: Keypair[]) =>
+    provider.sendAndConfirm(new Transaction().add(ix), signers);
+
+  const expectError = async (attempt: Promise<unknown>, code: string) => {
+    try {
+      await attempt;
+      expect.fail(`Should have failed with ${code}`);
+    } catch (error) {
+      expect(`${error.message}\n${(error.logs ?? []).join("\n")}`).to.include(code);
+    }
+  };
+
+  // Register `wallet` under `referrerKey` - `upline` is the Referral chain above the referrer
+  const registerReferral = (wallet: Keypair, referrerKey: PublicKey, upline: PublicKey[] = [referralPda(referrerKey)]) =>
+    send(
+      affiliateIx(
+        "register_referral",
+        [
+          { pubkey: affiliateState, isSigner: false, isWritable: true },
+          { pubkey: referralPda(wallet.publicKey), isSigner: false, isWritable: true },
+          { pubkey: registryPda(referrerKey), isSigner: false, isWritable: true },
+          { pubkey: referrerKey, isSigner: false, isWritable: false },
+          { pubkey: wallet.publicKey, isSigner: true, isWritable: true },
+          { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+          ...upline.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false })),
+        ],
+        referrerKey.toBuffer()
+      ),
+      [wallet]
+    );
+
+  // ReferralRegistry balances, read past the variable-length rate_override_bps Option
+  const registryBalances = async (wallet: PublicKey) => {
+    const data = (await provider.connection.getAccountInfo(registryPda(wallet))).data;
+    const offset = 8 + 32 + 8 + 8 + 8 + (data[64] === 1 ? 3 : 1);
+    return {
+      totalCommissionEarned: Number(data.readBigUInt64LE(8 + 32 + 8)),
+      claimable: Number(data.readBigUInt64LE(offset)),
+      vestingAmount: Number(data.readBigUInt64LE(offset + 8)),
+      vestingStart: Number(data.readBigInt64LE(offset + 16)),
+      vestingEnd: Number(data.readBigInt64LE(offset + 24)),
+      totalClaimed: Number(data.readBigUInt64LE(offset + 32)),
+    };
+  };
+
+  // Borsh Option<u64>
+  const optionU64 = (value: number | null) =>
+    value === null
+      ? Buffer.from([0])
+      : Buffer.concat([Buffer.from([1]), new anchor.BN(value).toArrayLike(Buffer, "le", 8)]);
+
+  const claimCommissions = (maxAmount: number | null) =>
+    send(
+      affiliateIx(
+        "claim_commissions",
+        [
+          { pubkey: affiliateState, isSigner: false, isWritable: false },
+          { pubkey: registryPda(referrer.publicKey), isSigner: false, isWritable: true },
+          { pubkey: referrer.publicKey, isSigner: true, isWritable: false },
+          { pubkey: commissionVault, isSigner: false, isWritable: true },
+          { pubkey: referrerUsdcAccount, isSigner: false, isWritable: true },
+          { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+        ],
+        optionU64(maxAmount)
+      ),
+      [referrer]
+    );
+
+  // Pin the referrer to `rateBps`, or back to the tiers with null
+  const setRateOverride = (rateBps: number | null, signer: Keypair) =>
+    send(
+      affiliateIx(
+        "set_referrer_rate_override",
+        [
+          { pubkey: affiliateState, isSigner: false, isWritable: false },
+          { pubkey: registryPda(referrer.publicKey), isSigner: false, isWritable: true },
+          { pubkey: signer.publicKey, isSigner: true, isWritable: false },
+        ],
+        rateBps === null ? Buffer.from([0]) : Buffer.from([1, rateBps & 0xff, rateBps >> 8])
+      ),
+      [signer]
+    );
+
+  const usdcBalance = async (account: PublicKey) => Number((await getAccount(provider.connection, account)).amount);
+
+  // `uplineWalk` defaults to the referrer's own Referral, for a referrer nobody referred
+  const buySmart = (
+    user: Keypair,
+    userUsdcAccount: PublicKey,
+    userEverAccount: PublicKey,
+    usdcAmount: number,
+    referrerKey: PublicKey,
+    uplineWalk: PublicKey[] = [referralPda(referrerKey)]
+  ) =>
+    program.methods
+      .buySmart(new anchor.BN(usdcAmount), new anchor.BN(0), null, uplineWalk.length)
+      .accounts({
+        bondingCurve: bondingCurve,
+        user: user.publicKey,
+        userUsdcAccount: userUsdcAccount,
+        userEverAccount: userEverAccount,
+        treasuryUsdcAccount: treasuryUsdcAccount,
+        programEverAccount: programEverAccount,
+        sellOrder: bondingCurve, // Not used while the sell queue is empty
+        sellerUsdcAccount: userUsdcAccount, // Not used while the sell queue is empty
+        referral: referralPda(user.publicKey),
+        referrer: referrerKey,
+        commissionVault: commissionVault,
+        referralRegistry: registryPda(referrerKey),
+        affiliateState: affiliateState,
+        affiliateProgram: AFFILIATE_PROGRAM_ID,
+        affiliateAuthority: affiliateAuthorityPda(bondingCurve),
+        volumeStats: volumeStats,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(uplineWalk.map((pubkey) => ({ pubkey, isWritable: false, isSigner: false })))
+      .signers([user])
+      .rpc();
+
+  before(async () => {
+    authority = Keypair.generate();
+    referrer = Keypair.generate();
+    buyer = Keypair.generate();
+    stranger = Keypair.generate();
+    upline = Keypair.generate();
+
+    for (const wallet of [authority, referrer, buyer, stranger, upline]) {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(wallet.publicKey, 2 * anchor.web3.LAMPORTS_PER_SOL)
+      );
+    }
+
+    everMint = await createMint(provider.connection, authority, authority.publicKey, null, 9);
+    usdcMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+
+    [bondingCurve] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bonding_curve"), everMint.toBuffer()],
+      program.programId
+    );
+    [volumeStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from("volume_stats"), bondingCurve.toBuffer()],
+      program.programId
+    );
+
+    // The bonding curve PDA holds the vaults and is the treasury wallet
+    treasuryUsdcAccount = await createAccount(provider.connection, authority, usdcMint, bondingCurve, Keypair.generate());
+    programEverAccount = await createAccount(provider.connection, authority, everMint, bondingCurve, Keypair.generate());
+    await mintTo(provider.connection, authority, usdcMint, treasuryUsdcAccount, authority, 1_000_000 * 1_000_000);
+    await mintTo(provider.connection, authority, everMint, programEverAccount, authority, 1_000_000_000 * 1_000_000_000);
+
+    buyerUsdcAccount = await createAccount(provider.connection, buyer, usdcMint, buyer.publicKey);
+    buyerEverAccount = await createAccount(provider.connection, buyer, everMint, buyer.publicKey);
+    strangerUsdcAccount = await createAccount(provider.connection, stranger, usdcMint, stranger.publicKey);
+    strangerEverAccount = await createAccount(provider.connection, stranger, everMint, stranger.publicKey);
+    referrerUsdcAccount = await createAccount(provider.connection, referrer, usdcMint, referrer.publicKey);
+    await mintTo(provider.connection, authority, usdcMint, buyerUsdcAccount, authority, 10_000 * 1_000_000);
+    await mintTo(provider.connection, authority, usdcMint, strangerUsdcAccount, authority, 10_000 * 1_000_000);
+
+    await program.methods
+      .createMarket(bondingCurve, {
+        quoteMint: usdcMint,
+        baseMint: everMint,
+        initialX: new anchor.BN(1_000_000 * 1_000_000),
+        initialY: new anchor.BN("1000000000000000000"),
+        dailyGrowthBps: 2,
+        maxQuoteAmount: new anchor.BN("10000000000000"),
+        maxBaseAmount: new anchor.BN("10000000000000000"),
+        commissionBps: 500,
+      }, AFFILIATE_PROGRAM_ID)
+      .accounts({
+        bondingCurve: bondingCurve,
+        baseMint: everMint,
+        quoteMint: usdcMint,
+        authority: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    // Only the allow-listed market's affiliate authority PDA may report purchases
+    await send(
+      affiliateIx(
+        "initialize",
+        [
+          { pubkey: affiliateState, isSigner: false, isWritable: true },
+          { pubkey: commissionVault, isSigner: false, isWritable: true },
+          { pubkey: usdcMint, isSigner: false, isWritable: false },
+          { pubkey: authority.publicKey, isSigner: true, isWritable: true },
+          { pubkey: bondingCurve, isSigner: false, isWritable: false },
+          { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+          { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+          { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
+        ],
+        // Borsh Vec<Pubkey>: u32 length, then the keys
+        Buffer.concat([Buffer.from([1, 0, 0, 0]), affiliateAuthorityPda(bondingCurve).toBuffer()])
+      ),
+      [authority]
+    );
+
+    await registerReferral(buyer, referrer.publicKey);
+  });
+
+  it("records the affiliate program on the market", async () => {
+    const curve = await program.account.bondingCurve.fetch(bondingCurve);
+    expect(curve.affiliateProgram.toString()).to.equal(AFFILIATE_PROGRAM_ID.toString());
+  });
+
+  it("pays a referred buyer's commission into the affiliate vault", async () => {
+    const usdcAmount = 1_000 * 1_000_000;
+    const commission = (usdcAmount * DEFAULT_RATE_BPS) / 10_000;
+
+    const treasuryBefore = (await getAccount(provider.connection, treasuryUsdcAccount)).amount;
+    await buySmart(buyer, buyerUsdcAccount, buyerEverAccount, usdcAmount, referrer.publicKey);
+
+    const vault = await getAccount(provider.connection, commissionVault);
+    const treasuryAfter = (await getAccount(provider.connection, treasuryUsdcAccount)).amount;
+    expect(Number(vault.amount)).to.equal(commission);
+    expect(Number(treasuryAfter - treasuryBefore)).to.equal(usdcAmount - commission);
+    expect(Number((await getAccount(provider.connection, buyerEverAccount)).amount)).to.be.greaterThan(0);
+  });
+
+  it("sends an unreferred buyer's commission to the treasury", async () => {
+    const usdcAmount = 1_000 * 1_000_000;
+
+    const vaultBefore = (await getAccount(provider.connection, commissionVault)).amount;
+    const treasuryBefore = (await getAccount(provider.connection, treasuryUsdcAccount)).amount;
+    // No Referral - any key stands in for the referrer
+    await buySmart(stranger, strangerUsdcAccount, strangerEverAccount, usdcAmount, stranger.publicKey);
+
+    const vaultAfter = (await getAccount(provider.connection, commissionVault)).amount;
+    const treasuryAfter = (await getAccount(provider.connection, treasuryUsdcAccount)).amount;
+    expect(vaultAfter).to.equal(vaultBefore);
+    expect(Number(treasuryAfter - treasuryBefore)).to.equal(usdcAmount);
+  });
+
+  it("rejects process_commission not signed by the DEX", async () => {
+    const vaultBefore = (await getAccount(provider.connection, commissionVault)).amount;
+    const purchaseAmount = new anchor.BN(1_000 * 1_000_000).toArrayLike(Buffer, "le", 8);
+
+    try {
+      await send(
+        affiliateIx(
+          "process_commission",
+          [
+            { pubkey: affiliateState, isSigner: false, isWritable: true },
+            { pubkey: referralPda(buyer.publicKey), isSigner: false, isWritable: false },
+            { pubkey: registryPda(referrer.publicKey), isSigner: false, isWritable: true },
+            { pubkey: referrer.publicKey, isSigner: false, isWritable: false },
+            { pubkey: buyer.publicKey, isSigner: true, isWritable: false },
+            { pubkey: buyerUsdcAccount, isSigner: false, isWritable: true },
+            { pubkey: commissionVault, isSigner: false, isWritable: true },
+            { pubkey: buyer.publicKey, isSigner: true, isWritable: false }, // Not the commission authority
+            { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+            { pubkey: referralPda(referrer.publicKey), isSigner: false, isWritable: false },
+          ],
+          purchaseAmount
+        ),
+        [buyer]
+      );
+      expect.fail("Should have failed without the DEX's signature");
+    } catch (error) {
+      expect(`${error.message}\n${(error.logs ?? []).join("\n")}`).to.include("Unauthorized");
+    }
+
+    expect((await getAccount(provider.connection, commissionVault)).amount).to.equal(vaultBefore);
+  });
+
+  it("rejects commissions from a market the affiliate program has not allow-listed", async () => {
+    // Anyone holding a mint authority can open a market and point it at the affiliate program
+    const attacker = Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(attacker.publicKey, 2 * anchor.web3.LAMPORTS_PER_SOL)
+    );
+    const rogueMint = await createMint(provider.connection, attacker, attacker.publicKey, null, 9);
+    const [rogueCurve] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bonding_curve"), rogueMint.toBuffer()],
+      program.programId
+    );
+    const [rogueVolumeStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from("volume_stats"), rogueCurve.toBuffer()],
+      program.programId
+    );
+    const rogueTreasuryUsdc = await createAccount(provider.connection, attacker, usdcMint, attacker.publicKey, Keypair.generate());
+    const rogueEverAccount = await createAccount(provider.connection, attacker, rogueMint, rogueCurve, Keypair.generate());
+    const attackerUsdcAccount = await createAccount(provider.connection, attacker, usdcMint, attacker.publicKey);
+    const attackerEverAccount = await createAccount(provider.connection, attacker, rogueMint, attacker.publicKey);
+    await mintTo(provider.connection, attacker, rogueMint, rogueEverAccount, attacker, 1_000_000_000 * 1_000_000_000);
+    await mintTo(provider.connection, authority, usdcMint, attackerUsdcAccount, authority, 1_000 * 1_000_000);
+
+    await program.methods
+      .createMarket(attacker.publicKey, {
+        quoteMint: usdcMint,
+        baseMint: rogueMint,
+        initialX: new anchor.BN(1_000_000 * 1_000_000),
+        initialY: new anchor.BN("1000000000000000000"),
+        dailyGrowthBps: 2,
+        maxQuoteAmount: new anchor.BN("10000000000000"),
+        maxBaseAmount: new anchor.BN("10000000000000000"),
+        commissionBps: 500,
+      }, AFFILIATE_PROGRAM_ID)
+      .accounts({
+        bondingCurve: rogueCurve,
+        baseMint: rogueMint,
+        quoteMint: usdcMint,
+        authority: attacker.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([attacker])
+      .rpc();
+    await registerReferral(attacker, stranger.publicKey);
+
+    const vaultBefore = (await getAccount(provider.connection, commissionVault)).amount;
+    await expectError(
+      program.methods
+        .buySmart(new anchor.BN(1_000 * 1_000_000), new anchor.BN(0), null, 1)
+        .accounts({
+          bondingCurve: rogueCurve,
+          user: attacker.publicKey,
+          userUsdcAccount: attackerUsdcAccount,
+          userEverAccount: attackerEverAccount,
+          treasuryUsdcAccount: rogueTreasuryUsdc,
+          programEverAccount: rogueEverAccount,
+          sellOrder: rogueCurve,
+          sellerUsdcAccount: attackerUsdcAccount,
+          referral: referralPda(attacker.publicKey),
+          referrer: stranger.publicKey,
+          commissionVault: commissionVault,
+          referralRegistry: registryPda(stranger.publicKey),
+          affiliateState: affiliateState,
+          affiliateProgram: AFFILIATE_PROGRAM_ID,
+          affiliateAuthority: affiliateAuthorityPda(rogueCurve),
+          volumeStats: rogueVolumeStats,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([{ pubkey: referralPda(stranger.publicKey), isWritable: false, isSigner: false }])
+        .signers([attacker])
+        .rpc(),
+      "Unauthorized"
+    );
+
+    expect((await getAccount(provider.connection, commissionVault)).amount).to.equal(vaultBefore);
+    expect((await registryBalances(stranger.publicKey)).totalCommissionEarned).to.equal(0);
+  });
+
+  it("lets the referrer claim part of their commissions", async () => {
+    const commission = (1_000 * 1_000_000 * DEFAULT_RATE_BPS) / 10_000; // From the referred buy above
+    const partial = 10 * 1_000_000;
+
+    await claimCommissions(partial);
+
+    expect(await usdcBalance(referrerUsdcAccount)).to.equal(partial);
+    expect(await usdcBalance(commissionVault)).to.equal(commission - partial);
+    const registry = await registryBalances(referrer.publicKey);
+    expect(registry.claimable).to.equal(commission - partial);
+    expect(registry.totalClaimed).to.equal(partial);
+  });
+
+  it("lets the referrer claim the rest and then rejects an empty claim", async () => {
+    const commission = (1_000 * 1_000_000 * DEFAULT_RATE_BPS) / 10_000;
+
+    await claimCommissions(null);
+
+    expect(await usdcBalance(referrerUsdcAccount)).to.equal(commission);
+    expect(await usdcBalance(commissionVault)).to.equal(0);
+    expect((await registryBalances(referrer.publicKey)).claimable).to.equal(0);
+
+    await expectError(claimCommissions(null), "NothingToClaim");
+  });
+
+  it("vests new commissions without resetting what has vested", async () => {
+    const vestingPeriod = 30 * 24 * 60 * 60; // 30 days
+    await send(
+      affiliateIx(
+        "set_vesting_period",
+        [
+          { pubkey: affiliateState, isSigner: false, isWritable: true },
+          { pubkey: authority.publicKey, isSigner: true, isWritable: false },
+        ],
+        new anchor.BN(vestingPeriod).toArrayLike(Buffer, "le", 8)
+      ),
+      [authority]
+    );
+
+    const usdcAmount = 1_000 * 1_000_000;
+    const commission = (usdcAmount * DEFAULT_RATE_BPS) / 10_000;
+
+    await buySmart(buyer, buyerUsdcAccount, buyerEverAccount, usdcAmount, referrer.publicKey);
+    const first = await registryBalances(referrer.publicKey);
+    expect(first.claimable + first.vestingAmount).to.equal(commission);
+    expect(first.vestingEnd - first.vestingStart).to.equal(vestingPeriod);
+
+    await buySmart(buyer, buyerUsdcAccount, buyerEverAccount, usdcAmount, referrer.publicKey);
+    const second = await registryBalances(referrer.publicKey);
+    expect(second.claimable + second.vestingAmount).to.equal(2 * commission);
+    expect(second.claimable).to.be.at.least(first.claimable);
+    // The first commission was already on its way, so the shared window ends before a full period
+    expect(second.vestingEnd).to.be.at.most(second.vestingStart + vestingPeriod);
+    expect(second.vestingEnd).to.be.at.least(first.vestingEnd);
+  });
+
+  it("keeps a referred wallet with its first referrer", async () => {
+    await expectError(registerReferral(buyer, stranger.publicKey), "AlreadyReferred");
+    await expectError(registerReferral(stranger, stranger.publicKey), "SelfReferral");
+
+    const referral = await provider.connection.getAccountInfo(referralPda(buyer.publicKey));
+    expect(new PublicKey(referral.data.subarray(8, 40)).toString()).to.equal(referrer.publicKey.toString());
+  });
+
+  it("charges the referrer's rate override instead of the tier", async () => {
+    const usdcAmount = 1_000 * 1_000_000;
+    const overrideBps = 1_000;
+
+    await expectError(setRateOverride(overrideBps, stranger), "Unauthorized");
+    await expectError(setRateOverride(10_001, authority), "InvalidCommissionAmount");
+    await setRateOverride(overrideBps, authority);
+
+    const before = await registryBalances(referrer.publicKey);
+    await buySmart(buyer, buyerUsdcAccount, buyerEverAccount, usdcAmount, referrer.publicKey);
+    const after = await registryBalances(referrer.publicKey);
+    expect(after.totalCommissionEarned - before.totalCommissionEarned).to.equal((usdcAmount * overrideBps) / 10_000);
+
+    // Back on the tiers
+    await setRateOverride(null, authority);
+    await buySmart(buyer, buyerUsdcAccount, buyerEverAccount, usdcAmount, referrer.publicKey);
+    const tiered = await registryBalances(referrer.publicKey);
+    expect(tiered.totalCommissionEarned - after.totalCommissionEarned).to.equal((usdcAmount * DEFAULT_RATE_BPS) / 10_000);
+  });
+
+  it("splits a commission with the referrer's upline", async () => {
+    const usdcAmount = 1_000 * 1_000_000;
+    const commission = (usdcAmount * DEFAULT_RATE_BPS) / 10_000;
+    const uplineShare = (commission * 2_000) / 10_000; // Default split for level 2
+
+    await registerReferral(referrer, upline.publicKey);
+    // buyer -> referrer -> upline: the upline cannot also be referred by the buyer
+    await expectError(
+      registerReferral(upline, buyer.publicKey, [referralPda(buyer.publicKey), referralPda(referrer.publicKey)]),
+      "ReferralCycle"
+    );
+
+    const referrerBefore = await registryBalances(referrer.publicKey);
+    await buySmart(buyer, buyerUsdcAccount, buyerEverAccount, usdcAmount, referrer.publicKey, [
+      referralPda(referrer.publicKey),
+      registryPda(upline.publicKey),
+    ]);
+
+    const referrerAfter = await registryBalances(referrer.publicKey);
+    const uplineAfter = await registryBalances(upline.publicKey);
+    expect(referrerAfter.totalCommissionEarned - referrerBefore.totalCommissionEarned).to.equal(commission - uplineShare);
+    expect(uplineAfter.totalCommissionEarned).to.equal(uplineShare);
+
+    // Without the upline's registry the walk is incomplete
+    await expectError(
+      buySmart(buyer, buyerUsdcAccount, buyerEverAccount, usdcAmount, referrer.publicKey),
+      "UplineAccountsMissing"
+    );
+  });
+});
@@ -23,8 +23,13 @@ describe("EverRiseDEX Comprehensive Tests", () => {
   let aliceUsdcAccount: PublicKey;
   let bobUsdcAccount: PublicKey;
   let charlieUsdcAccount: PublicKey;
+  let dianaUsdcAccount: PublicKey;
+  let eveUsdcAccount: PublicKey;
   let dianaEverAccount: PublicKey;
   let eveEverAccount: PublicKey;
+  let aliceEverAccount: PublicKey;
+  let bobEverAccount: PublicKey;
+  let charlieEverAccount: PublicKey;
 
   // Program accounts
   let bondingCurve: PublicKey;
@@ -33,13 +38,46 @@ describe("EverRiseDEX Comprehensive Tests", () => {
   let treasuryUsdcAccount: PublicKey;
   let programUsdcAccount: PublicKey;
   let programEverAccount: PublicKey;
-  let burnEverAccount: PublicKey;
 
   // Constants
   const INITIAL_TREASURY_USDC = 1_000_000 * 1_000_000; // 1M USDC (6 decimals)
   const INITIAL_RESERVE_EVER = 1_000_000_000 * 1_000_000_000; // 1B EVER (9 decimals)
   const INITIAL_PRICE = 0.001; // 0.001 USDC per EVER
 
+  // Order PDAs are seeded by order id - ids come from bondingCurve.nextOrderId and 0 means "no order"
+  const orderPda = (kind: "sell_order" | "buy_order", curve: PublicKey, orderId: anchor.BN | number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from(kind), curve.toBuffer(), new anchor.BN(orderId).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+  // New orders link from the current tail order (null when the queue is empty)
+  const tailOrder = (kind: "sell_order" | "buy_order", curve: PublicKey, tailId: anchor.BN) =>
+    tailId.isZero() ? null : orderPda(kind, curve, tailId);
+
+  // verify_invariants with every open sell order, head first - fails on any broken invariant
+  const expectInvariantsHold = async () => {
+    const state = await program.account.bondingCurve.fetch(bondingCurve);
+    const openSellOrders: PublicKey[] = [];
+    for (let orderId = state.sellQueueHead; !orderId.isZero(); ) {
+      const sellOrderPda = orderPda("sell_order", bondingCurve, orderId);
+      openSellOrders.push(sellOrderPda);
+      orderId = (await program.account.sellOrder.fetch(sellOrderPda)).next;
+    }
+
+    const report = await program.methods
+      .verifyInvariants()
+      .accounts({
+        bondingCurve: bondingCurve,
+        treasuryUsdcAccount: treasuryUsdcAccount,
+        programEverAccount: programEverAccount,
+        everMint: everMint,
+      })
+      .remainingAccounts(openSellOrders.map((pubkey) => ({ pubkey, isWritable: false, isSigner: false })))
+      .view();
+    expect(report.violations).to.equal(0);
+  };
+
   before(async () => {
     // Create test participants
     alice = Keypair.generate();
@@ -96,6 +134,21 @@ describe("EverRiseDEX Comprehensive Tests", () => {
       charlie.publicKey
     );
 
+    // Sellers receive queue fills in their own USDC accounts
+    dianaUsdcAccount = await createAccount(
+      provider.connection,
+      diana,
+      usdcMint,
+      diana.publicKey
+    );
+
+    eveUsdcAccount = await createAccount(
+      provider.connection,
+      eve,
+      usdcMint,
+      eve.publicKey
+    );
+
     dianaEverAccount = await createAccount(
       provider.connection,
       diana,
@@ -110,33 +163,57 @@ describe("EverRiseDEX Comprehensive Tests", () => {
       eve.publicKey
     );
 
-    // Create program token accounts
-    treasuryUsdcAccount = await createAccount(
+    // Buyers receive queued fills in their own EVER accounts
+    aliceEverAccount = await createAccount(
       provider.connection,
       alice,
-      usdcMint,
+      everMint,
       alice.publicKey
     );
 
-    programUsdcAccount = await createAccount(
+    bobEverAccount = await createAccount(
+      provider.connection,
+      bob,
+      everMint,
+      bob.publicKey
+    );
+
+    charlieEverAccount = await createAccount(
+      provider.connection,
+      charlie,
+      everMint,
+      charlie.publicKey
+    );
+
+    // Program token accounts are owned by the bonding curve PDA, which also acts as the
+    // treasury wallet so it can sign sell-queue payouts
+    [bondingCurve] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bonding_curve"), everMint.toBuffer()],
+      program.programId
+    );
+
+    treasuryUsdcAccount = await createAccount(
       provider.connection,
       alice,
       usdcMint,
-      alice.publicKey
+      bondingCurve,
+      Keypair.generate()
     );
 
-    programEverAccount = await createAccount(
+    programUsdcAccount = await createAccount(
       provider.connection,
       alice,
-      everMint,
-      alice.publicKey
+      usdcMint,
+      bondingCurve,
+      Keypair.generate()
     );
 
-    burnEverAccount = await createAccount(
+    programEverAccount = await createAccount(
       provider.connection,
       alice,
       everMint,
-      alice.publicKey
+      bondingCurve,
+      Keypair.generate()
     );
 
     // Mint initial tokens
@@ -196,22 +273,22 @@ describe("EverRiseDEX Comprehensive Tests", () => {
     );
 
     // Initialize bonding curve
-    const [bondingCurvePda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("bonding_curve")],
-      program.programId
-    );
-    bondingCurve = bondingCurvePda;
-
     await program.methods
-      .initialize(
-        new anchor.BN(INITIAL_TREASURY_USDC),
-        new anchor.BN(INITIAL_RESERVE_EVER),
-        treasuryUsdcAccount
-      )
+      .createMarket(bondingCurve, {
+        quoteMint: usdcMint,
+        baseMint: everMint,
+        initialX: new anchor.BN(INITIAL_TREASURY_USDC),
+        initialY: new anchor.BN(INITIAL_RESERVE_EVER),
+        dailyGrowthBps: 2,
+        maxQuoteAmount: new anchor.BN("10000000000000"), // 10M USDC
+        maxBaseAmount: new anchor.BN("10000000000000000"), // 10M EVER
+        commissionBps: 500,
+      }, PublicKey.default) // No affiliate program - commissions go to the treasury
       .accounts({
         bondingCurve: bondingCurve,
+        baseMint: everMint,
+        quoteMint: usdcMint,
         authority: alice.publicKey,
-        treasuryWallet: alice.publicKey,
         systemProgram: SystemProgram.programId,
       })
       .signers([alice])
@@ -227,21 +304,20 @@ describe("EverRiseDEX Comprehensive Tests", () => {
 
     // Create buy order PDA
     const [buyOrderPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("buy_order"), bondingCurveBefore.buyQueueTail.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("buy_order"), bondingCurve.toBuffer(), bondingCurveBefore.nextOrderId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
     // Execute buy
     await program.methods
-      .buy(new anchor.BN(buyAmount))
+      .queueBuy(new anchor.BN(buyAmount))
       .accounts({
         bondingCurve: bondingCurve,
         buyOrder: buyOrderPda,
+        tailBuyOrder: tailOrder("buy_order", bondingCurve, bondingCurveBefore.buyQueueTail),
         user: alice.publicKey,
         userUsdcAccount: aliceUsdcAccount,
-        userEverAccount: alice.publicKey, // Will be created
         programUsdcAccount: programUsdcAccount,
-        everMint: everMint,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
@@ -250,7 +326,7 @@ describe("EverRiseDEX Comprehensive Tests", () => {
 
     // Process buy queue
     const [sellOrderPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("sell_order"), bondingCurveBefore.sellQueueHead.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("sell_order"), bondingCurve.toBuffer(), bondingCurveBefore.sellQueueHead.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
@@ -262,7 +338,7 @@ describe("EverRiseDEX Comprehensive Tests", () => {
         sellOrder: sellOrderPda,
         programUsdcAccount: programUsdcAccount,
         programEverAccount: programEverAccount,
-        buyerEverAccount: alice.publicKey, // Will be created
+        buyerEverAccount: aliceEverAccount,
         sellerUsdcAccount: alice.publicKey, // Not used in this case
         treasuryUsdcAccount: treasuryUsdcAccount,
         everMint: everMint,
@@ -270,6 +346,8 @@ describe("EverRiseDEX Comprehensive Tests", () => {
       })
       .rpc();
 
+    await expectInvariantsHold();
+
     // Verify results
     const bondingCurveAfter = await program.account.bondingCurve.fetch(bondingCurve);
     const priceAfter = Number(bondingCurveAfter.x) / Number(bondingCurveAfter.y);
@@ -291,16 +369,17 @@ describe("EverRiseDEX Comprehensive Tests", () => {
 
     // Create sell order PDA
     const [sellOrderPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("sell_order"), bondingCurveState.sellQueueTail.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("sell_order"), bondingCurve.toBuffer(), bondingCurveState.nextOrderId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
     // Execute sell
     await program.methods
-      .sell(new anchor.BN(sellAmount))
+      .sell(new anchor.BN(sellAmount), new anchor.BN(0), null)
       .accounts({
         bondingCurve: bondingCurve,
         sellOrder: sellOrderPda,
+        tailSellOrder: tailOrder("sell_order", bondingCurve, bondingCurveState.sellQueueTail),
         user: diana.publicKey,
         userEverAccount: dianaEverAccount,
         programEverAccount: programEverAccount,
@@ -317,6 +396,10 @@ describe("EverRiseDEX Comprehensive Tests", () => {
     expect(Number(sellOrder.remainingAmount)).to.equal(sellAmount);
     expect(sellOrder.processed).to.be.false;
 
+    // Escrowed EVER leaves circulation
+    const bondingCurveAfter = await program.account.bondingCurve.fetch(bondingCurve);
+    expect(bondingCurveState.circulatingSupply.sub(bondingCurveAfter.circulatingSupply).toNumber()).to.equal(sellAmount);
+
     console.log(`Sell order created: ${Number(sellOrder.everAmount) / 1_000_000_000} EVER at price ${Number(sellOrder.lockedPrice) / 1_000_000_000}`);
   });
 
@@ -328,21 +411,20 @@ describe("EverRiseDEX Comprehensive Tests", () => {
 
     // Create buy order PDA
     const [buyOrderPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("buy_order"), bondingCurveState.buyQueueTail.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("buy_order"), bondingCurve.toBuffer(), bondingCurveState.nextOrderId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
     // Execute buy
     await program.methods
-      .buy(new anchor.BN(buyAmount))
+      .queueBuy(new anchor.BN(buyAmount))
       .accounts({
         bondingCurve: bondingCurve,
         buyOrder: buyOrderPda,
+        tailBuyOrder: tailOrder("buy_order", bondingCurve, bondingCurveState.buyQueueTail),
         user: bob.publicKey,
         userUsdcAccount: bobUsdcAccount,
-        userEverAccount: bob.publicKey, // Will be created
         programUsdcAccount: programUsdcAccount,
-        everMint: everMint,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
@@ -351,7 +433,7 @@ describe("EverRiseDEX Comprehensive Tests", () => {
 
     // Process buy queue (should match with Diana's sell order)
     const [sellOrderPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("sell_order"), bondingCurveState.sellQueueHead.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("sell_order"), bondingCurve.toBuffer(), bondingCurveState.sellQueueHead.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
@@ -363,19 +445,25 @@ describe("EverRiseDEX Comprehensive Tests", () => {
         sellOrder: sellOrderPda,
         programUsdcAccount: programUsdcAccount,
         programEverAccount: programEverAccount,
-        buyerEverAccount: bob.publicKey, // Will be created
-        sellerUsdcAccount: diana.publicKey, // Diana should receive USDC
+        buyerEverAccount: bobEverAccount,
+        sellerUsdcAccount: dianaUsdcAccount, // Diana should receive USDC
         treasuryUsdcAccount: treasuryUsdcAccount,
         everMint: everMint,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
       .rpc();
 
+    await expectInvariantsHold();
+
     // Verify partial fill
     const sellOrderAfter = await program.account.sellOrder.fetch(sellOrderPda);
     expect(Number(sellOrderAfter.remainingAmount)).to.be.lessThan(Number(sellOrderAfter.everAmount));
     expect(sellOrderAfter.processed).to.be.false; // Should not be fully processed
 
+    // Queue fills accrue the (0.001 × V) / ((X/Y) × SC) appreciation bonus
+    const bondingCurveAfter = await program.account.bondingCurve.fetch(bondingCurve);
+    expect(Number(bondingCurveAfter.cumulativeBonus)).to.be.greaterThan(Number(bondingCurveState.cumulativeBonus));
+
     console.log(`Sell order remaining: ${Number(sellOrderAfter.remainingAmount) / 1_000_000_000} EVER`);
   });
 
@@ -387,21 +475,20 @@ describe("EverRiseDEX Comprehensive Tests", () => {
 
     // Create buy order PDA
     const [buyOrderPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("buy_order"), bondingCurveState.buyQueueTail.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("buy_order"), bondingCurve.toBuffer(), bondingCurveState.nextOrderId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
     // Execute buy
     await program.methods
-      .buy(new anchor.BN(buyAmount))
+      .queueBuy(new anchor.BN(buyAmount))
       .accounts({
         bondingCurve: bondingCurve,
         buyOrder: buyOrderPda,
+        tailBuyOrder: tailOrder("buy_order", bondingCurve, bondingCurveState.buyQueueTail),
         user: charlie.publicKey,
         userUsdcAccount: charlieUsdcAccount,
-        userEverAccount: charlie.publicKey, // Will be created
         programUsdcAccount: programUsdcAccount,
-        everMint: everMint,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
@@ -410,7 +497,7 @@ describe("EverRiseDEX Comprehensive Tests", () => {
 
     // Process buy queue (should complete Diana's sell order)
     const [sellOrderPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("sell_order"), bondingCurveState.sellQueueHead.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("sell_order"), bondingCurve.toBuffer(), bondingCurveState.sellQueueHead.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
@@ -422,14 +509,16 @@ describe("EverRiseDEX Comprehensive Tests", () => {
         sellOrder: sellOrderPda,
         programUsdcAccount: programUsdcAccount,
         programEverAccount: programEverAccount,
-        buyerEverAccount: charlie.publicKey, // Will be created
-        sellerUsdcAccount: diana.publicKey, // Diana should receive USDC
+        buyerEverAccount: charlieEverAccount,
+        sellerUsdcAccount: dianaUsdcAccount, // Diana should receive USDC
         treasuryUsdcAccount: treasuryUsdcAccount,
         everMint: everMint,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
       .rpc();
 
+    await expectInvariantsHold();
+
     // Verify sell order is completed
     const sellOrderAfter = await program.account.sellOrder.fetch(sellOrderPda);
     expect(sellOrderAfter.processed).to.be.true;
@@ -446,16 +535,17 @@ describe("EverRiseDEX Comprehensive Tests", () => {
 
     // Create sell order PDA
     const [sellOrderPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("sell_order"), bondingCurveState.sellQueueTail.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("sell_order"), bondingCurve.toBuffer(), bondingCurveState.nextOrderId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
     // Execute sell
     await program.methods
-      .sell(new anchor.BN(sellAmount))
+      .sell(new anchor.BN(sellAmount), new anchor.BN(0), null)
       .accounts({
         bondingCurve: bondingCurve,
         sellOrder: sellOrderPda,
+        tailSellOrder: tailOrder("sell_order", bondingCurve, bondingCurveState.sellQueueTail),
         user: eve.publicKey,
         userEverAccount: eveEverAccount,
         programEverAccount: programEverAccount,
@@ -483,21 +573,20 @@ describe("EverRiseDEX Comprehensive Tests", () => {
 
     // Create buy order PDA
     const [buyOrderPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("buy_order"), bondingCurveState.buyQueueTail.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("buy_order"), bondingCurve.toBuffer(), bondingCurveState.nextOrderId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
     // Execute buy
     await program.methods
-      .buy(new anchor.BN(buyAmount))
+      .queueBuy(new anchor.BN(buyAmount))
       .accounts({
         bondingCurve: bondingCurve,
         buyOrder: buyOrderPda,
+        tailBuyOrder: tailOrder("buy_order", bondingCurve, bondingCurveState.buyQueueTail),
         user: alice.publicKey,
         userUsdcAccount: aliceUsdcAccount,
-        userEverAccount: alice.publicKey, // Will be created
         programUsdcAccount: programUsdcAccount,
-        everMint: everMint,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
@@ -506,7 +595,7 @@ describe("EverRiseDEX Comprehensive Tests", () => {
 
     // Process buy queue (should match with Eve's sell order)
     const [sellOrderPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("sell_order"), bondingCurveState.sellQueueHead.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("sell_order"), bondingCurve.toBuffer(), bondingCurveState.sellQueueHead.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
@@ -518,14 +607,16 @@ describe("EverRiseDEX Comprehensive Tests", () => {
         sellOrder: sellOrderPda,
         programUsdcAccount: programUsdcAccount,
         programEverAccount: programEverAccount,
-        buyerEverAccount: alice.publicKey, // Will be created
-        sellerUsdcAccount: eve.publicKey, // Eve should receive USDC
+        buyerEverAccount: aliceEverAccount,
+        sellerUsdcAccount: eveUsdcAccount, // Eve should receive USDC
         treasuryUsdcAccount: treasuryUsdcAccount,
         everMint: everMint,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
       .rpc();
 
+    await expectInvariantsHold();
+
     // Verify Eve's sell order is completed
     const sellOrderAfter = await program.account.sellOrder.fetch(sellOrderPda);
     expect(sellOrderAfter.processed).to.be.true;
@@ -534,6 +625,235 @@ describe("EverRiseDEX Comprehensive Tests", () => {
     console.log(`Eve's sell order completed: ${Number(sellOrderAfter.everAmount) / 1_000_000_000} EVER`);
   });
 
+  it("Test Scenario 6b: Buy Spanning Multiple Sell Orders - Diana and Eve sell, Bob buys through both", async () => {
+    const sellAmount = 10_000 * 1_000_000_000; // 10K EVER each
+
+    // Earlier scenarios already paid both sellers
+    const dianaUsdcBefore = (await getAccount(provider.connection, dianaUsdcAccount)).amount;
+    const eveUsdcBefore = (await getAccount(provider.connection, eveUsdcAccount)).amount;
+
+    // Queue two consecutive sell orders (each links from the previous tail)
+    const sellOrderPdas: PublicKey[] = [];
+    for (const [seller, sellerEverAccount] of [[diana, dianaEverAccount], [eve, eveEverAccount]] as [Keypair, PublicKey][]) {
+      const state = await program.account.bondingCurve.fetch(bondingCurve);
+      const [sellOrderPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("sell_order"), bondingCurve.toBuffer(), state.nextOrderId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      await program.methods
+        .sell(new anchor.BN(sellAmount), new anchor.BN(0), null)
+        .accounts({
+          bondingCurve: bondingCurve,
+          sellOrder: sellOrderPda,
+          tailSellOrder: tailOrder("sell_order", bondingCurve, state.sellQueueTail),
+          user: seller.publicKey,
+          userEverAccount: sellerEverAccount,
+          programEverAccount: programEverAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([seller])
+        .rpc();
+      sellOrderPdas.push(sellOrderPda);
+    }
+
+    // Queue a buy large enough to consume both orders and spill into reserves
+    const bondingCurveState = await program.account.bondingCurve.fetch(bondingCurve);
+    const [buyOrderPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("buy_order"), bondingCurve.toBuffer(), bondingCurveState.nextOrderId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    await program.methods
+      .queueBuy(new anchor.BN(5_000 * 1_000_000))
+      .accounts({
+        bondingCurve: bondingCurve,
+        buyOrder: buyOrderPda,
+        tailBuyOrder: tailOrder("buy_order", bondingCurve, bondingCurveState.buyQueueTail),
+        user: bob.publicKey,
+        userUsdcAccount: bobUsdcAccount,
+        programUsdcAccount: programUsdcAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([bob])
+      .rpc();
+
+    // First pair goes in the named accounts, the second in remaining_accounts
+    await program.methods
+      .processBuyQueue()
+      .accounts({
+        bondingCurve: bondingCurve,
+        buyOrder: buyOrderPda,
+        sellOrder: sellOrderPdas[0],
+        programUsdcAccount: programUsdcAccount,
+        programEverAccount: programEverAccount,
+        buyerEverAccount: bobEverAccount,
+        sellerUsdcAccount: dianaUsdcAccount,
+        treasuryUsdcAccount: treasuryUsdcAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: sellOrderPdas[1], isWritable: true, isSigner: false },
+        { pubkey: eveUsdcAccount, isWritable: true, isSigner: false },
+      ])
+      .rpc();
+
+    await expectInvariantsHold();
+
+    // Both sell orders are consumed in one transaction
+    for (const sellOrderPda of sellOrderPdas) {
+      const sellOrderAfter = await program.account.sellOrder.fetch(sellOrderPda);
+      expect(sellOrderAfter.processed).to.be.true;
+      expect(Number(sellOrderAfter.remainingAmount)).to.equal(0);
+    }
+    const bondingCurveAfter = await program.account.bondingCurve.fetch(bondingCurve);
+    // Both orders are unlinked, leaving the sell queue empty
+    expect(bondingCurveAfter.sellQueueHead.toNumber()).to.equal(0);
+    expect(bondingCurveAfter.sellQueueTail.toNumber()).to.equal(0);
+    expect((await getAccount(provider.connection, dianaUsdcAccount)).amount > dianaUsdcBefore).to.be.true;
+    expect((await getAccount(provider.connection, eveUsdcAccount)).amount > eveUsdcBefore).to.be.true;
+  });
+
+  it("Test Scenario 6c: Sell Order Cancellation - Eve cancels her queued sell order", async () => {
+    const sellAmount = 5_000 * 1_000_000_000; // 5K EVER
+
+    const bondingCurveState = await program.account.bondingCurve.fetch(bondingCurve);
+    const orderId = bondingCurveState.nextOrderId;
+    const sellOrderPda = orderPda("sell_order", bondingCurve, orderId);
+
+    await program.methods
+      .sell(new anchor.BN(sellAmount), new anchor.BN(0), null)
+      .accounts({
+        bondingCurve: bondingCurve,
+        sellOrder: sellOrderPda,
+        tailSellOrder: tailOrder("sell_order", bondingCurve, bondingCurveState.sellQueueTail),
+        user: eve.publicKey,
+        userEverAccount: eveEverAccount,
+        programEverAccount: programEverAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([eve])
+      .rpc();
+
+    const eveEverBefore = Number((await getAccount(provider.connection, eveEverAccount)).amount);
+
+    // Another wallet cannot cancel Eve's order
+    try {
+      await program.methods
+        .cancelSellOrder(orderId)
+        .accounts({
+          bondingCurve: bondingCurve,
+          sellOrder: sellOrderPda,
+          prevSellOrder: null,
+          nextSellOrder: null,
+          seller: diana.publicKey,
+          sellerEverAccount: dianaEverAccount,
+          programEverAccount: programEverAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([diana])
+        .rpc();
+      expect.fail("Should have failed for a different seller");
+    } catch (error) {
+      expect(error.message).to.include("ConstraintHasOne");
+    }
+
+    await program.methods
+      .cancelSellOrder(orderId)
+      .accounts({
+        bondingCurve: bondingCurve,
+        sellOrder: sellOrderPda,
+        prevSellOrder: null,
+        nextSellOrder: null,
+        seller: eve.publicKey,
+        sellerEverAccount: eveEverAccount,
+        programEverAccount: programEverAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([eve])
+      .rpc();
+
+    // EVER is returned and the order PDA is closed
+    const eveEverAfter = Number((await getAccount(provider.connection, eveEverAccount)).amount);
+    expect(eveEverAfter - eveEverBefore).to.equal(sellAmount);
+    expect(await provider.connection.getAccountInfo(sellOrderPda)).to.be.null;
+
+    // Cancelling the only order empties the queue and puts the EVER back in circulation
+    const bondingCurveAfter = await program.account.bondingCurve.fetch(bondingCurve);
+    expect(bondingCurveAfter.sellQueueHead.toNumber()).to.equal(0);
+    expect(bondingCurveAfter.sellQueueTail.toNumber()).to.equal(0);
+    expect(bondingCurveAfter.circulatingSupply.toString()).to.equal(bondingCurveState.circulatingSupply.toString());
+  });
+
+  it("Test Scenario 6d: Cancelling from the Middle of the Sell Queue Relinks its Neighbours", async () => {
+    const sellAmount = 1_000 * 1_000_000_000; // 1K EVER each
+
+    // Diana, Eve and Diana queue three orders
+    const orderIds: anchor.BN[] = [];
+    for (const [seller, sellerEverAccount] of [[diana, dianaEverAccount], [eve, eveEverAccount], [diana, dianaEverAccount]] as [Keypair, PublicKey][]) {
+      const state = await program.account.bondingCurve.fetch(bondingCurve);
+      await program.methods
+        .sell(new anchor.BN(sellAmount), new anchor.BN(0), null)
+        .accounts({
+          bondingCurve: bondingCurve,
+          sellOrder: orderPda("sell_order", bondingCurve, state.nextOrderId),
+          tailSellOrder: tailOrder("sell_order", bondingCurve, state.sellQueueTail),
+          user: seller.publicKey,
+          userEverAccount: sellerEverAccount,
+          programEverAccount: programEverAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([seller])
+        .rpc();
+      orderIds.push(state.nextOrderId);
+    }
+    const [first, middle, last] = orderIds.map((id) => orderPda("sell_order", bondingCurve, id));
+
+    const cancel = (orderId: anchor.BN, sellOrder: PublicKey, prevSellOrder: PublicKey | null, nextSellOrder: PublicKey | null, seller: Keypair, sellerEverAccount: PublicKey) =>
+      program.methods
+        .cancelSellOrder(orderId)
+        .accounts({
+          bondingCurve: bondingCurve,
+          sellOrder,
+          prevSellOrder,
+          nextSellOrder,
+          seller: seller.publicKey,
+          sellerEverAccount,
+          programEverAccount: programEverAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([seller])
+        .rpc();
+
+    // A middle order needs both neighbours to unlink
+    try {
+      await cancel(orderIds[1], middle, null, last, eve, eveEverAccount);
+      expect.fail("Should have failed without the previous order");
+    } catch (error) {
+      expect(error.message).to.include("QueueLinkMissing");
+    }
+
+    await cancel(orderIds[1], middle, first, last, eve, eveEverAccount);
+    expect((await program.account.sellOrder.fetch(first)).next.toString()).to.equal(orderIds[2].toString());
+    expect((await program.account.sellOrder.fetch(last)).prev.toString()).to.equal(orderIds[0].toString());
+
+    let state = await program.account.bondingCurve.fetch(bondingCurve);
+    expect(state.sellQueueHead.toString()).to.equal(orderIds[0].toString());
+    expect(state.sellQueueTail.toString()).to.equal(orderIds[2].toString());
+
+    // Cancelling the head moves the head to its next order, then the last order empties the queue
+    await cancel(orderIds[0], first, null, last, diana, dianaEverAccount);
+    state = await program.account.bondingCurve.fetch(bondingCurve);
+    expect(state.sellQueueHead.toString()).to.equal(orderIds[2].toString());
+
+    await cancel(orderIds[2], last, null, null, diana, dianaEverAccount);
+    state = await program.account.bondingCurve.fetch(bondingCurve);
+    expect(state.sellQueueHead.toNumber()).to.equal(0);
+    expect(state.sellQueueTail.toNumber()).to.equal(0);
+  });
+
   it("Test Scenario 7: Daily Boost Application", async () => {
     // Get bonding curve state before
     const bondingCurveBefore = await program.account.bondingCurve.fetch(bondingCurve);
@@ -557,10 +877,321 @@ describe("EverRiseDEX Comprehensive Tests", () => {
     console.log(`Price after daily boost: ${priceAfter}`);
     console.log(`Cumulative bonus: ${Number(bondingCurveAfter.cumulativeBonus)}`);
 
+    // Days are anchored to midnight UTC and P_start is tracked alongside
+    expect(bondingCurveAfter.dayStartTs.toNumber() % 86400).to.equal(0);
+    expect(bondingCurveAfter.dayStartPrice.toNumber()).to.be.greaterThan(0);
+
     // Price should increase due to daily boost
     expect(priceAfter).to.be.greaterThan(priceBefore);
   });
 
+  it("Test Scenario 7b: Admin Authority Gating and Two-Step Transfer", async () => {
+    // Non-authority signers are rejected by admin instructions
+    try {
+      await program.methods
+        .applyDailyBoostManual()
+        .accounts({ bondingCurve: bondingCurve, authority: bob.publicKey })
+        .signers([bob])
+        .rpc();
+      expect.fail("Should have failed for non-authority");
+    } catch (error) {
+      expect(error.message).to.include("Unauthorized");
+    }
+
+    try {
+      await program.methods
+        .setTreasuryWallet(bob.publicKey)
+        .accounts({ bondingCurve: bondingCurve, authority: bob.publicKey })
+        .signers([bob])
+        .rpc();
+      expect.fail("Should have failed for non-authority");
+    } catch (error) {
+      expect(error.message).to.include("Unauthorized");
+    }
+
+    // Alice proposes Bob; only Bob can accept
+    await program.methods
+      .proposeAuthority(bob.publicKey)
+      .accounts({ bondingCurve: bondingCurve, authority: alice.publicKey })
+      .signers([alice])
+      .rpc();
+
+    try {
+      await program.methods
+        .acceptAuthority()
+        .accounts({ bondingCurve: bondingCurve, newAuthority: charlie.publicKey })
+        .signers([charlie])
+        .rpc();
+      expect.fail("Should have failed for a key that was not proposed");
+    } catch (error) {
+      expect(error.message).to.include("Unauthorized");
+    }
+
+    await program.methods
+      .acceptAuthority()
+      .accounts({ bondingCurve: bondingCurve, newAuthority: bob.publicKey })
+      .signers([bob])
+      .rpc();
+
+    let state = await program.account.bondingCurve.fetch(bondingCurve);
+    expect(state.authority.toString()).to.equal(bob.publicKey.toString());
+    expect(state.pendingAuthority.toString()).to.equal(PublicKey.default.toString());
+
+    // Hand authority back to Alice for the remaining scenarios
+    await program.methods
+      .proposeAuthority(alice.publicKey)
+      .accounts({ bondingCurve: bondingCurve, authority: bob.publicKey })
+      .signers([bob])
+      .rpc();
+    await program.methods
+      .acceptAuthority()
+      .accounts({ bondingCurve: bondingCurve, newAuthority: alice.publicKey })
+      .signers([alice])
+      .rpc();
+
+    state = await program.account.bondingCurve.fetch(bondingCurve);
+    expect(state.authority.toString()).to.equal(alice.publicKey.toString());
+  });
+
+  it("Test Scenario 7c: Pause and Unpause Trading", async () => {
+    const PAUSE_BUY = 1;
+    const PAUSE_SELL = 2;
+
+    await program.methods
+      .pause(PAUSE_BUY | PAUSE_SELL)
+      .accounts({ bondingCurve: bondingCurve, authority: alice.publicKey })
+      .signers([alice])
+      .rpc();
+
+    const state = await program.account.bondingCurve.fetch(bondingCurve);
+    expect(state.pausedOperations).to.equal(PAUSE_BUY | PAUSE_SELL);
+
+    // Buys are rejected while paused
+    const [buyOrderPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("buy_order"), bondingCurve.toBuffer(), state.nextOrderId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    try {
+      await program.methods
+        .queueBuy(new anchor.BN(100 * 1_000_000))
+        .accounts({
+          bondingCurve: bondingCurve,
+          buyOrder: buyOrderPda,
+          tailBuyOrder: tailOrder("buy_order", bondingCurve, state.buyQueueTail),
+          user: bob.publicKey,
+          userUsdcAccount: bobUsdcAccount,
+          programUsdcAccount: programUsdcAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bob])
+        .rpc();
+      expect.fail("Should have failed while paused");
+    } catch (error) {
+      expect(error.message).to.include("OperationPaused");
+    }
+
+    // Sells are rejected while paused
+    const [sellOrderPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("sell_order"), bondingCurve.toBuffer(), state.nextOrderId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    try {
+      await program.methods
+        .sell(new anchor.BN(1_000 * 1_000_000_000), new anchor.BN(0), null)
+        .accounts({
+          bondingCurve: bondingCurve,
+          sellOrder: sellOrderPda,
+          tailSellOrder: tailOrder("sell_order", bondingCurve, state.sellQueueTail),
+          user: diana.publicKey,
+          userEverAccount: dianaEverAccount,
+          programEverAccount: programEverAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([diana])
+        .rpc();
+      expect.fail("Should have failed while paused");
+    } catch (error) {
+      expect(error.message).to.include("OperationPaused");
+    }
+
+    // Only the authority can unpause
+    try {
+      await program.methods
+        .unpause(PAUSE_BUY | PAUSE_SELL)
+        .accounts({ bondingCurve: bondingCurve, authority: bob.publicKey })
+        .signers([bob])
+        .rpc();
+      expect.fail("Should have failed for non-authority");
+    } catch (error) {
+      expect(error.message).to.include("Unauthorized");
+    }
+
+    await program.methods
+      .unpause(PAUSE_BUY | PAUSE_SELL)
+      .accounts({ bondingCurve: bondingCurve, authority: alice.publicKey })
+      .signers([alice])
+      .rpc();
+
+    const stateAfter = await program.account.bondingCurve.fetch(bondingCurve);
+    expect(stateAfter.pausedOperations).to.equal(0);
+  });
+
+  it("Test Scenario 7d: Multi-Market - A second token gets its own curve and queues", async () => {
+    const secondMint = await createMint(provider.connection, alice, alice.publicKey, null, 9);
+    const [secondCurve] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bonding_curve"), secondMint.toBuffer()],
+      program.programId
+    );
+    const secondParams = {
+      quoteMint: usdcMint,
+      baseMint: secondMint,
+      initialX: new anchor.BN(INITIAL_TREASURY_USDC),
+      initialY: new anchor.BN(INITIAL_RESERVE_EVER),
+      dailyGrowthBps: 5,
+      maxQuoteAmount: new anchor.BN("10000000000000"),
+      maxBaseAmount: new anchor.BN("10000000000000000"),
+      commissionBps: 300,
+    };
+
+    // Only the base mint's mint authority can open a market for it
+    try {
+      await program.methods
+        .createMarket(bob.publicKey, secondParams, PublicKey.default)
+        .accounts({
+          bondingCurve: secondCurve,
+          baseMint: secondMint,
+          quoteMint: usdcMint,
+          authority: bob.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bob])
+        .rpc();
+      expect.fail("Should have failed for a non mint authority");
+    } catch (error) {
+      expect(error.message).to.include("Unauthorized");
+    }
+
+    await program.methods
+      .createMarket(alice.publicKey, secondParams, PublicKey.default)
+      .accounts({
+        bondingCurve: secondCurve,
+        baseMint: secondMint,
+        quoteMint: usdcMint,
+        authority: alice.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([alice])
+      .rpc();
+
+    const first = await program.account.bondingCurve.fetch(bondingCurve);
+    const second = await program.account.bondingCurve.fetch(secondCurve);
+    expect(second.config.baseMint.toString()).to.equal(secondMint.toString());
+    expect(second.config.dailyGrowthBps).to.equal(5);
+    expect(second.sellQueueTail.toNumber()).to.equal(0);
+    expect(second.buyQueueTail.toNumber()).to.equal(0);
+    expect(first.config.baseMint.toString()).to.equal(everMint.toString());
+
+    // Order PDAs are namespaced per curve
+    const [firstOrder] = PublicKey.findProgramAddressSync(
+      [Buffer.from("sell_order"), bondingCurve.toBuffer(), new anchor.BN(1).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [secondOrder] = PublicKey.findProgramAddressSync(
+      [Buffer.from("sell_order"), secondCurve.toBuffer(), new anchor.BN(1).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    expect(firstOrder.toString()).to.not.equal(secondOrder.toString());
+  });
+
+  it("Test Scenario 7e: Layout Versioning and Migration", async () => {
+    const versionInfo = await program.methods.getVersion().view();
+    const state = await program.account.bondingCurve.fetch(bondingCurve);
+    expect(state.version).to.equal(versionInfo.layoutVersion);
+
+    // Diana's sell order from Scenario 2 (order id 2)
+    const sellOrderPda = orderPda("sell_order", bondingCurve, 2);
+    const sellOrder = await program.account.sellOrder.fetch(sellOrderPda);
+    expect(sellOrder.version).to.equal(versionInfo.layoutVersion);
+
+    // Only the curve authority can migrate
+    try {
+      await program.methods
+        .migrateBondingCurve()
+        .accounts({ bondingCurve: bondingCurve, authority: bob.publicKey, systemProgram: SystemProgram.programId })
+        .signers([bob])
+        .rpc();
+      expect.fail("Should have failed for non-authority");
+    } catch (error) {
+      expect(error.message).to.include("Unauthorized");
+    }
+
+    // Accounts already at the current layout are left untouched
+    await program.methods
+      .migrateBondingCurve()
+      .accounts({ bondingCurve: bondingCurve, authority: alice.publicKey, systemProgram: SystemProgram.programId })
+      .remainingAccounts([{ pubkey: sellOrderPda, isWritable: true, isSigner: false }])
+      .signers([alice])
+      .rpc();
+
+    const stateAfter = await program.account.bondingCurve.fetch(bondingCurve);
+    expect(stateAfter.version).to.equal(state.version);
+    expect(stateAfter.x.toString()).to.equal(state.x.toString());
+    expect(stateAfter.sellQueueTail.toString()).to.equal(state.sellQueueTail.toString());
+  });
+
+  it("Test Scenario 7f: Sell Settlement Mode - direct sells burn EVER unless set to return it to reserve", async () => {
+    const SELL_SETTLEMENT_BURN = 0;
+    const SELL_SETTLEMENT_RESERVE = 1;
+
+    // New markets burn sold EVER by default
+    const state = await program.account.bondingCurve.fetch(bondingCurve);
+    expect(state.sellSettlement).to.equal(SELL_SETTLEMENT_BURN);
+
+    // Only the authority can change the mode
+    try {
+      await program.methods
+        .setSellSettlement(SELL_SETTLEMENT_RESERVE)
+        .accounts({ bondingCurve: bondingCurve, authority: bob.publicKey })
+        .signers([bob])
+        .rpc();
+      expect.fail("Should have failed for non-authority");
+    } catch (error) {
+      expect(error.message).to.include("Unauthorized");
+    }
+
+    // Unknown modes are rejected
+    try {
+      await program.methods
+        .setSellSettlement(2)
+        .accounts({ bondingCurve: bondingCurve, authority: alice.publicKey })
+        .signers([alice])
+        .rpc();
+      expect.fail("Should have rejected an unknown mode");
+    } catch (error) {
+      expect(error.message).to.include("InvalidAmount");
+    }
+
+    await program.methods
+      .setSellSettlement(SELL_SETTLEMENT_RESERVE)
+      .accounts({ bondingCurve: bondingCurve, authority: alice.publicKey })
+      .signers([alice])
+      .rpc();
+    expect((await program.account.bondingCurve.fetch(bondingCurve)).sellSettlement).to.equal(SELL_SETTLEMENT_RESERVE);
+
+    await program.methods
+      .setSellSettlement(SELL_SETTLEMENT_BURN)
+      .accounts({ bondingCurve: bondingCurve, authority: alice.publicKey })
+      .signers([alice])
+      .rpc();
+
+    // Changing the mode leaves the burned total alone
+    const stateAfter = await program.account.bondingCurve.fetch(bondingCurve);
+    expect(stateAfter.sellSettlement).to.equal(SELL_SETTLEMENT_BURN);
+    expect(stateAfter.totalBurned.toString()).to.equal(state.totalBurned.toString());
+  });
+
   it("Test Scenario 8: Small Buy Order - Bob buys 1,000 USDC", async () => {
     const buyAmount = 1_000 * 1_000_000; // 1K USDC
 
@@ -569,21 +1200,20 @@ describe("EverRiseDEX Comprehensive Tests", () => {
 
     // Create buy order PDA
     const [buyOrderPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("buy_order"), bondingCurveState.buyQueueTail.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("buy_order"), bondingCurve.toBuffer(), bondingCurveState.nextOrderId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
     // Execute buy
     await program.methods
-      .buy(new anchor.BN(buyAmount))
+      .queueBuy(new anchor.BN(buyAmount))
       .accounts({
         bondingCurve: bondingCurve,
         buyOrder: buyOrderPda,
+        tailBuyOrder: tailOrder("buy_order", bondingCurve, bondingCurveState.buyQueueTail),
         user: bob.publicKey,
         userUsdcAccount: bobUsdcAccount,
-        userEverAccount: bob.publicKey, // Will be created
         programUsdcAccount: programUsdcAccount,
-        everMint: everMint,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
@@ -592,7 +1222,7 @@ describe("EverRiseDEX Comprehensive Tests", () => {
 
     // Process buy queue (should buy from reserves since no sell queue)
     const [sellOrderPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("sell_order"), bondingCurveState.sellQueueHead.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("sell_order"), bondingCurve.toBuffer(), bondingCurveState.sellQueueHead.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
@@ -604,7 +1234,7 @@ describe("EverRiseDEX Comprehensive Tests", () => {
         sellOrder: sellOrderPda,
         programUsdcAccount: programUsdcAccount,
         programEverAccount: programEverAccount,
-        buyerEverAccount: bob.publicKey, // Will be created
+        buyerEverAccount: bobEverAccount,
         sellerUsdcAccount: bob.publicKey, // Not used
         treasuryUsdcAccount: treasuryUsdcAccount,
         everMint: everMint,
@@ -612,6 +1242,8 @@ describe("EverRiseDEX Comprehensive Tests", () => {
       })
       .rpc();
 
+    await expectInvariantsHold();
+
     // Verify bonding curve state changed
     const bondingCurveAfter = await program.account.bondingCurve.fetch(bondingCurve);
     expect(Number(bondingCurveAfter.x)).to.be.greaterThan(Number(bondingCurveState.x));
@@ -629,21 +1261,20 @@ describe("EverRiseDEX Comprehensive Tests", () => {
 
     // Create buy order PDA
     const [buyOrderPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("buy_order"), bondingCurveState.buyQueueTail.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("buy_order"), bondingCurve.toBuffer(), bondingCurveState.nextOrderId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
     // Execute buy
     await program.methods
-      .buy(new anchor.BN(buyAmount))
+      .queueBuy(new anchor.BN(buyAmount))
       .accounts({
         bondingCurve: bondingCurve,
         buyOrder: buyOrderPda,
+        tailBuyOrder: tailOrder("buy_order", bondingCurve, bondingCurveState.buyQueueTail),
         user: charlie.publicKey,
         userUsdcAccount: charlieUsdcAccount,
-        userEverAccount: charlie.publicKey, // Will be created
         programUsdcAccount: programUsdcAccount,
-        everMint: everMint,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
@@ -675,6 +1306,35 @@ describe("EverRiseDEX Comprehensive Tests", () => {
     }
   });
 
+  it("Test Scenario 9b: Emergency refund rejects a foreign destination account", async () => {
+    const bondingCurveState = await program.account.bondingCurve.fetch(bondingCurve);
+    const headBuyOrder = orderPda("buy_order", bondingCurve, bondingCurveState.buyQueueHead);
+    const buyOrder = await program.account.buyOrder.fetch(headBuyOrder);
+
+    // A quote mint account that does not belong to the queued buyer
+    const foreignUsdcAccount = buyOrder.buyer.equals(bob.publicKey) ? aliceUsdcAccount : bobUsdcAccount;
+    const foreignBalanceBefore = (await getAccount(provider.connection, foreignUsdcAccount)).amount;
+
+    try {
+      await program.methods
+        .emergencyRefund()
+        .accounts({
+          bondingCurve: bondingCurve,
+          buyOrder: headBuyOrder,
+          programUsdcAccount: programUsdcAccount,
+          buyerUsdcAccount: foreignUsdcAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      expect.fail("Emergency refund paid out to an account the buyer does not own");
+    } catch (error) {
+      expect(error.message).to.include("BuyerUsdcOwnerMismatch");
+    }
+
+    const foreignBalanceAfter = (await getAccount(provider.connection, foreignUsdcAccount)).amount;
+    expect(foreignBalanceAfter.toString()).to.equal(foreignBalanceBefore.toString());
+  });
+
   it("Test Scenario 10: Price Consistency Check", async () => {
     // Get bonding curve state
     const bondingCurveState = await program.account.bondingCurve.fetch(bondingCurve);
@@ -692,6 +1352,38 @@ describe("EverRiseDEX Comprehensive Tests", () => {
     expect(effectivePrice).to.be.greaterThan(expectedPrice);
   });
 
+  it("Test Scenario 10b: Rolling Volume - hourly buckets, lifetime totals and the trailing 24h buy volume", async () => {
+    const [volumeStatsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("volume_stats"), bondingCurve.toBuffer()],
+      program.programId
+    );
+    const volumeStats = await program.account.volumeStats.fetch(volumeStatsPda);
+    const bondingCurveState = await program.account.bondingCurve.fetch(bondingCurve);
+    expect(volumeStats.bondingCurve.toString()).to.equal(bondingCurve.toString());
+
+    // Everything so far happened within the last day, so the buckets still hold all of it
+    const sum = (side: "buy" | "sell" | "queue" | "reserve") =>
+      volumeStats.hourly.reduce((total, bucket) => total.add(bucket[side]), new anchor.BN(0));
+    for (const side of ["buy", "sell", "queue", "reserve"] as const) {
+      expect(sum(side).toString()).to.equal(volumeStats.lifetime[side].toString());
+    }
+    expect(bondingCurveState.totalVolume24h.toString()).to.equal(sum("buy").toString());
+    expect(volumeStats.lifetime.buy.toNumber()).to.be.greaterThan(0);
+    expect(volumeStats.lifetime.queue.toNumber()).to.be.greaterThan(0);
+
+    // The market already has its stats account
+    try {
+      await program.methods
+        .createVolumeStats()
+        .accounts({ bondingCurve: bondingCurve, payer: alice.publicKey })
+        .signers([alice])
+        .rpc();
+      expect.fail("Should have failed for an existing stats account");
+    } catch (error) {
+      expect(error.message).to.include("already in use");
+    }
+  });
+
   it("Test Scenario 11: Queue State Management", async () => {
     // Get bonding curve state
     const bondingCurveState = await program.account.bondingCurve.fetch(bondingCurve);
@@ -708,15 +1400,14 @@ describe("EverRiseDEX Comprehensive Tests", () => {
     // Test invalid amount (0)
     try {
       await program.methods
-        .buy(new anchor.BN(0))
+        .queueBuy(new anchor.BN(0))
         .accounts({
           bondingCurve: bondingCurve,
           buyOrder: PublicKey.default,
+          tailBuyOrder: null,
           user: alice.publicKey,
           userUsdcAccount: aliceUsdcAccount,
-          userEverAccount: alice.publicKey,
           programUsdcAccount: programUsdcAccount,
-          everMint: everMint,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
@@ -731,15 +1422,14 @@ describe("EverRiseDEX Comprehensive Tests", () => {
     // Test amount too large
     try {
       await program.methods
-        .buy(new anchor.BN(2_000_000 * 1_000_000)) // 2M USDC
+        .queueBuy(new anchor.BN(2_000_000 * 1_000_000)) // 2M USDC
         .accounts({
           bondingCurve: bondingCurve,
           buyOrder: PublicKey.default,
+          tailBuyOrder: null,
           user: alice.publicKey,
           userUsdcAccount: aliceUsdcAccount,
-          userEverAccount: alice.publicKey,
           programUsdcAccount: programUsdcAccount,
-          everMint: everMint,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
@@ -752,6 +1442,139 @@ describe("EverRiseDEX Comprehensive Tests", () => {
     }
   });
 
+  it("Test Scenario 12b: Sell Queue Account Validation", async () => {
+    const dianaUsdcAccount = await createAccount(provider.connection, diana, usdcMint, diana.publicKey);
+
+    // Diana queues a sell order
+    let bondingCurveState = await program.account.bondingCurve.fetch(bondingCurve);
+    const [sellOrderPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("sell_order"), bondingCurve.toBuffer(), bondingCurveState.nextOrderId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    await program.methods
+      .sell(new anchor.BN(1_000 * 1_000_000_000), new anchor.BN(0), null)
+      .accounts({
+        bondingCurve: bondingCurve,
+        sellOrder: sellOrderPda,
+        tailSellOrder: tailOrder("sell_order", bondingCurve, bondingCurveState.sellQueueTail),
+        user: diana.publicKey,
+        userEverAccount: dianaEverAccount,
+        programEverAccount: programEverAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([diana])
+      .rpc();
+
+    // Bob queues a buy order
+    bondingCurveState = await program.account.bondingCurve.fetch(bondingCurve);
+    const [buyOrderPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("buy_order"), bondingCurve.toBuffer(), bondingCurveState.nextOrderId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    await program.methods
+      .queueBuy(new anchor.BN(100 * 1_000_000))
+      .accounts({
+        bondingCurve: bondingCurve,
+        buyOrder: buyOrderPda,
+        tailBuyOrder: tailOrder("buy_order", bondingCurve, bondingCurveState.buyQueueTail),
+        user: bob.publicKey,
+        userUsdcAccount: bobUsdcAccount,
+        programUsdcAccount: programUsdcAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([bob])
+      .rpc();
+
+    const processWith = (sellOrder: PublicKey, sellerUsdcAccount: PublicKey) =>
+      program.methods
+        .processBuyQueue()
+        .accounts({
+          bondingCurve: bondingCurve,
+          buyOrder: buyOrderPda,
+          sellOrder,
+          programUsdcAccount: programUsdcAccount,
+          programEverAccount: programEverAccount,
+          buyerEverAccount: bobEverAccount,
+          sellerUsdcAccount,
+          treasuryUsdcAccount: treasuryUsdcAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+    // Sell order that is not the PDA at the queue head
+    try {
+      await processWith(Keypair.generate().publicKey, dianaUsdcAccount);
+      expect.fail("Should have failed with invalid sell order");
+    } catch (error) {
+      expect(error.message).to.include("InvalidSellOrder");
+    }
+
+    // Seller USDC account that is not a token account
+    try {
+      await processWith(sellOrderPda, diana.publicKey);
+      expect.fail("Should have failed with invalid seller USDC account");
+    } catch (error) {
+      expect(error.message).to.include("InvalidSellerUsdcAccount");
+    }
+
+    // Seller USDC account owned by someone other than the seller
+    try {
+      await processWith(sellOrderPda, bobUsdcAccount);
+      expect.fail("Should have failed with seller USDC owner mismatch");
+    } catch (error) {
+      expect(error.message).to.include("SellerUsdcOwnerMismatch");
+    }
+
+    // Seller token account with the wrong mint
+    try {
+      await processWith(sellOrderPda, dianaEverAccount);
+      expect.fail("Should have failed with invalid seller USDC mint");
+    } catch (error) {
+      expect(error.message).to.include("InvalidSellerUsdcMint");
+    }
+  });
+
+  it("Test Scenario 12c: Slippage and Deadline Protection", async () => {
+    const bondingCurveState = await program.account.bondingCurve.fetch(bondingCurve);
+    const [sellOrderPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("sell_order"), bondingCurve.toBuffer(), bondingCurveState.nextOrderId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const sellWith = (minLockedPrice: anchor.BN, deadline: anchor.BN | null) =>
+      program.methods
+        .sell(new anchor.BN(1_000 * 1_000_000_000), minLockedPrice, deadline)
+        .accounts({
+          bondingCurve: bondingCurve,
+          sellOrder: sellOrderPda,
+          tailSellOrder: tailOrder("sell_order", bondingCurve, bondingCurveState.sellQueueTail),
+          user: diana.publicKey,
+          userEverAccount: dianaEverAccount,
+          programEverAccount: programEverAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([diana])
+        .rpc();
+
+    // Minimum locked price above the current price
+    try {
+      await sellWith(new anchor.BN("18446744073709551615"), null);
+      expect.fail("Should have failed with slippage exceeded");
+    } catch (error) {
+      expect(error.message).to.include("SlippageExceeded");
+    }
+
+    // Deadline in the past
+    try {
+      await sellWith(new anchor.BN(0), new anchor.BN(1));
+      expect.fail("Should have failed with deadline expired");
+    } catch (error) {
+      expect(error.message).to.include("DeadlineExpired");
+    }
+  });
+
   it("Test Scenario 13: Final State Validation", async () => {
     // Get final bonding curve state
     const bondingCurveState = await program.account.bondingCurve.fetch(bondingCurve);
@@ -776,4 +1599,88 @@ describe("EverRiseDEX Comprehensive Tests", () => {
     console.log(`Buy queue: ${bondingCurveState.buyQueueHead} -> ${bondingCurveState.buyQueueTail}`);
     console.log(`Sell queue: ${bondingCurveState.sellQueueHead} -> ${bondingCurveState.sellQueueTail}`);
   });
+
+  it("Test Scenario 13b: Invariant Check - verify_invariants reconciles the curve with its balances", async () => {
+    const bondingCurveState = await program.account.bondingCurve.fetch(bondingCurve);
+
+    // Collect the open sell orders head first
+    const openSellOrders: PublicKey[] = [];
+    let escrowed = new anchor.BN(0);
+    for (let orderId = bondingCurveState.sellQueueHead; !orderId.isZero(); ) {
+      const sellOrderPda = orderPda("sell_order", bondingCurve, orderId);
+      const sellOrder = await program.account.sellOrder.fetch(sellOrderPda);
+      openSellOrders.push(sellOrderPda);
+      escrowed = escrowed.add(sellOrder.remainingAmount);
+      orderId = sellOrder.next;
+    }
+
+    const verify = (sellOrders: PublicKey[]) =>
+      program.methods
+        .verifyInvariants()
+        .accounts({
+          bondingCurve: bondingCurve,
+          treasuryUsdcAccount: treasuryUsdcAccount,
+          programEverAccount: programEverAccount,
+          everMint: everMint,
+        })
+        .remainingAccounts(sellOrders.map((pubkey) => ({ pubkey, isWritable: false, isSigner: false })))
+        .view();
+
+    const report = await verify(openSellOrders);
+    expect(report.k.toString()).to.equal(report.x.mul(report.y).toString());
+    expect(report.escrowedSellEver.toString()).to.equal(escrowed.toString());
+    expect(report.openSellOrders).to.equal(openSellOrders.length);
+    expect(report.reserveEver.sub(report.y).toString()).to.equal(escrowed.toString());
+    expect(report.trackedEscrowedSellEver.toString()).to.equal(escrowed.toString());
+    expect(report.violations).to.equal(0);
+
+    // Leaving out an open order is rejected rather than under-counting the escrow
+    if (openSellOrders.length > 0) {
+      try {
+        await verify(openSellOrders.slice(0, -1));
+        expect.fail("Should have failed with an incomplete sell queue");
+      } catch (error) {
+        expect(error.message).to.include("QueueLinkMissing");
+      }
+    }
+  });
+
+  it("Test Scenario 14: Close Processed Orders - rent returns to the original traders", async () => {
+    // Alice's buy order (Scenario 1, order id 1) and Diana's sell order (Scenario 2, order id 2, filled in Scenario 4)
+    const aliceBuyOrder = orderPda("buy_order", bondingCurve, 1);
+    const dianaSellOrder = orderPda("sell_order", bondingCurve, 2);
+    expect((await program.account.buyOrder.fetch(aliceBuyOrder)).processed).to.be.true;
+    expect((await program.account.sellOrder.fetch(dianaSellOrder)).processed).to.be.true;
+
+    // Rent can only go to the order's own trader
+    try {
+      await program.methods
+        .closeOrder()
+        .accounts({ order: aliceBuyOrder, owner: bob.publicKey })
+        .rpc();
+      expect.fail("Should have failed for the wrong rent recipient");
+    } catch (error) {
+      expect(error.message).to.include("OrderOwnerMismatch");
+    }
+
+    const aliceBalanceBefore = await provider.connection.getBalance(alice.publicKey);
+    const dianaBalanceBefore = await provider.connection.getBalance(diana.publicKey);
+    const buyOrderRent = await provider.connection.getBalance(aliceBuyOrder);
+    const sellOrderRent = await provider.connection.getBalance(dianaSellOrder);
+
+    // Anyone can crank; extra orders are batched as (order, owner) pairs
+    await program.methods
+      .closeOrder()
+      .accounts({ order: aliceBuyOrder, owner: alice.publicKey })
+      .remainingAccounts([
+        { pubkey: dianaSellOrder, isWritable: true, isSigner: false },
+        { pubkey: diana.publicKey, isWritable: true, isSigner: false },
+      ])
+      .rpc();
+
+    expect(await provider.connection.getAccountInfo(aliceBuyOrder)).to.be.null;
+    expect(await provider.connection.getAccountInfo(dianaSellOrder)).to.be.null;
+    expect(await provider.connection.getBalance(alice.publicKey)).to.equal(aliceBalanceBefore + buyOrderRent);
+    expect(await provider.connection.getBalance(diana.publicKey)).to.equal(dianaBalanceBefore + sellOrderRent);
+  });
 });
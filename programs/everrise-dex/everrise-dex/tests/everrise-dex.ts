@@ -15,17 +15,34 @@ describe("everrise-dex", () => {
   const authority = provider.wallet;
   const treasuryWallet = new PublicKey("FEVyge83aMu6gP2uSXUFFH7ujVs2SQqfA425S7mJJGqA");
   
+  // Curve parameters (mainnet values)
+  const curveParams = {
+    quoteMint: new PublicKey("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"), // USDC
+    baseMint: new PublicKey("3q4YFYMKHrdYw5FPANQ7nrCQMT4t12XKgzYX8JaTeEx8"), // EVER
+    initialX: new anchor.BN("10000000000"), // 10K USDC
+    initialY: new anchor.BN("100000000000000000"), // 100M EVER
+    dailyGrowthBps: 2, // 0.02%
+    maxQuoteAmount: new anchor.BN("10000000000000"), // 10M USDC
+    maxBaseAmount: new anchor.BN("10000000000000000"), // 10M EVER
+    commissionBps: 500, // 5%
+  };
+
+  // Referral commissions go through the affiliate program
+  const affiliateProgramId = new PublicKey("2vxcei86d8uh3NWcZKqJui9uFwMEe71KZbRmbFFv5ayP");
+
   // PDA for bonding curve
   const [bondingCurvePDA] = PublicKey.findProgramAddressSync(
-    [Buffer.from("bonding_curve")],
+    [Buffer.from("bonding_curve"), curveParams.baseMint.toBuffer()],
     program.programId
   );
 
   it("Initialize EverRise DEX", async () => {
     const tx = await program.methods
-      .initialize(treasuryWallet)
+      .createMarket(treasuryWallet, curveParams, affiliateProgramId)
       .accounts({
         bondingCurve: bondingCurvePDA,
+        baseMint: curveParams.baseMint,
+        quoteMint: curveParams.quoteMint,
         authority: authority.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
@@ -38,6 +55,7 @@ describe("everrise-dex", () => {
     
     expect(bondingCurve.authority.toString()).to.equal(authority.publicKey.toString());
     expect(bondingCurve.treasuryWallet.toString()).to.equal(treasuryWallet.toString());
+    expect(bondingCurve.affiliateProgram.toString()).to.equal(affiliateProgramId.toString());
     expect(bondingCurve.x.toString()).to.equal("10000000000"); // 10K USDC
     expect(bondingCurve.y.toString()).to.equal("100000000000000000"); // 100M EVER
     expect(bondingCurve.k.toString()).to.equal("1000000000000000000000000000"); // K = X * Y
@@ -45,6 +63,14 @@ describe("everrise-dex", () => {
     expect(bondingCurve.sellQueueTail.toNumber()).to.equal(0);
     expect(bondingCurve.buyQueueHead.toNumber()).to.equal(0);
     expect(bondingCurve.buyQueueTail.toNumber()).to.equal(0);
+    expect(bondingCurve.nextOrderId.toNumber()).to.equal(1);
+    expect(bondingCurve.sellSettlement).to.equal(0); // SELL_SETTLEMENT_BURN
+    expect(bondingCurve.totalBurned.toNumber()).to.equal(0);
+    expect(bondingCurve.currentPrice.toNumber()).to.equal(100); // X / Y in 9 decimals
+    expect(bondingCurve.config.quoteMint.toString()).to.equal(curveParams.quoteMint.toString());
+    expect(bondingCurve.config.baseMint.toString()).to.equal(curveParams.baseMint.toString());
+    expect(bondingCurve.config.dailyGrowthBps).to.equal(2);
+    expect(bondingCurve.config.commissionBps).to.equal(500);
   });
 
   it("Calculate initial price", async () => {
@@ -12,10 +12,21 @@ async function initializeDevNet() {
   
   // Treasury wallet from memory
   const treasuryWallet = new PublicKey("FEVyge83aMu6gP2uSXUFFH7ujVs2SQqfA425S7mJJGqA");
+  // Curve parameters - set USDC_MINT / EVER_MINT to the devnet test mints
+  const curveParams = {
+    quoteMint: new PublicKey(process.env.USDC_MINT || "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
+    baseMint: new PublicKey(process.env.EVER_MINT || "3q4YFYMKHrdYw5FPANQ7nrCQMT4t12XKgzYX8JaTeEx8"),
+    initialX: new anchor.BN("10000000000"), // 10K USDC
+    initialY: new anchor.BN("100000000000000000"), // 100M EVER
+    dailyGrowthBps: 2, // 0.02%
+    maxQuoteAmount: new anchor.BN("10000000000000"), // 10M USDC
+    maxBaseAmount: new anchor.BN("10000000000000000"), // 10M EVER
+    commissionBps: 500, // 5%
+  };
   
   // PDA for bonding curve
   const [bondingCurvePDA] = PublicKey.findProgramAddressSync(
-    [Buffer.from("bonding_curve")],
+    [Buffer.from("bonding_curve"), curveParams.baseMint.toBuffer()],
     program.programId
   );
 
@@ -27,9 +38,11 @@ async function initializeDevNet() {
   try {
     // Initialize the bonding curve
     const tx = await program.methods
-      .initialize(treasuryWallet)
+      .createMarket(treasuryWallet, curveParams)
       .accounts({
         bondingCurve: bondingCurvePDA,
+        baseMint: curveParams.baseMint,
+        quoteMint: curveParams.quoteMint,
         authority: provider.wallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })